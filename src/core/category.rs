@@ -5,13 +5,20 @@ use std::fmt;
 
 // A searchable category in the market. May or may not
 // correspond 1:1 to an Archetype.
-#[derive(Copy, Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub enum Category {
     Profile = 0,
     Gotchi = 1,
     Misc = 2,
     Land = 3,
+    BuyOrder = 4,
+    Auction = 5,
+    TradeOffer = 6,
+    SaleRecord = 7,
+    MarketFees = 8,
     Sale = 9,
+    ShopStock = 10,
+    Watchers = 11,
 }
 
 impl fmt::Display for Category {
@@ -79,7 +86,14 @@ impl std::convert::TryFrom<u8> for Category {
             1 => Gotchi,
             2 => Misc,
             3 => Land,
+            4 => BuyOrder,
+            5 => Auction,
+            6 => TradeOffer,
+            7 => SaleRecord,
+            8 => MarketFees,
             9 => Sale,
+            10 => ShopStock,
+            11 => Watchers,
             _ => return Err(CategoryError::UnknownCategory),
         })
     }
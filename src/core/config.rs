@@ -21,6 +21,9 @@ pub struct Config {
     pub profile_archetype: ProfileArchetype,
     pub plant_archetypes: Vec<PlantArchetype>,
     pub possession_archetypes: Vec<Archetype>,
+    pub market: MarketConfig,
+    pub shop: ShopConfig,
+    pub crafting: CraftingConfig,
 }
 impl Config {
     #[allow(dead_code)]
@@ -55,6 +58,125 @@ impl Config {
             .position(|x| name.as_ref() == x.name)
             .ok_or(ConfigError::UnknownArchetypeName(name.as_ref().to_string()))
     }
+
+    /// Finds the hybrid seeds which can be made by crossbreeding
+    /// plants of the two named plant archetypes, in either order.
+    pub fn find_hybrids<'a>(
+        &'a self,
+        a: &'a str,
+        b: &'a str,
+    ) -> impl Iterator<Item = ArchetypeHandle> + 'a {
+        self.possession_archetypes
+            .iter()
+            .enumerate()
+            .filter(move |(_, x)| match &x.kind {
+                ArchetypeKind::Seed(sa) => sa.is_hybrid_of(a, b),
+                _ => false,
+            })
+            .map(|(ah, _)| ah)
+    }
+
+    /// Every plant archetype with an advancement that yields this possession,
+    /// along with the level of that advancement.
+    pub fn yielders_of(&self, ah: ArchetypeHandle) -> Vec<(ArchetypeHandle, usize)> {
+        self.plant_archetypes
+            .iter()
+            .enumerate()
+            .flat_map(|(plant_ah, arch)| {
+                arch.advancements
+                    .all()
+                    .enumerate()
+                    .filter(move |(_, adv)| match &adv.kind {
+                        PlantAdvancementKind::Yield(resources) => resources
+                            .iter()
+                            .any(|(_, name)| self.find_possession_handle(name).ok() == Some(ah)),
+                        _ => false,
+                    })
+                    .map(move |(level, _)| (plant_ah, level))
+            })
+            .collect()
+    }
+
+    /// Every recipe from every plant and workbench advancement, along with where it's crafted.
+    pub fn recipes(&self) -> Vec<(RecipeSource, Recipe<ArchetypeHandle>)> {
+        let plant_recipes = self
+            .plant_archetypes
+            .iter()
+            .enumerate()
+            .flat_map(|(plant_ah, arch)| {
+                arch.advancements
+                    .all()
+                    .enumerate()
+                    .flat_map(move |(level, adv)| match &adv.kind {
+                        PlantAdvancementKind::Craft(recipes) => recipes
+                            .iter()
+                            .map(|r| (RecipeSource::Plant(plant_ah, level), r))
+                            .collect(),
+                        _ => vec![],
+                    })
+            });
+        let workbench_recipes = self
+            .profile_archetype
+            .advancements
+            .all()
+            .enumerate()
+            .flat_map(|(level, adv)| match &adv.kind {
+                HacksteadAdvancementKind::Workbench(recipes) => recipes
+                    .iter()
+                    .map(|r| (RecipeSource::Workbench(level), r))
+                    .collect(),
+                _ => vec![],
+            });
+
+        plant_recipes
+            .chain(workbench_recipes)
+            .filter_map(|(source, r)| Some((source, r.find_handles().ok()?)))
+            .collect()
+    }
+
+    /// The recipes which can produce this possession.
+    pub fn recipes_making(&self, ah: ArchetypeHandle) -> Vec<(RecipeSource, Recipe<ArchetypeHandle>)> {
+        self.recipes()
+            .into_iter()
+            .filter(|(_, r)| r.makes.outputs().contains(&ah))
+            .collect()
+    }
+
+    /// The recipes which use up this possession, or need it as a catalyst.
+    pub fn recipes_using(&self, ah: ArchetypeHandle) -> Vec<(RecipeSource, Recipe<ArchetypeHandle>)> {
+        self.recipes()
+            .into_iter()
+            .filter(|(_, r)| r.needs.iter().any(|&(_, x)| x == ah) || r.catalysts.contains(&ah))
+            .collect()
+    }
+}
+
+/// Where a recipe can be crafted, and the level at which it becomes available there.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RecipeSource {
+    /// On a plant of this archetype
+    Plant(ArchetypeHandle, usize),
+    /// On the Hackstead's workbench
+    Workbench(usize),
+}
+impl fmt::Display for RecipeSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use crate::frontend::emojify;
+
+        match self {
+            RecipeSource::Plant(ah, level) => {
+                let name = CONFIG
+                    .plant_archetypes
+                    .get(*ah)
+                    .map(|p| p.name.as_str())
+                    .unwrap_or("unknown");
+                write!(f, "{} *{}* at level *{}*", emojify(name), name, level)
+            }
+            RecipeSource::Workbench(level) => {
+                write!(f, ":hammer_and_wrench: *Workbench* at Hackstead level *{}*", level)
+            }
+        }
+    }
 }
 
 // I should _really_ use a different version of this for PlantArchetypes and PossessionArchetypes ...
@@ -83,10 +205,145 @@ lazy_static::lazy_static! {
             },
             plant_archetypes: f("plant_archetypes"),
             possession_archetypes: f("possession_archetypes"),
+            market: f("market"),
+            shop: f("shop"),
+            crafting: f("crafting"),
         }
     };
 }
 
+#[derive(Deserialize, Debug, Clone)]
+pub struct MarketConfig {
+    /// The percentage of a seed's sale price which is paid out
+    /// to the growers in that seed's pedigree.
+    pub seed_royalty_percent: u64,
+    /// How many hours a listing stays up on the market before it
+    /// expires and is given back to its seller.
+    pub listing_hours: u64,
+    /// What's charged to sell something, which goes towards funding Harvests.
+    pub fees: FeeSchedule,
+}
+impl MarketConfig {
+    pub fn seed_royalty(&self, price: u64) -> u64 {
+        price * self.seed_royalty_percent / 100
+    }
+
+    pub fn listing_lifetime(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.listing_hours * 60 * 60)
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct FeeSchedule {
+    /// The percentage of a sale's price that's charged, rounded down to the nearest GP.
+    pub percent: u64,
+    /// Overrides `percent` for sales of things in these categories.
+    #[serde(default)]
+    pub category_percent: std::collections::HashMap<crate::Category, u64>,
+    /// The least that's charged for any sale that isn't fee-free.
+    pub minimum: u64,
+    /// Sales for less than this many GP aren't charged at all.
+    pub free_below: u64,
+}
+impl FeeSchedule {
+    pub fn percent(&self, category: crate::Category) -> u64 {
+        self.category_percent
+            .get(&category)
+            .copied()
+            .unwrap_or(self.percent)
+    }
+
+    /// What's charged to sell something of the given category for `price`.
+    pub fn fee(&self, category: crate::Category, price: u64) -> u64 {
+        if price < self.free_below {
+            return 0;
+        }
+        (price * self.percent(category) / 100)
+            .max(self.minimum)
+            .min(price)
+    }
+
+    /// Spells out how the fee for something of the given category is worked out.
+    pub fn describe(&self, category: crate::Category) -> String {
+        let mut description = format!(
+            "{}% of the price, rounded down to the nearest GP",
+            self.percent(category)
+        );
+        if self.minimum > 0 {
+            description.push_str(&format!(", but at least {}gp", self.minimum));
+        }
+        if self.free_below > 0 {
+            description.push_str(&format!(
+                " (sales below {}gp aren't charged at all)",
+                self.free_below
+            ));
+        }
+        description
+    }
+}
+
+/// A shop run by the game itself, so that there's always somewhere to buy the basics
+/// even when nobody's selling them on the market.
+#[derive(Deserialize, Debug, Clone)]
+pub struct ShopConfig {
+    /// Who runs the shop.
+    pub keeper: String,
+    /// How much an item's price goes up for each of it that's already been sold that day,
+    /// as a percentage of its base price. Prices stay fixed if this is 0.
+    pub markup_percent: u64,
+    pub stock: Vec<ShopItem>,
+}
+impl ShopConfig {
+    pub fn item(&self, ah: ArchetypeHandle) -> Option<&ShopItem> {
+        self.stock
+            .iter()
+            .find(|i| i.archetype_handle().ok() == Some(ah))
+    }
+
+    /// What the next one of `item` costs, once `sold` of it have been sold today.
+    pub fn price(&self, item: &ShopItem, sold: u64) -> u64 {
+        item.price + item.price * self.markup_percent * sold / 100
+    }
+
+    /// What buying `quantity` more of `item` costs altogether,
+    /// once `sold` of it have been sold today.
+    pub fn total(&self, item: &ShopItem, sold: u64, quantity: u64) -> u64 {
+        (sold..sold + quantity).map(|s| self.price(item, s)).sum()
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct ShopItem {
+    /// The name of the possession archetype being sold.
+    pub name: String,
+    /// What the first one sold each day goes for.
+    pub price: u64,
+    /// How many can be sold each day before the shop runs out.
+    pub daily_stock: u64,
+}
+impl ShopItem {
+    pub fn archetype_handle(&self) -> Result<ArchetypeHandle, ConfigError> {
+        CONFIG.find_possession_handle(&self.name)
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct CraftingConfig {
+    /// The fraction of the resources a craft consumed that
+    /// are given back if that craft is cancelled.
+    pub cancel_refund_fraction: f32,
+}
+impl CraftingConfig {
+    /// What you get back for cancelling a craft that consumed `needs`, rounded down.
+    pub fn cancel_refund(&self, needs: &[(usize, ArchetypeHandle)]) -> Vec<(usize, ArchetypeHandle)> {
+        needs
+            .iter()
+            .map(|&(count, ah)| ((count as f32 * self.cancel_refund_fraction) as usize, ah))
+            .filter(|&(count, _)| count > 0)
+            .collect()
+    }
+}
+
 #[derive(Deserialize, Debug, Clone)]
 pub struct ProfileArchetype {
     pub advancements: AdvancementSet<HacksteadAdvancementSum>,
@@ -97,24 +354,39 @@ pub type HacksteadAdvancementSet = AdvancementSet<HacksteadAdvancementSum>;
 #[derive(Deserialize, Debug, Clone, PartialEq)]
 pub enum HacksteadAdvancementKind {
     Land { pieces: u32 },
+    /// Recipes that can be crafted on the Hackstead's workbench, without a plant.
+    Workbench(Vec<Recipe<String>>),
 }
 #[derive(Deserialize, Debug, Clone, PartialEq)]
 pub struct HacksteadAdvancementSum {
     pub land: u32,
     pub xp: u64,
+    pub recipes: Vec<Recipe<ArchetypeHandle>>,
 }
 impl AdvancementSum for HacksteadAdvancementSum {
     type Kind = HacksteadAdvancementKind;
 
     fn new(unlocked: &[&Advancement<Self>]) -> Self {
+        use HacksteadAdvancementKind::*;
+
         Self {
             xp: unlocked.iter().fold(0, |a, c| a + c.xp),
             land: unlocked
                 .iter()
                 .map(|k| match k.kind {
-                    HacksteadAdvancementKind::Land { pieces } => pieces,
+                    Land { pieces } => pieces,
+                    _ => 0,
                 })
                 .sum(),
+            recipes: unlocked
+                .iter()
+                .flat_map(|k| match &k.kind {
+                    Workbench(recipes) => recipes.iter().collect(),
+                    _ => vec![],
+                })
+                .map(|r| r.find_handles())
+                .collect::<Result<Vec<_>, ConfigError>>()
+                .expect("couldn't find archetype for workbench advancement"),
         }
     }
 
@@ -132,13 +404,84 @@ pub struct GotchiArchetype {
 #[derive(Deserialize, Debug, Clone)]
 pub struct SeedArchetype {
     pub grows_into: String,
+    /// The names of the two plant archetypes which can be crossbred
+    /// to produce this seed, if it's a hybrid.
+    #[serde(default)]
+    pub hybrid_of: Option<(String, String)>,
+}
+impl SeedArchetype {
+    pub fn is_hybrid_of(&self, a: &str, b: &str) -> bool {
+        match &self.hybrid_of {
+            Some((l, r)) => (l == a && r == b) || (l == b && r == a),
+            None => false,
+        }
+    }
 }
 #[derive(Deserialize, Debug, Clone)]
 pub enum ApplicationEffect {
+    /// Squeezes `extra_cycles` worth of growth into the next `duration_cycles`.
     TimeIncrease {
         extra_cycles: u64,
         duration_cycles: u64,
     },
+    /// Multiplies the size of everything the plant yields for `duration_cycles`.
+    YieldSizeBoost {
+        multiplier: f32,
+        duration_cycles: u64,
+    },
+    /// Awards `extra_xp` more xp each cycle for `duration_cycles`.
+    XpBoost {
+        extra_xp: u64,
+        duration_cycles: u64,
+    },
+    /// Completes whatever the plant is crafting, or its next craft if it's idle.
+    FinishCraft,
+    /// Makes sure the plant's next yield comes with one of its seeds.
+    GuaranteedSeed,
+}
+impl ApplicationEffect {
+    /// How much of this effect a fresh application leaves on a plant;
+    /// see `Effect::remaining` for what that means for each kind of effect.
+    pub fn amount(&self) -> u64 {
+        use ApplicationEffect::*;
+        match *self {
+            TimeIncrease { extra_cycles, .. } => extra_cycles,
+            YieldSizeBoost {
+                duration_cycles, ..
+            }
+            | XpBoost {
+                duration_cycles, ..
+            } => duration_cycles,
+            FinishCraft | GuaranteedSeed => 1,
+        }
+    }
+
+    pub fn same_kind(&self, other: &Self) -> bool {
+        std::mem::discriminant(self) == std::mem::discriminant(other)
+    }
+
+    /// How this effect stacks unless the item applying it says otherwise.
+    pub fn default_stacking(&self) -> EffectStacking {
+        use ApplicationEffect::*;
+        match self {
+            TimeIncrease { .. } | YieldSizeBoost { .. } => EffectStacking::Extend,
+            XpBoost { .. } | FinishCraft => EffectStacking::Stack,
+            GuaranteedSeed => EffectStacking::Reject,
+        }
+    }
+}
+/// What happens when an item is applied to a plant
+/// that's already under an effect of the same kind.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum EffectStacking {
+    /// The two effects work side by side.
+    Stack,
+    /// The existing effect lasts longer by however much the new item would have lasted.
+    Extend,
+    /// The new effect starts over, throwing away whatever was left of the old one.
+    Replace,
+    /// The item can't be applied until the existing effect wears off.
+    Reject,
 }
 #[derive(Deserialize, Debug, Clone)]
 pub struct LandUnlock {
@@ -147,8 +490,23 @@ pub struct LandUnlock {
 #[derive(Deserialize, Debug, Clone)]
 pub struct KeepsakeArchetype {
     pub item_application_effect: Option<ApplicationEffect>,
+    /// Overrides how this item's application effect stacks with one already on a plant.
+    #[serde(default)]
+    pub item_application_stacking: Option<EffectStacking>,
     pub unlocks_land: Option<LandUnlock>,
     pub plant_effects: Option<(String, PlantAdvancement)>,
+    /// Studying this reveals the hidden recipes for the named archetype.
+    #[serde(default)]
+    pub blueprint: Option<String>,
+}
+impl KeepsakeArchetype {
+    pub fn application_stacking(&self) -> Option<EffectStacking> {
+        let effect = self.item_application_effect.as_ref()?;
+        Some(
+            self.item_application_stacking
+                .unwrap_or_else(|| effect.default_stacking()),
+        )
+    }
 }
 
 #[derive(Deserialize, Debug, Clone)]
@@ -206,6 +564,17 @@ pub enum RecipeMakes<Handle: Clone> {
     AllOf(Vec<(usize, Handle)>),
 }
 impl<Handle: Clone> RecipeMakes<Handle> {
+    /// Everything this could possibly produce.
+    pub fn outputs(&self) -> Vec<Handle> {
+        use RecipeMakes::*;
+
+        match self {
+            Just(_, h) => vec![h.clone()],
+            OneOf(these) => these.iter().map(|(_, h)| h.clone()).collect(),
+            AllOf(these) => these.iter().map(|(_, h)| h.clone()).collect(),
+        }
+    }
+
     /// Returns one possible output, randomly (but properly weighted)
     /// if more than one is possible.
     pub fn any(&self) -> Handle {
@@ -335,18 +704,84 @@ impl RecipeMakes<String> {
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct Recipe<Handle: Clone> {
     pub needs: Vec<(usize, Handle)>,
+    /// Possessions which must be on hand to craft this, but aren't used up
+    #[serde(default)]
+    pub catalysts: Vec<Handle>,
+    /// The level the plant crafting this must have reached.
+    /// For workbench recipes, this is the level of the Hackstead instead.
+    #[serde(default)]
+    pub min_level: usize,
+    /// Hidden recipes aren't shown to a Hacksteader until they've discovered them,
+    /// either by crafting what they make some other way or by studying a blueprint.
+    #[serde(default)]
+    pub hidden: bool,
     pub makes: RecipeMakes<Handle>,
     #[serde(default)]
     pub destroys_plant: bool,
     pub time: f32,
 }
-impl Recipe<ArchetypeHandle> {
-    pub fn satisfies(&self, inv: &[crate::Possession]) -> bool {
-        self.needs.iter().copied().all(|(count, ah)| {
-            let has = inv.iter().filter(|x| x.archetype_handle == ah).count();
-            count <= has
+impl Recipe<String> {
+    pub fn find_handles(&self) -> Result<Recipe<ArchetypeHandle>, ConfigError> {
+        Ok(Recipe {
+            makes: self.makes.clone().find_handles()?,
+            needs: self
+                .needs
+                .iter()
+                .map(|(c, s)| Ok((*c, CONFIG.find_possession_handle(s)?)))
+                .collect::<Result<Vec<_>, ConfigError>>()?,
+            catalysts: self
+                .catalysts
+                .iter()
+                .map(|s| CONFIG.find_possession_handle(s))
+                .collect::<Result<Vec<_>, ConfigError>>()?,
+            min_level: self.min_level,
+            hidden: self.hidden,
+            time: self.time,
+            destroys_plant: self.destroys_plant,
         })
     }
+}
+impl Recipe<ArchetypeHandle> {
+    /// Whether someone who's discovered the hidden recipes for these
+    /// archetypes should be able to see this recipe.
+    pub fn discovered_by(&self, discovered: &[ArchetypeHandle]) -> bool {
+        !self.hidden || self.makes.outputs().iter().any(|o| discovered.contains(o))
+    }
+    pub fn satisfies(&self, inv: &[crate::Possession], level: usize) -> bool {
+        self.missing(inv, level).is_empty()
+    }
+    /// Everything keeping someone with this inventory, crafting
+    /// with something at this level, from crafting this recipe.
+    pub fn missing(&self, inv: &[crate::Possession], level: usize) -> Vec<MissingRequirement> {
+        let has = |ah: ArchetypeHandle| inv.iter().filter(|x| x.archetype_handle == ah).count();
+        let consumed = |ah: ArchetypeHandle| -> usize {
+            self.needs
+                .iter()
+                .filter(|&&(_, x)| x == ah)
+                .map(|&(count, _)| count)
+                .sum()
+        };
+
+        let mut missing: Vec<MissingRequirement> = self
+            .needs
+            .iter()
+            .copied()
+            .filter(|&(count, ah)| has(ah) < count)
+            .map(|(count, ah)| MissingRequirement::Resource(count - has(ah), ah))
+            .collect();
+        missing.extend(
+            self.catalysts
+                .iter()
+                .copied()
+                // a catalyst can't also be one of the things that gets used up
+                .filter(|&ah| has(ah) <= consumed(ah))
+                .map(MissingRequirement::Catalyst),
+        );
+        if level < self.min_level {
+            missing.push(MissingRequirement::Level(self.min_level));
+        }
+        missing
+    }
     pub fn lookup_handles(self) -> Option<Recipe<&'static Archetype>> {
         Some(Recipe {
             makes: self.makes.lookup_handles()?,
@@ -355,12 +790,49 @@ impl Recipe<ArchetypeHandle> {
                 .into_iter()
                 .map(|(n, x)| Some((n, CONFIG.possession_archetypes.get(x)?)))
                 .collect::<Option<Vec<(_, &Archetype)>>>()?,
+            catalysts: self
+                .catalysts
+                .into_iter()
+                .map(|x| CONFIG.possession_archetypes.get(x))
+                .collect::<Option<Vec<&Archetype>>>()?,
+            min_level: self.min_level,
+            hidden: self.hidden,
             time: self.time,
             destroys_plant: self.destroys_plant,
         })
     }
 }
 
+/// Something a Recipe requires that a would-be crafter doesn't have.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum MissingRequirement {
+    /// This many more of something that gets used up
+    Resource(usize, ArchetypeHandle),
+    /// Something that has to be on hand, but isn't used up
+    Catalyst(ArchetypeHandle),
+    /// The level that must be reached
+    Level(usize),
+}
+impl fmt::Display for MissingRequirement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use crate::frontend::emojify;
+        use MissingRequirement::*;
+
+        let name = |ah: &ArchetypeHandle| {
+            CONFIG
+                .possession_archetypes
+                .get(*ah)
+                .map(|a| a.name.as_str())
+                .unwrap_or("unknown")
+        };
+        match self {
+            Resource(n, ah) => write!(f, "*{}* more {} _{}_", n, emojify(name(ah)), name(ah)),
+            Catalyst(ah) => write!(f, "a {} _{}_ on hand", emojify(name(ah)), name(ah)),
+            Level(l) => write!(f, "level *{}*", l),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
 pub struct SpawnRate(pub f32, pub (f32, f32));
 impl SpawnRate {
@@ -389,6 +861,7 @@ pub enum PlantAdvancementKind {
     Neighbor(Box<PlantAdvancementKind>),
     Yield(Vec<(SpawnRate, String)>),
     Craft(Vec<Recipe<String>>),
+    CraftQueue(usize),
 }
 
 #[derive(Deserialize, Debug, Clone, PartialEq)]
@@ -400,6 +873,27 @@ pub struct PlantAdvancementSum {
     pub yield_size_multiplier: f32,
     pub yields: Vec<(SpawnRate, ArchetypeHandle)>,
     pub recipes: Vec<Recipe<ArchetypeHandle>>,
+    /// How many crafts can wait in line behind the one a plant is working on
+    pub craft_queue_slots: usize,
+}
+impl PlantAdvancementSum {
+    /// Scales the yield multipliers in this sum by those a specific plant
+    /// inherited from its seed. Its xp rate is applied whenever it earns xp.
+    pub fn with_traits(mut self, traits: crate::possess::seed::PlantTraits) -> Self {
+        self.yield_speed_multiplier *= traits.yield_speed;
+        self.with_yield_size(traits.yield_size)
+    }
+
+    /// Scales up (or down) how much of everything this plant yields.
+    pub fn with_yield_size(mut self, multiplier: f32) -> Self {
+        self.yield_size_multiplier *= multiplier;
+        for (SpawnRate(guard, (lo, hi)), _) in self.yields.iter_mut() {
+            *guard = (*guard * multiplier).min(1.0);
+            *lo *= multiplier;
+            *hi *= multiplier;
+        }
+        self
+    }
 }
 impl AdvancementSum for PlantAdvancementSum {
     type Kind = PlantAdvancementKind;
@@ -413,6 +907,7 @@ impl AdvancementSum for PlantAdvancementSum {
         let mut yield_size_multiplier = 1.0;
         let mut yields = vec![];
         let mut recipes = vec![];
+        let mut craft_queue_slots = 0;
 
         for k in unlocked.iter() {
             xp += k.xp;
@@ -428,6 +923,7 @@ impl AdvancementSum for PlantAdvancementSum {
                 YieldSpeed(multiplier) => yield_speed_multiplier *= multiplier,
                 Neighbor(..) => {}
                 YieldSize(multiplier) => yield_size_multiplier *= multiplier,
+                CraftQueue(slots) => craft_queue_slots += slots,
                 Yield(resources) => yields.append(
                     &mut resources
                         .iter()
@@ -438,19 +934,7 @@ impl AdvancementSum for PlantAdvancementSum {
                 Craft(new_recipes) => recipes.append(
                     &mut new_recipes
                         .iter()
-                        .map(|r| {
-                            Ok(Recipe {
-                                makes: r.makes.clone().find_handles()?,
-                                needs: r
-                                    .needs
-                                    .iter()
-                                    .map(|(c, s)| Ok((*c, CONFIG.find_possession_handle(s)?)))
-                                    .collect::<Result<Vec<_>, ConfigError>>()?,
-
-                                time: r.time,
-                                destroys_plant: r.destroys_plant,
-                            })
-                        })
+                        .map(|r| r.find_handles())
                         .collect::<Result<Vec<_>, ConfigError>>()
                         .expect("couldn't find archetype for crafting advancement"),
                 ),
@@ -477,6 +961,7 @@ impl AdvancementSum for PlantAdvancementSum {
             yield_size_multiplier,
             yields,
             recipes,
+            craft_queue_slots,
         }
     }
 
@@ -608,12 +1093,35 @@ fn upgrade_increase() {
 fn archetype_name_matches() {
     for a in CONFIG.possession_archetypes.iter() {
         match &a.kind {
-            ArchetypeKind::Seed(sa) => assert!(
-                CONFIG.find_plant(&sa.grows_into).is_ok(),
-                "seed archetype {:?} claims it grows into unknown plant archetype {:?}",
-                a.name,
-                sa.grows_into,
-            ),
+            ArchetypeKind::Seed(sa) => {
+                assert!(
+                    CONFIG.find_plant(&sa.grows_into).is_ok(),
+                    "seed archetype {:?} claims it grows into unknown plant archetype {:?}",
+                    a.name,
+                    sa.grows_into,
+                );
+                if let Some((l, r)) = &sa.hybrid_of {
+                    for parent in [l, r].iter() {
+                        assert!(
+                            CONFIG.find_plant(parent).is_ok(),
+                            "hybrid seed archetype {:?} claims it's bred from unknown plant archetype {:?}",
+                            a.name,
+                            parent,
+                        );
+                    }
+                }
+            }
+            ArchetypeKind::Keepsake(KeepsakeArchetype {
+                blueprint: Some(bp),
+                ..
+            }) => {
+                assert!(
+                    CONFIG.find_possession(bp).is_ok(),
+                    "blueprint {:?} reveals recipes for unknown archetype {:?}",
+                    a.name,
+                    bp,
+                );
+            }
             _ => {}
         }
     }
@@ -635,7 +1143,7 @@ fn archetype_name_matches() {
                     }
                 }
                 Craft(recipes) => {
-                    for Recipe { makes, needs, .. } in recipes.iter() {
+                    for Recipe { makes, needs, catalysts, .. } in recipes.iter() {
                         assert!(
                             CONFIG.find_possession(makes).is_ok(),
                             "Crafting advancement {:?} for plant {:?} produces unknown resource {:?}",
@@ -653,10 +1161,142 @@ fn archetype_name_matches() {
                                 makes
                             )
                         }
+                        for catalyst in catalysts.iter() {
+                            assert!(
+                                CONFIG.find_possession(catalyst).is_ok(),
+                                "Crafting advancement {:?} for plant {:?} uses unknown catalyst {:?} in recipe for {:?}",
+                                adv.title,
+                                arch.name,
+                                catalyst,
+                                makes
+                            )
+                        }
                     }
                 }
                 _ => {}
             }
         }
     }
+
+    for adv in CONFIG.profile_archetype.advancements.all() {
+        if let HacksteadAdvancementKind::Workbench(recipes) = &adv.kind {
+            for recipe in recipes.iter() {
+                assert!(
+                    recipe.find_handles().is_ok(),
+                    "Workbench advancement {:?} has a recipe with unknown resources: {:?}",
+                    adv.title,
+                    recipe,
+                )
+            }
+        }
+    }
+}
+
+#[test]
+fn shop_prices() {
+    for item in CONFIG.shop.stock.iter() {
+        assert!(
+            item.archetype_handle().is_ok(),
+            "shop claims to sell unknown possession archetype {:?}",
+            item.name
+        );
+    }
+
+    let shop = ShopConfig {
+        keeper: "Bob".to_string(),
+        markup_percent: 10,
+        stock: vec![],
+    };
+    let item = ShopItem {
+        name: "Bractus Seed".to_string(),
+        price: 20,
+        daily_stock: 10,
+    };
+    assert_eq!(shop.price(&item, 0), 20);
+    assert_eq!(shop.price(&item, 3), 26);
+    assert_eq!(shop.total(&item, 0, 3), 20 + 22 + 24);
+
+    let fixed = ShopConfig {
+        markup_percent: 0,
+        ..shop
+    };
+    assert_eq!(fixed.total(&item, 5, 2), 40);
+}
+
+#[test]
+fn recipe_catalysts_are_not_consumed() {
+    use crate::possess::{Owner, Possession};
+
+    let pin = CONFIG.find_possession_handle(&"Rolling Pin").unwrap();
+    let essence = CONFIG.find_possession_handle(&"bressence").unwrap();
+    let recipe = Recipe {
+        needs: vec![(2, essence)],
+        catalysts: vec![pin, essence],
+        min_level: 3,
+        hidden: false,
+        makes: RecipeMakes::Just(1, pin),
+        destroys_plant: false,
+        time: 1.0,
+    };
+    let inv = |n: usize| -> Vec<Possession> {
+        (0..n)
+            .map(|_| Possession::new(essence, Owner::farmer("U1".to_string())))
+            .chain(Some(Possession::new(pin, Owner::farmer("U1".to_string()))))
+            .collect()
+    };
+
+    // the two essences that get used up can't also count as the catalyst
+    assert_eq!(
+        recipe.missing(&inv(2), 3),
+        vec![MissingRequirement::Catalyst(essence)]
+    );
+    assert_eq!(
+        recipe.missing(&inv(1), 2),
+        vec![
+            MissingRequirement::Resource(1, essence),
+            MissingRequirement::Catalyst(essence),
+            MissingRequirement::Level(3),
+        ]
+    );
+    assert!(recipe.satisfies(&inv(3), 3));
+}
+
+#[test]
+fn encyclopedia_finds_recipes() {
+    let kingpin = CONFIG.find_possession_handle(&"Kingpin").unwrap();
+    let rolling_pin = CONFIG.find_possession_handle(&"Rolling Pin").unwrap();
+    let bractus = CONFIG.find_plant_handle(&"Bractus").unwrap();
+
+    let making = CONFIG.recipes_making(kingpin);
+    assert!(
+        making
+            .iter()
+            .any(|(source, _)| match source {
+                RecipeSource::Plant(ah, _) => *ah == bractus,
+                _ => false,
+            }),
+        "Bractus should be able to craft a Kingpin: {:?}",
+        making
+    );
+    assert!(CONFIG
+        .recipes_using(rolling_pin)
+        .iter()
+        .any(|(_, r)| r.makes.outputs().contains(&kingpin)));
+}
+
+#[test]
+fn market_fees() {
+    use crate::Category;
+
+    let fees = FeeSchedule {
+        percent: 5,
+        category_percent: [(Category::Gotchi, 10)].iter().cloned().collect(),
+        minimum: 2,
+        free_below: 20,
+    };
+
+    assert_eq!(fees.fee(Category::Misc, 19), 0);
+    assert_eq!(fees.fee(Category::Misc, 20), 2);
+    assert_eq!(fees.fee(Category::Misc, 100), 5);
+    assert_eq!(fees.fee(Category::Gotchi, 100), 10);
 }
@@ -0,0 +1,139 @@
+use crate::config::ArchetypeHandle;
+use crate::AttributeParseError;
+use rusoto_dynamodb::AttributeValue;
+
+#[derive(Debug, Clone)]
+pub struct Craft {
+    pub until_finish: f32,
+    pub total_cycles: f32,
+    pub destroys_plant: bool,
+    pub makes: ArchetypeHandle,
+    /// The resources which were consumed to begin this craft
+    pub needs: Vec<(usize, ArchetypeHandle)>,
+}
+
+impl Craft {
+    pub fn from_av(av: &AttributeValue) -> Result<Self, AttributeParseError> {
+        use AttributeParseError::*;
+
+        let m = av.m.as_ref().ok_or(WrongType)?;
+
+        Ok(Self {
+            until_finish: m
+                .get("until_finish")
+                .ok_or(MissingField("until_finish"))?
+                .n
+                .as_ref()
+                .ok_or(WronglyTypedField("until_finish"))?
+                .parse()
+                .map_err(|e| FloatFieldParse("until_finish", e))?,
+            total_cycles: m
+                .get("total_cycles")
+                .ok_or(MissingField("total_cycles"))?
+                .n
+                .as_ref()
+                .ok_or(WronglyTypedField("total_cycles"))?
+                .parse()
+                .map_err(|e| FloatFieldParse("total_cycles", e))?,
+            makes: m
+                .get("makes")
+                .ok_or(MissingField("makes"))?
+                .n
+                .as_ref()
+                .ok_or(WronglyTypedField("makes"))?
+                .parse()
+                .map_err(|e| IntFieldParse("makes", e))?,
+            destroys_plant: match m.get("destroys_plant") {
+                None => false,
+                Some(x) => x.bool.ok_or(WronglyTypedField("destroys_plant"))?,
+            },
+            needs: match m.get("needs") {
+                None => vec![],
+                Some(x) => x
+                    .l
+                    .as_ref()
+                    .ok_or(WronglyTypedField("needs"))?
+                    .iter()
+                    .map(|need| {
+                        let pair = need.l.as_ref().ok_or(WronglyTypedField("needs"))?;
+                        let num = |i: usize| -> Result<usize, AttributeParseError> {
+                            pair.get(i)
+                                .ok_or(MissingField("needs"))?
+                                .n
+                                .as_ref()
+                                .ok_or(WronglyTypedField("needs"))?
+                                .parse()
+                                .map_err(|e| IntFieldParse("needs", e))
+                        };
+                        Ok((num(0)?, num(1)?))
+                    })
+                    .collect::<Result<_, AttributeParseError>>()?,
+            },
+        })
+    }
+
+    pub fn into_av(self) -> AttributeValue {
+        AttributeValue {
+            m: Some(
+                [
+                    (
+                        "until_finish".to_string(),
+                        AttributeValue {
+                            n: Some(self.until_finish.to_string()),
+                            ..Default::default()
+                        },
+                    ),
+                    (
+                        "total_cycles".to_string(),
+                        AttributeValue {
+                            n: Some(self.total_cycles.to_string()),
+                            ..Default::default()
+                        },
+                    ),
+                    (
+                        "makes".to_string(),
+                        AttributeValue {
+                            n: Some(self.makes.to_string()),
+                            ..Default::default()
+                        },
+                    ),
+                    (
+                        "destroys_plant".to_string(),
+                        AttributeValue {
+                            bool: Some(self.destroys_plant),
+                            ..Default::default()
+                        },
+                    ),
+                    (
+                        "needs".to_string(),
+                        AttributeValue {
+                            l: Some(
+                                self.needs
+                                    .iter()
+                                    .map(|&(count, ah)| AttributeValue {
+                                        l: Some(vec![
+                                            AttributeValue {
+                                                n: Some(count.to_string()),
+                                                ..Default::default()
+                                            },
+                                            AttributeValue {
+                                                n: Some(ah.to_string()),
+                                                ..Default::default()
+                                            },
+                                        ]),
+                                        ..Default::default()
+                                    })
+                                    .collect(),
+                            ),
+                            ..Default::default()
+                        },
+                    ),
+                ]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            ..Default::default()
+        }
+    }
+}
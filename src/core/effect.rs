@@ -0,0 +1,180 @@
+use crate::config::{
+    ApplicationEffect, ArchetypeHandle, EffectStacking, KeepsakeArchetype, CONFIG,
+};
+use crate::AttributeParseError;
+use rusoto_dynamodb::AttributeValue;
+
+/// What's left of an item that's been applied to a plant.
+#[derive(Debug, Clone)]
+pub struct Effect {
+    /// The keepsake which was applied to the plant
+    pub item_archetype_handle: ArchetypeHandle,
+    /// How much of this effect is left. For a TimeIncrease, this is how many extra
+    /// cycles are still to be awarded to the plant. For boosts, it's how many
+    /// farming cycles the boost still lasts, and for effects which are used up,
+    /// it's how many more times the effect can be used.
+    pub remaining: u64,
+}
+
+/// How applying an item would interact with the effects already on a plant.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Application {
+    /// The item's effect is added alongside the others.
+    Added,
+    /// The effect at this index lasts longer.
+    Extended(usize),
+    /// The effect at this index is thrown away and started over.
+    Replaced(usize),
+    /// The effect at this index prevents the item from being applied.
+    Rejected(usize),
+}
+
+impl Effect {
+    fn keepsake(ah: ArchetypeHandle) -> Option<&'static KeepsakeArchetype> {
+        CONFIG.possession_archetypes.get(ah)?.kind.keepsake()
+    }
+
+    /// A fresh effect from applying one of this item, if it can be applied.
+    pub fn new(item_archetype_handle: ArchetypeHandle) -> Option<Self> {
+        Some(Self {
+            item_archetype_handle,
+            remaining: Self::keepsake(item_archetype_handle)?
+                .item_application_effect
+                .as_ref()?
+                .amount(),
+        })
+    }
+
+    pub fn application_effect(&self) -> Option<&'static ApplicationEffect> {
+        Self::keepsake(self.item_archetype_handle)?
+            .item_application_effect
+            .as_ref()
+    }
+
+    /// What would happen if this item were applied to a plant under these effects,
+    /// or None if the item has no effect to apply.
+    pub fn application(effects: &[Effect], item_ah: ArchetypeHandle) -> Option<Application> {
+        use EffectStacking::*;
+
+        let keepsake = Self::keepsake(item_ah)?;
+        let effect = keepsake.item_application_effect.as_ref()?;
+        let existing = effects.iter().position(|e| {
+            e.application_effect()
+                .map(|x| x.same_kind(effect))
+                .unwrap_or(false)
+        });
+
+        Some(match (existing, keepsake.application_stacking()?) {
+            (None, _) | (Some(_), Stack) => Application::Added,
+            (Some(i), Extend) => Application::Extended(i),
+            (Some(i), Replace) => Application::Replaced(i),
+            (Some(i), Reject) => Application::Rejected(i),
+        })
+    }
+
+    /// Applies this item to a plant under these effects, according to its stacking rules.
+    pub fn apply(effects: &mut Vec<Effect>, item_ah: ArchetypeHandle) -> Option<Application> {
+        let application = Self::application(effects, item_ah)?;
+        let fresh = Self::new(item_ah)?;
+
+        match application {
+            Application::Added => effects.push(fresh),
+            Application::Extended(i) => effects[i].remaining += fresh.remaining,
+            Application::Replaced(i) => effects[i] = fresh,
+            Application::Rejected(_) => {}
+        }
+        Some(application)
+    }
+
+    pub fn from_av(av: &AttributeValue) -> Result<Self, AttributeParseError> {
+        use AttributeParseError::*;
+
+        let m = av.m.as_ref().ok_or(WrongType)?;
+
+        Ok(Self {
+            item_archetype_handle: m
+                .get("item_archetype_handle")
+                .ok_or(MissingField("item_archetype_handle"))?
+                .n
+                .as_ref()
+                .ok_or(WronglyTypedField("item_archetype_handle"))?
+                .parse()
+                .map_err(|e| IntFieldParse("item_archetype_handle", e))?,
+            remaining: m
+                .get("remaining")
+                .ok_or(MissingField("remaining"))?
+                .n
+                .as_ref()
+                .ok_or(WronglyTypedField("remaining"))?
+                .parse()
+                .map_err(|e| IntFieldParse("remaining", e))?,
+        })
+    }
+
+    pub fn into_av(self) -> AttributeValue {
+        AttributeValue {
+            m: Some(
+                [
+                    (
+                        "item_archetype_handle".to_string(),
+                        AttributeValue {
+                            n: Some(self.item_archetype_handle.to_string()),
+                            ..Default::default()
+                        },
+                    ),
+                    (
+                        "remaining".to_string(),
+                        AttributeValue {
+                            n: Some(self.remaining.to_string()),
+                            ..Default::default()
+                        },
+                    ),
+                ]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            ..Default::default()
+        }
+    }
+}
+
+#[test]
+fn effect_serializes() -> Result<(), AttributeParseError> {
+    let warp = CONFIG.find_possession_handle(&"Warp Powder").unwrap();
+    let og = Effect::new(warp).unwrap();
+    let og_remaining = og.remaining;
+
+    let deserialized = Effect::from_av(&og.into_av())?;
+    assert_eq!(deserialized.item_archetype_handle, warp);
+    assert_eq!(deserialized.remaining, og_remaining);
+    Ok(())
+}
+
+#[test]
+fn stacking_rules_are_followed() {
+    let warp = CONFIG.find_possession_handle(&"Warp Powder").unwrap();
+    let pouch = CONFIG.find_possession_handle(&"Seed Pouch").unwrap();
+    let oil = CONFIG.find_possession_handle(&"Quickening Oil").unwrap();
+
+    let mut effects = vec![];
+    assert_eq!(Effect::apply(&mut effects, warp), Some(Application::Added));
+    let before = effects[0].remaining;
+    // powders extend each other instead of wasting what's left
+    assert_eq!(
+        Effect::apply(&mut effects, warp),
+        Some(Application::Extended(0))
+    );
+    assert_eq!(effects[0].remaining, before * 2);
+
+    assert_eq!(Effect::apply(&mut effects, pouch), Some(Application::Added));
+    assert_eq!(
+        Effect::apply(&mut effects, pouch),
+        Some(Application::Rejected(1))
+    );
+    assert_eq!(effects.len(), 2);
+
+    Effect::apply(&mut effects, oil);
+    Effect::apply(&mut effects, oil);
+    assert_eq!(effects.len(), 4);
+}
@@ -8,8 +8,12 @@ use std::time::SystemTime;
 
 pub mod category;
 pub mod config;
+pub mod craft;
+pub mod effect;
 pub mod market;
+pub mod planner;
 pub mod possess;
+pub mod trade;
 pub mod frontend {
     pub fn emojify<S: ToString>(txt: S) -> String {
         format!(":{}:", txt.to_string().replace(" ", "_"))
@@ -18,6 +22,8 @@ pub mod frontend {
 
 pub use category::{Category, CategoryError};
 pub use config::CONFIG;
+pub use craft::Craft;
+pub use effect::Effect;
 pub use possess::{Possessed, Possession};
 
 pub const TABLE_NAME: &'static str = "hackagotchi";
@@ -81,6 +87,13 @@ pub struct Profile {
     /// This is not an uuid::Uuid because it's actually the steader id of the person who owns this Profile
     pub id: String,
     pub xp: u64,
+    /// Whatever's being crafted on this Hackstead's workbench,
+    /// which doesn't need a plant to craft on.
+    pub workbench: Option<Craft>,
+    /// The things this Hacksteader has discovered the hidden recipes for.
+    pub discovered_recipes: Vec<config::ArchetypeHandle>,
+    /// What this Hacksteader wants to hear about when it goes up on the market.
+    pub watches: Vec<market::Watch>,
 }
 
 impl std::ops::Deref for Profile {
@@ -99,6 +112,9 @@ impl Profile {
             last_farm: SystemTime::now(),
             xp: 0,
             id: owner_id,
+            workbench: None,
+            discovered_recipes: vec![],
+            watches: vec![],
         }
     }
 
@@ -115,6 +131,35 @@ impl Profile {
         self.advancements.sum(self.xp, std::iter::empty())
     }
 
+    /// Reveals any hidden recipes for making `ah`.
+    /// Returns false if there weren't any, or they were already discovered.
+    pub fn discover_recipes_for(&mut self, ah: config::ArchetypeHandle) -> bool {
+        if self.discovered_recipes.contains(&ah)
+            || !CONFIG.recipes_making(ah).iter().any(|(_, r)| r.hidden)
+        {
+            return false;
+        }
+        self.discovered_recipes.push(ah);
+        true
+    }
+
+    /// Starts watching the market for `ah` at or below `max_price`,
+    /// replacing any watch already kept on `ah`.
+    /// Returns false if there's no room left for another watch.
+    pub fn watch(&mut self, ah: config::ArchetypeHandle, max_price: u64) -> bool {
+        match self.watches.iter_mut().find(|w| w.archetype_handle == ah) {
+            Some(w) => w.max_price = max_price,
+            None if self.watches.len() < market::Watch::MAX_PER_PROFILE => {
+                self.watches.push(market::Watch {
+                    archetype_handle: ah,
+                    max_price,
+                })
+            }
+            None => return false,
+        }
+        true
+    }
+
     pub fn increment_xp(&mut self) -> Option<&config::HacksteadAdvancement> {
         CONFIG
             .profile_archetype
@@ -207,6 +252,35 @@ impl Profile {
                 ..Default::default()
             },
         );
+        if let Some(craft) = self.workbench.clone() {
+            m.insert("workbench".to_string(), craft.into_av());
+        }
+        if !self.discovered_recipes.is_empty() {
+            m.insert(
+                "discovered_recipes".to_string(),
+                AttributeValue {
+                    l: Some(
+                        self.discovered_recipes
+                            .iter()
+                            .map(|ah| AttributeValue {
+                                n: Some(ah.to_string()),
+                                ..Default::default()
+                            })
+                            .collect(),
+                    ),
+                    ..Default::default()
+                },
+            );
+        }
+        if !self.watches.is_empty() {
+            m.insert(
+                "watches".to_string(),
+                AttributeValue {
+                    l: Some(self.watches.iter().map(|w| w.into_av()).collect()),
+                    ..Default::default()
+                },
+            );
+        }
         m
     }
 
@@ -229,6 +303,35 @@ impl Profile {
                     .map_err(|x| IntFieldParse("xp", x))?,
                 None => 0,
             },
+            workbench: match item.get("workbench") {
+                Some(c) => Some(Craft::from_av(c)?),
+                None => None,
+            },
+            discovered_recipes: match item.get("discovered_recipes") {
+                Some(l) => l
+                    .l
+                    .as_ref()
+                    .ok_or(WronglyTypedField("discovered_recipes"))?
+                    .iter()
+                    .map(|ah| {
+                        ah.n.as_ref()
+                            .ok_or(WronglyTypedField("discovered_recipes"))?
+                            .parse()
+                            .map_err(|e| IntFieldParse("discovered_recipes", e))
+                    })
+                    .collect::<Result<_, _>>()?,
+                None => vec![],
+            },
+            watches: match item.get("watches") {
+                Some(l) => l
+                    .l
+                    .as_ref()
+                    .ok_or(WronglyTypedField("watches"))?
+                    .iter()
+                    .map(market::Watch::from_av)
+                    .collect::<Result<_, _>>()?,
+                None => vec![],
+            },
             joined: parse_rfc3339(
                 item.get("joined")
                     .ok_or(MissingField("joined"))?
@@ -259,7 +362,7 @@ impl Profile {
 
 /// A model for all keys that use uuid:Uuids internally,
 /// essentially all those except Profile keys.
-#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, Copy)]
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, Copy, PartialEq)]
 pub struct Key {
     pub category: Category,
     pub id: uuid::Uuid,
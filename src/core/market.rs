@@ -1,9 +1,16 @@
-use crate::AttributeParseError;
+use crate::config::{ArchetypeHandle, CONFIG};
+use crate::{AttributeParseError, Category, Item, Key};
+use humantime::{format_rfc3339, parse_rfc3339};
+use rusoto_dynamodb::AttributeValue;
+use std::time::{Duration, SystemTime};
 
 #[derive(Clone, PartialEq, Debug, serde::Serialize, serde::Deserialize)]
 pub struct Sale {
     pub price: u64,
     pub market_name: String,
+    /// When this listing comes off the market if nobody's bought it.
+    /// Listings from before listings expired stay up until they're sold.
+    pub expires: Option<SystemTime>,
 }
 impl Sale {
     pub fn from_item(i: &crate::Item) -> Result<Self, crate::AttributeParseError> {
@@ -18,6 +25,697 @@ impl Sale {
                 .ok_or(WronglyTypedField("market_name"))?
                 .clone(),
             price: i.get("price")?.n.as_ref()?.parse().ok()?,
+            expires: match i.get("expires") {
+                Some(av) => Some(
+                    parse_rfc3339(av.s.as_ref().ok_or(WronglyTypedField("expires"))?)
+                        .map_err(|e| TimeFieldParse("expires", e))?,
+                ),
+                None => None,
+            },
         })
     }
+
+    pub fn expired(&self) -> bool {
+        match self.expires {
+            Some(expires) => expires <= SystemTime::now(),
+            None => false,
+        }
+    }
+}
+
+/// A standing offer to buy some of an archetype, paid for up front
+/// so that it can be filled without the buyer around.
+#[derive(Clone, PartialEq, Debug, serde::Serialize, serde::Deserialize)]
+pub struct BuyOrder {
+    pub id: uuid::Uuid,
+    /// The user who placed the order, and who gets what it buys.
+    pub buyer: String,
+    pub archetype_handle: ArchetypeHandle,
+    /// The most this order will pay for each one.
+    pub price: u64,
+    /// How many more this order will buy.
+    /// The banker is holding `price * quantity` GP for it in escrow.
+    pub quantity: u64,
+}
+impl BuyOrder {
+    pub fn new(
+        buyer: String,
+        archetype_handle: ArchetypeHandle,
+        price: u64,
+        quantity: u64,
+    ) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4(),
+            buyer,
+            archetype_handle,
+            price,
+            quantity,
+        }
+    }
+
+    pub fn key(&self) -> Key {
+        Key {
+            category: Category::BuyOrder,
+            id: self.id,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        CONFIG
+            .possession_archetypes
+            .get(self.archetype_handle)
+            .map(|a| a.name.as_str())
+            .unwrap_or("unknown")
+    }
+
+    /// How much GP is still being held for this order.
+    pub fn escrow(&self) -> u64 {
+        self.price * self.quantity
+    }
+
+    pub fn item(&self) -> Item {
+        let mut m = self.key().into_item();
+        m.insert(
+            "steader".to_string(),
+            AttributeValue {
+                s: Some(self.buyer.clone()),
+                ..Default::default()
+            },
+        );
+        for (name, n) in [
+            ("archetype_handle", self.archetype_handle as u64),
+            ("price", self.price),
+            ("quantity", self.quantity),
+        ]
+        .iter()
+        {
+            m.insert(
+                name.to_string(),
+                AttributeValue {
+                    n: Some(n.to_string()),
+                    ..Default::default()
+                },
+            );
+        }
+        m
+    }
+
+    pub fn from_item(i: &Item) -> Result<Self, AttributeParseError> {
+        use AttributeParseError::*;
+
+        let num = |name: &'static str| -> Result<u64, AttributeParseError> {
+            i.get(name)
+                .ok_or(MissingField(name))?
+                .n
+                .as_ref()
+                .ok_or(WronglyTypedField(name))?
+                .parse()
+                .map_err(|e| IntFieldParse(name, e))
+        };
+
+        Ok(Self {
+            id: Key::from_item(i)?.id,
+            buyer: i
+                .get("steader")
+                .ok_or(MissingField("steader"))?
+                .s
+                .as_ref()
+                .ok_or(WronglyTypedField("steader"))?
+                .clone(),
+            archetype_handle: num("archetype_handle")? as ArchetypeHandle,
+            price: num("price")?,
+            quantity: num("quantity")?,
+        })
+    }
+}
+
+/// Something that changed hands on the market, kept around for its price history.
+#[derive(Clone, PartialEq, Debug)]
+pub struct SaleRecord {
+    pub id: uuid::Uuid,
+    pub archetype_handle: ArchetypeHandle,
+    pub price: u64,
+    pub sold_at: SystemTime,
+    pub buyer: String,
+    pub seller: String,
+}
+impl SaleRecord {
+    pub fn new(
+        archetype_handle: ArchetypeHandle,
+        price: u64,
+        buyer: String,
+        seller: String,
+    ) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4(),
+            archetype_handle,
+            price,
+            // rounded down to the second, since that's how precisely it's stored
+            sold_at: parse_rfc3339(&format_rfc3339(SystemTime::now()).to_string()).unwrap(),
+            buyer,
+            seller,
+        }
+    }
+
+    pub fn key(&self) -> Key {
+        Key {
+            category: Category::SaleRecord,
+            id: self.id,
+        }
+    }
+
+    // no steader, so that these don't show up in anyone's hackstead
+    pub fn item(&self) -> Item {
+        let mut m = self.key().into_item();
+        for (name, s) in [
+            ("sold_at", format_rfc3339(self.sold_at).to_string()),
+            ("buyer", self.buyer.clone()),
+            ("seller", self.seller.clone()),
+        ]
+        .iter()
+        {
+            m.insert(
+                name.to_string(),
+                AttributeValue {
+                    s: Some(s.clone()),
+                    ..Default::default()
+                },
+            );
+        }
+        for (name, n) in [
+            ("archetype_handle", self.archetype_handle as u64),
+            ("price", self.price),
+        ]
+        .iter()
+        {
+            m.insert(
+                name.to_string(),
+                AttributeValue {
+                    n: Some(n.to_string()),
+                    ..Default::default()
+                },
+            );
+        }
+        m
+    }
+
+    pub fn from_item(i: &Item) -> Result<Self, AttributeParseError> {
+        use AttributeParseError::*;
+
+        let num = |name: &'static str| -> Result<u64, AttributeParseError> {
+            i.get(name)
+                .ok_or(MissingField(name))?
+                .n
+                .as_ref()
+                .ok_or(WronglyTypedField(name))?
+                .parse()
+                .map_err(|e| IntFieldParse(name, e))
+        };
+        let string = |name: &'static str| -> Result<String, AttributeParseError> {
+            Ok(i.get(name)
+                .ok_or(MissingField(name))?
+                .s
+                .as_ref()
+                .ok_or(WronglyTypedField(name))?
+                .clone())
+        };
+
+        Ok(Self {
+            id: Key::from_item(i)?.id,
+            archetype_handle: num("archetype_handle")? as ArchetypeHandle,
+            price: num("price")?,
+            sold_at: parse_rfc3339(&string("sold_at")?)
+                .map_err(|e| TimeFieldParse("sold_at", e))?,
+            buyer: string("buyer")?,
+            seller: string("seller")?,
+        })
+    }
+}
+
+/// A summary of the prices something has sold for.
+#[derive(Clone, PartialEq, Debug)]
+pub struct PriceStats {
+    pub sales: usize,
+    /// How much GP changed hands in all of those sales.
+    pub volume: u64,
+    pub min: u64,
+    pub median: u64,
+    pub max: u64,
+}
+impl PriceStats {
+    /// None if nothing was sold.
+    pub fn from_prices(mut prices: Vec<u64>) -> Option<Self> {
+        prices.sort_unstable();
+        let mid = prices.len() / 2;
+
+        Some(Self {
+            sales: prices.len(),
+            volume: prices.iter().sum(),
+            min: *prices.first()?,
+            median: match prices.len() % 2 {
+                0 => (prices[mid - 1] + prices[mid]) / 2,
+                _ => prices[mid],
+            },
+            max: *prices.last()?,
+        })
+    }
+}
+#[test]
+fn price_stats() {
+    assert_eq!(PriceStats::from_prices(vec![]), None);
+
+    let stats = PriceStats::from_prices(vec![40, 10, 30]).unwrap();
+    assert_eq!((stats.sales, stats.volume), (3, 80));
+    assert_eq!((stats.min, stats.median, stats.max), (10, 30, 40));
+
+    let stats = PriceStats::from_prices(vec![40, 10, 30, 20]).unwrap();
+    assert_eq!((stats.min, stats.median, stats.max), (10, 25, 40));
+}
+
+/// The highest bid on an auction so far.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Bid {
+    pub bidder: String,
+    /// The banker is holding this much GP in escrow until the bidder is outbid.
+    pub amount: u64,
+}
+
+/// A possession up for auction, which goes to whoever bids the most for it before it ends.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Auction {
+    /// The same as the id of the possession being auctioned,
+    /// so that nothing can be up for auction twice at once.
+    pub id: uuid::Uuid,
+    pub seller: String,
+    pub possession_category: Category,
+    pub archetype_handle: ArchetypeHandle,
+    /// Bids for less than this aren't accepted.
+    pub reserve: u64,
+    pub ends: SystemTime,
+    pub high_bid: Option<Bid>,
+}
+impl Auction {
+    pub fn new(possession: &crate::Possession, reserve: u64, duration: Duration) -> Self {
+        Self {
+            id: possession.id,
+            seller: possession.steader.clone(),
+            possession_category: possession.kind.category(),
+            archetype_handle: possession.archetype_handle,
+            reserve,
+            // rounded down to the second, since that's how precisely it's stored
+            ends: parse_rfc3339(&format_rfc3339(SystemTime::now() + duration).to_string()).unwrap(),
+            high_bid: None,
+        }
+    }
+
+    pub fn key(&self) -> Key {
+        Key {
+            category: Category::Auction,
+            id: self.id,
+        }
+    }
+
+    /// The key of the possession being auctioned.
+    pub fn possession_key(&self) -> Key {
+        Key {
+            category: self.possession_category,
+            id: self.id,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        CONFIG
+            .possession_archetypes
+            .get(self.archetype_handle)
+            .map(|a| a.name.as_str())
+            .unwrap_or("unknown")
+    }
+
+    /// The least that the next bid can be for.
+    pub fn min_bid(&self) -> u64 {
+        match &self.high_bid {
+            Some(bid) => bid.amount + 1,
+            None => self.reserve,
+        }
+    }
+
+    /// How long until this auction ends, if it hasn't already.
+    pub fn time_left(&self) -> Option<Duration> {
+        self.ends.duration_since(SystemTime::now()).ok()
+    }
+
+    pub fn item(&self) -> Item {
+        let mut m = self.key().into_item();
+        m.insert(
+            "steader".to_string(),
+            AttributeValue {
+                s: Some(self.seller.clone()),
+                ..Default::default()
+            },
+        );
+        m.insert(
+            "ends".to_string(),
+            AttributeValue {
+                s: Some(format_rfc3339(self.ends).to_string()),
+                ..Default::default()
+            },
+        );
+        m.insert(
+            "possession_cat".to_string(),
+            self.possession_category.into_av(),
+        );
+        // price is what the market sorts by, so it's kept up to date with the bidding
+        let mut nums = vec![
+            ("archetype_handle", self.archetype_handle as u64),
+            ("reserve", self.reserve),
+            ("price", self.min_bid()),
+        ];
+        if let Some(bid) = &self.high_bid {
+            nums.push(("bid", bid.amount));
+            m.insert(
+                "bidder".to_string(),
+                AttributeValue {
+                    s: Some(bid.bidder.clone()),
+                    ..Default::default()
+                },
+            );
+        }
+        for (name, n) in nums {
+            m.insert(
+                name.to_string(),
+                AttributeValue {
+                    n: Some(n.to_string()),
+                    ..Default::default()
+                },
+            );
+        }
+        m
+    }
+
+    pub fn from_item(i: &Item) -> Result<Self, AttributeParseError> {
+        use AttributeParseError::*;
+
+        let num = |name: &'static str| -> Result<u64, AttributeParseError> {
+            i.get(name)
+                .ok_or(MissingField(name))?
+                .n
+                .as_ref()
+                .ok_or(WronglyTypedField(name))?
+                .parse()
+                .map_err(|e| IntFieldParse(name, e))
+        };
+        let string = |name: &'static str| -> Result<String, AttributeParseError> {
+            Ok(i.get(name)
+                .ok_or(MissingField(name))?
+                .s
+                .as_ref()
+                .ok_or(WronglyTypedField(name))?
+                .clone())
+        };
+
+        Ok(Self {
+            id: Key::from_item(i)?.id,
+            seller: string("steader")?,
+            possession_category: Category::from_av(
+                i.get("possession_cat")
+                    .ok_or(MissingField("possession_cat"))?,
+            )?,
+            archetype_handle: num("archetype_handle")? as ArchetypeHandle,
+            reserve: num("reserve")?,
+            ends: parse_rfc3339(&string("ends")?).map_err(|e| TimeFieldParse("ends", e))?,
+            high_bid: match i.get("bid") {
+                Some(_) => Some(Bid {
+                    bidder: string("bidder")?,
+                    amount: num("bid")?,
+                }),
+                None => None,
+            },
+        })
+    }
+}
+
+#[test]
+fn auction_serialize() {
+    dotenv::dotenv().ok();
+
+    let possession = crate::Possession::new(
+        CONFIG.find_possession_handle(&"Warp Powder").unwrap(),
+        crate::possess::Owner {
+            id: "bob".to_string(),
+            acquisition: crate::possess::Acquisition::spawned(),
+        },
+    );
+    let mut og = Auction::new(&possession, 30, Duration::from_secs(60 * 60));
+    assert_eq!(og, Auction::from_item(&og.item()).unwrap());
+
+    og.high_bid = Some(Bid {
+        bidder: "alice".to_string(),
+        amount: 45,
+    });
+    assert_eq!(og.min_bid(), 46);
+    assert_eq!(og, Auction::from_item(&og.item()).unwrap());
+}
+
+/// What sort of thing a listing is, for narrowing down the market.
+#[derive(Clone, Copy, PartialEq, Debug, serde::Serialize, serde::Deserialize)]
+pub enum ListingKind {
+    Gotchi,
+    Seed,
+    Keepsake,
+}
+impl ListingKind {
+    pub const ALL: [ListingKind; 3] = [
+        ListingKind::Gotchi,
+        ListingKind::Seed,
+        ListingKind::Keepsake,
+    ];
+
+    pub fn of(kind: &crate::config::ArchetypeKind) -> Self {
+        use crate::config::ArchetypeKind;
+
+        match kind {
+            ArchetypeKind::Gotchi(_) => ListingKind::Gotchi,
+            ArchetypeKind::Seed(_) => ListingKind::Seed,
+            ArchetypeKind::Keepsake(_) => ListingKind::Keepsake,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ListingKind::Gotchi => "Gotchi",
+            ListingKind::Seed => "Seed",
+            ListingKind::Keepsake => "Keepsake",
+        }
+    }
+}
+
+/// The order the things for sale on the market are shown in.
+#[derive(Clone, Copy, PartialEq, Debug, serde::Serialize, serde::Deserialize)]
+pub enum MarketSort {
+    /// The order they're defined in the config, so that similar things end up together.
+    Archetype,
+    Cheapest,
+    Priciest,
+    /// Whatever's been listed most recently comes first.
+    Newest,
+}
+impl MarketSort {
+    pub const ALL: [MarketSort; 4] = [
+        MarketSort::Archetype,
+        MarketSort::Cheapest,
+        MarketSort::Priciest,
+        MarketSort::Newest,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            MarketSort::Archetype => "By type",
+            MarketSort::Cheapest => "Cheapest first",
+            MarketSort::Priciest => "Priciest first",
+            MarketSort::Newest => "Newest first",
+        }
+    }
+}
+
+/// Narrows down what's shown of a category of the market.
+#[derive(Clone, PartialEq, Debug, serde::Serialize, serde::Deserialize)]
+pub struct MarketQuery {
+    pub category: Category,
+    /// Only listings with this in their name, ignoring case.
+    pub search: Option<String>,
+    pub kind: Option<ListingKind>,
+    /// Only listings by this user.
+    pub seller: Option<String>,
+    pub min_price: Option<u64>,
+    pub max_price: Option<u64>,
+    pub sort: MarketSort,
+    pub page: usize,
+}
+impl MarketQuery {
+    pub fn new(category: Category) -> Self {
+        Self {
+            category,
+            search: None,
+            kind: None,
+            seller: None,
+            min_price: None,
+            max_price: None,
+            sort: MarketSort::Archetype,
+            page: 0,
+        }
+    }
+
+    /// Whether anything's being filtered out.
+    pub fn filtered(&self) -> bool {
+        self.search.is_some()
+            || self.kind.is_some()
+            || self.seller.is_some()
+            || self.min_price.is_some()
+            || self.max_price.is_some()
+    }
+
+    pub fn matches(&self, sale: &Sale, possession: &crate::Possession) -> bool {
+        let search = match &self.search {
+            Some(search) => sale
+                .market_name
+                .to_lowercase()
+                .contains(&search.to_lowercase()),
+            None => true,
+        };
+        let kind = match self.kind {
+            Some(kind) => CONFIG
+                .possession_archetypes
+                .get(possession.archetype_handle)
+                .map(|a| ListingKind::of(&a.kind) == kind)
+                .unwrap_or(false),
+            None => true,
+        };
+        let seller = match &self.seller {
+            Some(seller) => possession.steader == *seller,
+            None => true,
+        };
+
+        search
+            && kind
+            && seller
+            && self.min_price.map(|min| sale.price >= min).unwrap_or(true)
+            && self.max_price.map(|max| sale.price <= max).unwrap_or(true)
+    }
+}
+
+#[test]
+fn market_query_matches() {
+    dotenv::dotenv().ok();
+
+    let possession = crate::Possession::new(
+        CONFIG.find_possession_handle(&"Warp Powder").unwrap(),
+        crate::possess::Owner {
+            id: "bob".to_string(),
+            acquisition: crate::possess::Acquisition::spawned(),
+        },
+    );
+    let sale = Sale {
+        price: 30,
+        market_name: "Warp Powder".to_string(),
+        expires: None,
+    };
+    let query = MarketQuery::new(Category::Misc);
+    assert!(!query.filtered());
+    assert!(query.matches(&sale, &possession));
+
+    let warp = MarketQuery {
+        search: Some("warp".to_string()),
+        seller: Some("bob".to_string()),
+        max_price: Some(30),
+        ..query.clone()
+    };
+    assert!(warp.filtered());
+    assert!(warp.matches(&sale, &possession));
+
+    let too_cheap = MarketQuery {
+        min_price: Some(31),
+        ..query.clone()
+    };
+    assert!(!too_cheap.matches(&sale, &possession));
+
+    let gotchis = MarketQuery {
+        kind: Some(ListingKind::Gotchi),
+        ..query
+    };
+    assert!(!gotchis.matches(&sale, &possession));
+}
+
+/// A hacksteader's standing request to be told whenever something of a certain
+/// archetype goes up on the market at or below a certain price.
+#[derive(Clone, Copy, PartialEq, Debug, serde::Serialize, serde::Deserialize)]
+pub struct Watch {
+    pub archetype_handle: ArchetypeHandle,
+    pub max_price: u64,
+}
+impl Watch {
+    /// The most watches a single hacksteader can keep at once.
+    pub const MAX_PER_PROFILE: usize = 10;
+
+    pub fn matches(&self, archetype_handle: ArchetypeHandle, price: u64) -> bool {
+        self.archetype_handle == archetype_handle && price <= self.max_price
+    }
+
+    pub fn into_av(self) -> AttributeValue {
+        AttributeValue {
+            m: Some(
+                [
+                    (
+                        "archetype_handle".to_string(),
+                        AttributeValue {
+                            n: Some(self.archetype_handle.to_string()),
+                            ..Default::default()
+                        },
+                    ),
+                    (
+                        "max_price".to_string(),
+                        AttributeValue {
+                            n: Some(self.max_price.to_string()),
+                            ..Default::default()
+                        },
+                    ),
+                ]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            ..Default::default()
+        }
+    }
+
+    pub fn from_av(av: &AttributeValue) -> Result<Self, AttributeParseError> {
+        use AttributeParseError::*;
+
+        let m = av.m.as_ref().ok_or(WrongType)?;
+        let num = |name: &'static str| -> Result<u64, AttributeParseError> {
+            m.get(name)
+                .ok_or(MissingField(name))?
+                .n
+                .as_ref()
+                .ok_or(WronglyTypedField(name))?
+                .parse()
+                .map_err(|e| IntFieldParse(name, e))
+        };
+
+        Ok(Self {
+            archetype_handle: num("archetype_handle")? as ArchetypeHandle,
+            max_price: num("max_price")?,
+        })
+    }
+}
+
+#[test]
+fn watch_serialize() {
+    let og = Watch {
+        archetype_handle: 3,
+        max_price: 50,
+    };
+    assert_eq!(og, Watch::from_av(&og.into_av()).unwrap());
+    assert!(og.matches(3, 50));
+    assert!(!og.matches(3, 51));
+    assert!(!og.matches(4, 20));
 }
@@ -0,0 +1,180 @@
+use crate::config::{ArchetypeHandle, Recipe, RecipeMakes, RecipeSource, CONFIG};
+use crate::Possession;
+use std::collections::HashMap;
+
+/// Everything that has to happen for someone to craft a given archetype,
+/// starting from what's in their inventory.
+#[derive(Debug, Clone, Default)]
+pub struct CraftPlan {
+    /// How many times each recipe needs to be crafted, in the order they need to happen.
+    pub crafts: Vec<(usize, RecipeSource, Recipe<ArchetypeHandle>)>,
+    /// What can't be crafted from what's on hand, and so must be bought.
+    pub shortfall: Vec<(usize, ArchetypeHandle)>,
+}
+
+impl CraftPlan {
+    /// Plans out crafting one of `target` using the possessions in `inventory`,
+    /// and only the hidden recipes for the archetypes in `discovered`.
+    /// If nothing can craft `target`, the plan is simply to buy one.
+    pub fn new(
+        target: ArchetypeHandle,
+        inventory: &[Possession],
+        discovered: &[ArchetypeHandle],
+    ) -> Self {
+        let mut planner = Planner::new(inventory, discovered, vec![target]);
+        match planner.recipe_for(target) {
+            Some((_, source, recipe)) => planner.craft(1, source, recipe),
+            None => planner.buy(1, target),
+        }
+        planner.plan
+    }
+
+    /// Plans out crafting this particular recipe once, using the possessions in `inventory`,
+    /// and only the hidden recipes for the archetypes in `discovered`.
+    pub fn for_recipe(
+        source: RecipeSource,
+        recipe: Recipe<ArchetypeHandle>,
+        inventory: &[Possession],
+        discovered: &[ArchetypeHandle],
+    ) -> Self {
+        let mut planner = Planner::new(inventory, discovered, recipe.makes.outputs());
+        planner.craft(1, source, recipe);
+        planner.plan
+    }
+}
+
+/// The state needed while walking down the tree of recipes.
+struct Planner<'a> {
+    plan: CraftPlan,
+    /// What's on hand, after everything planned so far
+    have: HashMap<ArchetypeHandle, usize>,
+    /// What's being crafted to make what's being crafted ...
+    path: Vec<ArchetypeHandle>,
+    discovered: &'a [ArchetypeHandle],
+}
+
+impl<'a> Planner<'a> {
+    fn new(
+        inventory: &[Possession],
+        discovered: &'a [ArchetypeHandle],
+        path: Vec<ArchetypeHandle>,
+    ) -> Self {
+        let mut have: HashMap<ArchetypeHandle, usize> = HashMap::new();
+        for p in inventory.iter() {
+            *have.entry(p.archetype_handle).or_insert(0) += 1;
+        }
+
+        Self {
+            plan: CraftPlan::default(),
+            have,
+            path,
+            discovered,
+        }
+    }
+
+    /// The first recipe that can be relied upon to make this archetype,
+    /// and how many of it that recipe makes.
+    fn recipe_for(&self, ah: ArchetypeHandle) -> Option<(usize, RecipeSource, Recipe<ArchetypeHandle>)> {
+        CONFIG
+            .recipes_making(ah)
+            .into_iter()
+            .filter(|(_, recipe)| recipe.discovered_by(self.discovered))
+            .find_map(|(source, recipe)| match recipe.makes {
+                // recipes with random outputs can't be planned around
+                RecipeMakes::Just(n, x) if x == ah && n > 0 => Some((n, source, recipe)),
+                _ => None,
+            })
+    }
+
+    fn craft(&mut self, times: usize, source: RecipeSource, recipe: Recipe<ArchetypeHandle>) {
+        for &(count, ah) in recipe.needs.iter() {
+            self.require(count * times, ah);
+        }
+        for &ah in recipe.catalysts.iter() {
+            if self.have.get(&ah).copied().unwrap_or(0) == 0 {
+                self.require(1, ah);
+                // catalysts aren't used up, so it's still around after crafting
+                *self.have.entry(ah).or_insert(0) += 1;
+            }
+        }
+        self.plan.crafts.push((times, source, recipe));
+    }
+
+    fn require(&mut self, count: usize, ah: ArchetypeHandle) {
+        let on_hand = self.have.entry(ah).or_insert(0);
+        let used = count.min(*on_hand);
+        *on_hand -= used;
+        let short = count - used;
+        if short == 0 {
+            return;
+        }
+
+        // if crafting this would need itself somewhere down the line, just buy it
+        match self.recipe_for(ah).filter(|_| !self.path.contains(&ah)) {
+            Some((makes, source, recipe)) => {
+                let times = (short + makes - 1) / makes;
+                *self.have.entry(ah).or_insert(0) += times * makes - short;
+
+                self.path.push(ah);
+                self.craft(times, source, recipe);
+                self.path.pop();
+            }
+            None => self.buy(short, ah),
+        }
+    }
+
+    fn buy(&mut self, count: usize, ah: ArchetypeHandle) {
+        match self.plan.shortfall.iter_mut().find(|(_, x)| *x == ah) {
+            Some((n, _)) => *n += count,
+            None => self.plan.shortfall.push((count, ah)),
+        }
+    }
+}
+
+#[test]
+fn plan_subtracts_inventory() {
+    use crate::possess::Owner;
+
+    let kingpin = CONFIG.find_possession_handle(&"Kingpin").unwrap();
+    let rolling_pin = CONFIG.find_possession_handle(&"Rolling Pin").unwrap();
+    let hacksprit = CONFIG.find_possession_handle(&"hacksprit").unwrap();
+    let spirit = CONFIG.find_possession_handle(&"Hacker Spirit").unwrap();
+
+    let inventory: Vec<Possession> = std::iter::repeat(hacksprit)
+        .take(48)
+        .chain(Some(rolling_pin))
+        .map(|ah| Possession::new(ah, Owner::farmer("U1".to_string())))
+        .collect();
+    let plan = CraftPlan::new(kingpin, &inventory, &[]);
+
+    // the two missing hacksprit get crafted from Hacker Spirit,
+    // which nothing crafts, so it has to be bought.
+    assert!(plan
+        .crafts
+        .iter()
+        .any(|(times, _, r)| *times == 2 && r.makes.outputs() == vec![hacksprit]));
+    assert!(plan.shortfall.contains(&(10, spirit)));
+    assert!(!plan.shortfall.iter().any(|&(_, ah)| ah == rolling_pin));
+}
+
+#[test]
+fn plan_for_recipe_with_random_output() {
+    let hacksprit = CONFIG.find_possession_handle(&"hacksprit").unwrap();
+    let spirit = CONFIG.find_possession_handle(&"Hacker Spirit").unwrap();
+    let rolling_pin = CONFIG.find_possession_handle(&"Rolling Pin").unwrap();
+
+    let recipe = Recipe {
+        needs: vec![(2, hacksprit)],
+        catalysts: vec![],
+        min_level: 0,
+        hidden: false,
+        makes: RecipeMakes::OneOf(vec![(0.5, rolling_pin), (0.5, spirit)]),
+        destroys_plant: false,
+        time: 1.0,
+    };
+    let plan = CraftPlan::for_recipe(RecipeSource::Workbench(0), recipe.clone(), &[], &[]);
+
+    // the recipe itself is crafted last, after whatever it needs
+    assert_eq!(plan.crafts.last().map(|(_, _, r)| r), Some(&recipe));
+    assert!(plan.shortfall.iter().any(|&(_, ah)| ah == spirit));
+}
@@ -8,6 +8,7 @@ use serde::{Deserialize, Serialize};
 pub struct Seed {
     pub archetype_handle: ArchetypeHandle,
     pub pedigree: Vec<SeedGrower>,
+    pub traits: PlantTraits,
 }
 impl Possessable for Seed {
     fn from_possession_kind(pk: PossessionKind) -> Option<Self> {
@@ -27,6 +28,14 @@ impl SeedGrower {
         SeedGrower { id, generations }
     }
 
+    /// Joins the lineages of two parent plants into the pedigree of their hybrid,
+    /// crediting the crossbreeder with a generation of their own.
+    pub fn crossbreed(a: &[SeedGrower], b: &[SeedGrower], crossbreeder: &str) -> Vec<SeedGrower> {
+        let mut pedigree: Vec<SeedGrower> = a.iter().chain(b.iter()).cloned().collect();
+        pedigree.push(SeedGrower::new(crossbreeder.to_string(), 1));
+        pedigree
+    }
+
     pub fn from_item(item: &Item) -> Result<Self, AttributeParseError> {
         use AttributeParseError::*;
 
@@ -78,6 +87,108 @@ impl Into<AttributeValue> for SeedGrower {
     }
 }
 
+/// Multipliers which set a plant apart from others of its archetype.
+/// Passed down (with a bit of mutation) from a plant to the seeds it yields.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct PlantTraits {
+    pub yield_speed: f32,
+    pub yield_size: f32,
+    pub xp_rate: f32,
+}
+impl Default for PlantTraits {
+    fn default() -> Self {
+        Self {
+            yield_speed: 1.0,
+            yield_size: 1.0,
+            xp_rate: 1.0,
+        }
+    }
+}
+impl PlantTraits {
+    /// How far up or down each trait can drift in a single generation
+    pub const MUTATION: f32 = 0.05;
+    /// Traits can't ever drift outside of this range
+    pub const BOUNDS: (f32, f32) = (0.5, 2.0);
+
+    /// Returns a copy of these traits with each one randomly nudged a bit.
+    pub fn mutate<R: rand::Rng>(self, rng: &mut R) -> Self {
+        let mut nudge = |x: f32| {
+            let (lo, hi) = Self::BOUNDS;
+            (x * rng.gen_range(1.0 - Self::MUTATION, 1.0 + Self::MUTATION))
+                .max(lo)
+                .min(hi)
+        };
+        Self {
+            yield_speed: nudge(self.yield_speed),
+            yield_size: nudge(self.yield_size),
+            xp_rate: nudge(self.xp_rate),
+        }
+    }
+
+    /// How much xp a plant with these traits earns when it would otherwise earn `base`.
+    /// Whatever fraction of a point is left over is awarded by chance.
+    pub fn xp_earned<R: rand::Rng>(self, base: u64, rng: &mut R) -> u64 {
+        let xp = base as f32 * self.xp_rate;
+        let leftover = if rng.gen_range(0.0, 1.0) < xp.fract() { 1 } else { 0 };
+        xp.floor() as u64 + leftover
+    }
+
+    /// The traits halfway between those of two parents.
+    pub fn blend(self, other: Self) -> Self {
+        Self {
+            yield_speed: (self.yield_speed + other.yield_speed) / 2.0,
+            yield_size: (self.yield_size + other.yield_size) / 2.0,
+            xp_rate: (self.xp_rate + other.xp_rate) / 2.0,
+        }
+    }
+
+    pub fn from_av(av: &AttributeValue) -> Result<Self, AttributeParseError> {
+        use AttributeParseError::*;
+
+        let m = av.m.as_ref().ok_or(WrongType)?;
+        let field = |name: &'static str| -> Result<f32, AttributeParseError> {
+            m.get(name)
+                .ok_or(MissingField(name))?
+                .n
+                .as_ref()
+                .ok_or(WronglyTypedField(name))?
+                .parse()
+                .map_err(|e| FloatFieldParse(name, e))
+        };
+
+        Ok(Self {
+            yield_speed: field("yield_speed")?,
+            yield_size: field("yield_size")?,
+            xp_rate: field("xp_rate")?,
+        })
+    }
+}
+impl Into<AttributeValue> for PlantTraits {
+    fn into(self) -> AttributeValue {
+        AttributeValue {
+            m: Some(
+                [
+                    ("yield_speed", self.yield_speed),
+                    ("yield_size", self.yield_size),
+                    ("xp_rate", self.xp_rate),
+                ]
+                .iter()
+                .map(|(name, x)| {
+                    (
+                        name.to_string(),
+                        AttributeValue {
+                            n: Some(x.to_string()),
+                            ..Default::default()
+                        },
+                    )
+                })
+                .collect(),
+            ),
+            ..Default::default()
+        }
+    }
+}
+
 impl std::ops::Deref for Seed {
     type Target = config::SeedArchetype;
 
@@ -101,8 +212,29 @@ impl Seed {
                 id: owner_id.to_string(),
                 generations: 0,
             }],
+            traits: PlantTraits::default(),
         }
     }
+    /// Splits `royalty` GP among the growers in this seed's pedigree,
+    /// weighted by how many generations each of them grew it for.
+    /// Shares are rounded down, so the sum may fall a bit short of `royalty`.
+    pub fn royalties(&self, royalty: u64) -> Vec<(String, u64)> {
+        let total_generations: u64 = self.pedigree.iter().map(|sg| sg.generations).sum();
+        if total_generations == 0 {
+            return vec![];
+        }
+
+        let mut shares: Vec<(String, u64)> = vec![];
+        for sg in self.pedigree.iter() {
+            let share = royalty * sg.generations / total_generations;
+            match shares.iter_mut().find(|(id, _)| *id == sg.id) {
+                Some((_, s)) => *s += share,
+                None => shares.push((sg.id.clone(), share)),
+            }
+        }
+        shares.retain(|(_, share)| *share > 0);
+        shares
+    }
     pub fn fill_from_item(&mut self, item: &Item) -> Result<(), AttributeParseError> {
         use AttributeParseError::*;
 
@@ -124,6 +256,11 @@ impl Seed {
                 None
             })
             .collect();
+        // seeds from before traits existed are perfectly average
+        self.traits = match item.get("traits") {
+            Some(av) => PlantTraits::from_av(av)?,
+            None => PlantTraits::default(),
+        };
 
         Ok(())
     }
@@ -135,5 +272,67 @@ impl Seed {
                 ..Default::default()
             },
         );
+        item.insert("traits".to_string(), self.traits.into());
     }
 }
+#[test]
+fn crossbreed_keeps_both_lineages() {
+    let a = vec![SeedGrower::new("alice".to_string(), 3)];
+    let b = vec![
+        SeedGrower::new("bob".to_string(), 1),
+        SeedGrower::new("carol".to_string(), 2),
+    ];
+
+    let hybrid = SeedGrower::crossbreed(&a, &b, "dave");
+
+    assert_eq!(
+        hybrid.iter().map(|sg| sg.id.as_str()).collect::<Vec<_>>(),
+        vec!["alice", "bob", "carol", "dave"]
+    );
+    assert_eq!(hybrid.iter().map(|sg| sg.generations).sum::<u64>(), 7);
+}
+
+#[test]
+fn xp_rate_scales_xp_earned() {
+    let mut rng = rand::thread_rng();
+    let traits = |xp_rate| PlantTraits {
+        xp_rate,
+        ..Default::default()
+    };
+
+    assert_eq!(traits(1.0).xp_earned(3, &mut rng), 3);
+    assert_eq!(traits(2.0).xp_earned(3, &mut rng), 6);
+    let slow = traits(0.5).xp_earned(3, &mut rng);
+    assert!(slow == 1 || slow == 2);
+}
+
+#[test]
+fn traits_serialize() -> Result<(), AttributeParseError> {
+    let og = PlantTraits::default().mutate(&mut rand::thread_rng());
+
+    let og_av: AttributeValue = og.into();
+    let og_copy = PlantTraits::from_av(&og_av)?;
+
+    assert_eq!(og, og_copy);
+
+    Ok(())
+}
+
+#[test]
+fn royalties_weighted_by_generations() {
+    let seed = Seed {
+        archetype_handle: 0,
+        pedigree: vec![
+            SeedGrower::new("alice".to_string(), 3),
+            SeedGrower::new("bob".to_string(), 1),
+            SeedGrower::new("alice".to_string(), 1),
+            SeedGrower::new("carol".to_string(), 0),
+        ],
+        traits: PlantTraits::default(),
+    };
+
+    assert_eq!(
+        seed.royalties(10),
+        vec![("alice".to_string(), 8), ("bob".to_string(), 2)]
+    );
+}
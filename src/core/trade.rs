@@ -0,0 +1,163 @@
+use crate::{AttributeParseError, Category, Item, Key};
+use rusoto_dynamodb::AttributeValue;
+
+/// One hacksteader's offer to swap some of their possessions, and maybe some GP,
+/// for some of another hacksteader's possessions.
+#[derive(Clone, PartialEq, Debug)]
+pub struct TradeOffer {
+    pub id: uuid::Uuid,
+    /// The user who made the offer.
+    pub proposer: String,
+    /// The user the offer was made to, who can accept or reject it.
+    pub recipient: String,
+    /// What the proposer would give up.
+    pub offered: Vec<Key>,
+    /// What the proposer wants in return.
+    pub requested: Vec<Key>,
+    /// GP the proposer is throwing in on top of what they've offered.
+    pub gp: u64,
+    /// Whether the recipient has been sent the offer yet. Offers with GP are only sent
+    /// once the proposer has paid that GP to the banker, who holds it until the trade goes
+    /// through or is called off.
+    pub sent: bool,
+}
+
+impl TradeOffer {
+    /// The most possessions that can change hands in a single trade.
+    /// A transaction holds at most 25 writes: one to use up the offer, and then,
+    /// for each possession, one to move it and one to check it isn't up for auction.
+    pub const MAX_POSSESSIONS: usize = 12;
+
+    pub fn new(
+        proposer: String,
+        recipient: String,
+        offered: Vec<Key>,
+        requested: Vec<Key>,
+        gp: u64,
+    ) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4(),
+            proposer,
+            recipient,
+            offered,
+            requested,
+            gp,
+            sent: gp == 0,
+        }
+    }
+
+    pub fn key(&self) -> Key {
+        Key {
+            category: Category::TradeOffer,
+            id: self.id,
+        }
+    }
+
+    pub fn item(&self) -> Item {
+        let keys = |keys: &[Key]| AttributeValue {
+            l: Some(
+                keys.iter()
+                    .map(|k| AttributeValue {
+                        m: Some(k.into_item()),
+                        ..Default::default()
+                    })
+                    .collect(),
+            ),
+            ..Default::default()
+        };
+
+        let mut m = self.key().into_item();
+        m.insert(
+            "steader".to_string(),
+            AttributeValue {
+                s: Some(self.proposer.clone()),
+                ..Default::default()
+            },
+        );
+        m.insert(
+            "recipient".to_string(),
+            AttributeValue {
+                s: Some(self.recipient.clone()),
+                ..Default::default()
+            },
+        );
+        m.insert("offered".to_string(), keys(&self.offered));
+        m.insert("requested".to_string(), keys(&self.requested));
+        m.insert(
+            "gp".to_string(),
+            AttributeValue {
+                n: Some(self.gp.to_string()),
+                ..Default::default()
+            },
+        );
+        m.insert(
+            "sent".to_string(),
+            AttributeValue {
+                bool: Some(self.sent),
+                ..Default::default()
+            },
+        );
+        m
+    }
+
+    pub fn from_item(i: &Item) -> Result<Self, AttributeParseError> {
+        use AttributeParseError::*;
+
+        let string = |name: &'static str| -> Result<String, AttributeParseError> {
+            Ok(i.get(name)
+                .ok_or(MissingField(name))?
+                .s
+                .as_ref()
+                .ok_or(WronglyTypedField(name))?
+                .clone())
+        };
+        let keys = |name: &'static str| -> Result<Vec<Key>, AttributeParseError> {
+            i.get(name)
+                .ok_or(MissingField(name))?
+                .l
+                .as_ref()
+                .ok_or(WronglyTypedField(name))?
+                .iter()
+                .map(|av| Key::from_item(av.m.as_ref().ok_or(WronglyTypedField(name))?))
+                .collect()
+        };
+
+        Ok(Self {
+            id: Key::from_item(i)?.id,
+            proposer: string("steader")?,
+            recipient: string("recipient")?,
+            offered: keys("offered")?,
+            requested: keys("requested")?,
+            gp: i
+                .get("gp")
+                .ok_or(MissingField("gp"))?
+                .n
+                .as_ref()
+                .ok_or(WronglyTypedField("gp"))?
+                .parse()
+                .map_err(|e| IntFieldParse("gp", e))?,
+            sent: i
+                .get("sent")
+                .ok_or(MissingField("sent"))?
+                .bool
+                .ok_or(WronglyTypedField("sent"))?,
+        })
+    }
+}
+
+#[test]
+fn trade_offer_serialize() {
+    let og = TradeOffer::new(
+        "bob".to_string(),
+        "alice".to_string(),
+        vec![Key::misc(uuid::Uuid::new_v4())],
+        vec![
+            Key::gotchi(uuid::Uuid::new_v4()),
+            Key::misc(uuid::Uuid::new_v4()),
+        ],
+        20,
+    );
+    assert!(!og.sent);
+
+    assert_eq!(og, TradeOffer::from_item(&og.item()).unwrap());
+}
@@ -7,6 +7,41 @@ lazy_static::lazy_static! {
         then: &banker_balance
     };
 }
+/// All of the Gotchi which will be paid out during a harvest,
+/// i.e. those with any happiness at all.
+pub async fn harvestable_gotchis(
+    db: &rusoto_dynamodb::DynamoDbClient,
+) -> Result<Vec<Possessed<Gotchi>>, String> {
+    let query = db
+        .query(rusoto_dynamodb::QueryInput {
+            table_name: core::TABLE_NAME.to_string(),
+            key_condition_expression: Some("cat = :gotchi_cat".to_string()),
+            expression_attribute_values: Some({
+                [(":gotchi_cat".to_string(), Category::Gotchi.into_av())]
+                    .iter()
+                    .cloned()
+                    .collect()
+            }),
+            ..Default::default()
+        })
+        .await;
+
+    Ok(query
+        .map_err(|e| format!("couldn't query all gotchis: {}", e))?
+        .items
+        .ok_or("no gotchis found!")?
+        .iter()
+        .filter_map(|i| match Possession::from_item(i) {
+            Ok(p) => Some(Possessed::<Gotchi>::from_possession(p).unwrap()),
+            Err(e) => {
+                error!("error parsing gotchi: {}", e);
+                None
+            }
+        })
+        .filter(|g| g.inner.base_happiness > 0)
+        .collect())
+}
+
 fn banker_balance<'a>(
     c: regex::Captures<'a>,
     _: Message<'a>,
@@ -22,34 +57,7 @@ fn banker_balance<'a>(
             .map_err(|e| format!("error parsing number in banker balance msg: {}", e))?;
         info!("I got {} problems and GP ain't one", balance);
 
-        let query = dyn_db()
-            .query(rusoto_dynamodb::QueryInput {
-                table_name: core::TABLE_NAME.to_string(),
-                key_condition_expression: Some("cat = :gotchi_cat".to_string()),
-                expression_attribute_values: Some({
-                    [(":gotchi_cat".to_string(), Category::Gotchi.into_av())]
-                        .iter()
-                        .cloned()
-                        .collect()
-                }),
-                ..Default::default()
-            })
-            .await;
-
-        let gotchis = query
-            .map_err(|e| format!("couldn't query all gotchis: {}", e))?
-            .items
-            .ok_or("no gotchis found!")?
-            .iter()
-            .filter_map(|i| match Possession::from_item(i) {
-                Ok(p) => Some(Possessed::<Gotchi>::from_possession(p).unwrap()),
-                Err(e) => {
-                    error!("error parsing gotchi: {}", e);
-                    None
-                }
-            })
-            .filter(|g| g.inner.base_happiness > 0)
-            .collect::<Vec<Possessed<Gotchi>>>();
+        let gotchis = harvestable_gotchis(&dyn_db()).await?;
 
         let total_happiness: u64 = gotchis.iter().map(|x| x.inner.base_happiness).sum();
         let mut funds_awarded = 0;
@@ -128,9 +136,15 @@ fn banker_balance<'a>(
             funds_awarded += total_happiness;
         }
 
+        let market_fees = market::take_collected_fees(&dyn_db()).await?;
+
         futures::try_join!(
             banker::message(format!("{} GP earned this harvest!", funds_awarded)),
             banker::message(format!("total happiness: {}", total_happiness)),
+            banker::message(format!(
+                "market fees collected since the last harvest: {}",
+                market_fees
+            )),
         )?;
         Ok(())
     }
@@ -65,36 +65,18 @@ fn hackmarket_fees<'a>(
         let db = dyn_db();
         let key = Key { category, id };
         let possession = hacksteader::get_possession(&db, key).await?;
+        // what they were actually charged, in case the fee schedule's changed since
+        let fee = paid_invoice.amount;
         match possession.sale {
             None => futures::try_join!(
-                market::place_on_market(&db, key, price, name.clone()),
-                market::log_blocks(vec![
-                    json!({
-                        "type": "section",
-                        "text": mrkdwn(format!(
-                            "A *{}* has gone up for sale! \
-                            <@{}> is selling it on the hackmarket for *{} GP*!",
-                            possession.name, paid_invoice.invoicee, price
-                        )),
-                        "accessory": {
-                            "type": "image",
-                            "image_url": format!(
-                                "http://{}/gotchi/img/{}/{}.png",
-                                *URL,
-                                category,
-                                filify(&possession.name)
-                            ),
-                            "alt_text": "Hackpheus sitting on bags of money!",
-                        }
-                    }),
-                    comment("QWIK U BETTR BYE ET B4 SUM1 EYLS"),
-                ]),
+                market::list(&db, possession.clone(), price, name.clone()),
+                market::collect_fee(&db, fee),
             )
             .map(|_| ()),
             Some(_) => futures::try_join!(
                 banker::pay(
                     possession.steader.clone(),
-                    price / 20,
+                    fee,
                     format!("the {} you tried to sell is already up for sale", name),
                 ),
                 dm_blocks(
@@ -108,7 +90,7 @@ fn hackmarket_fees<'a>(
                             ),
                             name,
                             price,
-                            price / 20
+                            fee
                         ))
                     })]
                 )
@@ -147,7 +129,27 @@ fn hackmarket_purchase<'a>(
 
         let db = dyn_db();
         let key = core::Key { category, id };
-        match hacksteader::get_possession(&db, key).await?.sale {
+        let possession = hacksteader::get_possession(&db, key).await?;
+
+        // the growers of a seed's line get a cut of every sale of it
+        let sale = match possession.sale {
+            Some(ref sale) if sale.price == price => market::complete_sale(
+                &db,
+                &possession,
+                seller.clone(),
+                paid_invoice.invoicee.clone(),
+                price,
+                0,
+            )
+            .await
+            .map_err(|e| {
+                let a = format!("Couldn't complete sale of {}: {}", id, e);
+                error!("{}", a);
+                a
+            })?,
+            _ => None,
+        };
+        let (seller_earnings, royalties) = match sale {
             Some(sale) => sale,
             None => {
                 futures::try_join!(
@@ -171,53 +173,27 @@ fn hackmarket_purchase<'a>(
                 return Ok(());
             }
         };
+        let royalty_note = if royalties.is_empty() {
+            String::new()
+        } else {
+            format!(
+                "\n\nRoyalties paid to the growers of this seed's line: {}",
+                royalties
+                    .iter()
+                    .map(|(grower, share)| format!("<@{}> *{} GP*", grower, share))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )
+        };
 
-        let paid_for = format!("sale of your {}", name);
         futures::try_join!(
-            db.update_item(rusoto_dynamodb::UpdateItemInput {
-                key: [
-                    ("cat".to_string(), category.into_av()),
-                    (
-                        "id".to_string(),
-                        AttributeValue {
-                            s: Some(id.to_string()),
-                            ..Default::default()
-                        },
-                    ),
-                ]
-                .iter()
-                .cloned()
-                .collect(),
-                expression_attribute_values: Some([
-                    (":new_owner".to_string(), AttributeValue {
-                        s: Some(paid_invoice.invoicee.clone()),
-                        ..Default::default()
-                    }),
-                    (":ownership_entry".to_string(), AttributeValue {
-                        l: Some(vec![possess::Owner {
-                            id: paid_invoice.invoicee.clone(),
-                            acquisition: possess::Acquisition::Purchase {
-                                price: paid_invoice.amount,
-                            }
-                        }.into()]),
-                        ..Default::default()
-                    })
-                ].iter().cloned().collect()),
-                update_expression: Some(concat!(
-                    "REMOVE price, market_name ",
-                    "SET steader = :new_owner, ownership_log = list_append(ownership_log, :ownership_entry)"
-                ).to_string()),
-                table_name: core::TABLE_NAME.to_string(),
-                ..Default::default()
-            }).map_err(|e| format!("database err: {}", e)),
-            banker::pay(seller.clone(), price, paid_for),
             market::log_blocks(vec![
                 json!({
                     "type": "section",
                     "text": mrkdwn(format!(
                         "The sale of a *{}* has gone through! \
-                        <@{}> made the purchase on hackmarket, earning <@{}> *{} GP*!",
-                        name, paid_invoice.invoicee, seller, price
+                        <@{}> made the purchase on hackmarket, earning <@{}> *{} GP*!{}",
+                        name, paid_invoice.invoicee, seller, seller_earnings, royalty_note
                     )),
                     "accessory": {
                         "type": "image",
@@ -232,8 +208,8 @@ fn hackmarket_purchase<'a>(
                     "type": "section",
                     "text": mrkdwn(format!(
                         "The sale of your *{}* has gone through! \
-                        <@{}> made the purchase on hackmarket, earning you *{} GP*!",
-                        name, paid_invoice.invoicee, price
+                        <@{}> made the purchase on hackmarket, earning you *{} GP*!{}",
+                        name, paid_invoice.invoicee, seller_earnings, royalty_note
                     )),
                     "accessory": {
                         "type": "image",
@@ -255,6 +231,541 @@ fn hackmarket_purchase<'a>(
     .boxed()
 }
 
+lazy_static::lazy_static! {
+    pub static ref HACKMARKET_BUY_ORDER: InvoicePaymentTrigger = InvoicePaymentTrigger {
+        regex: Regex::new("hackmarket buy order for ([0-9]+) (.+) at ([0-9]+)gp each :([0-9]+)").unwrap(),
+        then: &hackmarket_buy_order
+    };
+}
+fn hackmarket_buy_order<'a>(
+    c: regex::Captures<'a>,
+    _: Message<'a>,
+    paid_invoice: banker::PaidInvoice,
+) -> HandlerOutput<'a> {
+    async move {
+        let num = |i: usize| -> Result<u64, String> {
+            c.get(i)
+                .ok_or_else(|| format!("no capture {} in buy order", i))?
+                .as_str()
+                .parse()
+                .map_err(|e| format!("buy order number parsing: {}", e))
+        };
+        let order = core::market::BuyOrder::new(
+            paid_invoice.invoicee.clone(),
+            num(4)? as config::ArchetypeHandle,
+            num(3)?,
+            num(1)?,
+        );
+        let name = order.name().to_string();
+
+        let db = dyn_db();
+        futures::try_join!(
+            market::place_buy_order(&db, &order),
+            market::log_blocks(vec![
+                json!({
+                    "type": "section",
+                    "text": mrkdwn(format!(
+                        "<@{}> is looking to buy *{}* {} *{}* on the hackmarket, \
+                        for up to *{} GP* each!",
+                        order.buyer,
+                        order.quantity,
+                        emojify(&name),
+                        name,
+                        order.price
+                    )),
+                }),
+                comment("SELL SELL SELL"),
+            ]),
+            dm_blocks(
+                order.buyer.clone(),
+                vec![json!({
+                    "type": "section",
+                    "text": mrkdwn(format!(
+                        concat!(
+                            "Your buy order for *{}* {} *{}* is up! ",
+                            "Banker is holding onto your *{} GP* until it's filled, ",
+                            "and you can cancel it for a refund at any time from the /hackmarket."
+                        ),
+                        order.quantity,
+                        emojify(&name),
+                        name,
+                        order.escrow()
+                    ))
+                })]
+            )
+        )?;
+
+        market::match_buy_order(&db, &order).await?;
+
+        Ok(())
+    }
+    .boxed()
+}
+
+lazy_static::lazy_static! {
+    pub static ref HACKMARKET_BULK_FEES: InvoicePaymentTrigger = InvoicePaymentTrigger {
+        regex: Regex::new("hackmarket fees for selling ([0-9]+) (.+) at ([0-9]+)gp each :([0-9]+)").unwrap(),
+        then: &hackmarket_bulk_fees
+    };
+}
+fn hackmarket_bulk_fees<'a>(
+    c: regex::Captures<'a>,
+    _: Message<'a>,
+    paid_invoice: banker::PaidInvoice,
+) -> HandlerOutput<'a> {
+    async move {
+        let num = |i: usize| -> Result<u64, String> {
+            c.get(i)
+                .ok_or_else(|| format!("no capture {} in bulk listing", i))?
+                .as_str()
+                .parse()
+                .map_err(|e| format!("bulk listing number parsing: {}", e))
+        };
+        let (quantity, price) = (num(1)?, num(3)?);
+        let ah = num(4)? as config::ArchetypeHandle;
+        let seller = paid_invoice.invoicee.clone();
+
+        // whichever of them aren't already up for sale by the time the fees come in
+        let db = dyn_db();
+        let listing: Vec<Possession> = Hacksteader::from_db(&db, seller.clone())
+            .await?
+            .inventory
+            .into_iter()
+            .filter(|p| p.archetype_handle == ah && p.sale.is_none())
+            .take(quantity as usize)
+            .collect();
+        let archetype = CONFIG
+            .possession_archetypes
+            .get(ah)
+            .ok_or_else(|| format!("bulk listing of unknown archetype {}", ah))?;
+        let name = archetype.name.clone();
+        let fee = paid_invoice.amount / quantity.max(1);
+
+        futures::future::try_join_all(
+            listing
+                .iter()
+                .map(|p| market::place_on_market(&db, p.key(), price, p.name.clone())),
+        )
+        .await?;
+
+        market::collect_fee(&db, listing.len() as u64 * fee).await?;
+
+        let missing = quantity - listing.len() as u64;
+        let refund = missing * fee;
+        if refund > 0 {
+            futures::try_join!(
+                banker::pay(
+                    seller.clone(),
+                    refund,
+                    format!("the {} you tried to sell but didn't have", name),
+                ),
+                dm_blocks(
+                    seller.clone(),
+                    vec![json!({
+                        "type": "section",
+                        "text": mrkdwn(format!(
+                            concat!(
+                                "You only had {} {} left to sell, not {}, ",
+                                "so the {}gp in market fees for the other {} has been refunded."
+                            ),
+                            listing.len(),
+                            name,
+                            quantity,
+                            refund,
+                            missing
+                        ))
+                    })]
+                )
+            )?;
+        }
+
+        if !listing.is_empty() {
+            market::log_blocks(vec![
+                json!({
+                    "type": "section",
+                    "text": mrkdwn(format!(
+                        "*{}* {} *{}* have gone up for sale! \
+                        <@{}> is selling them on the hackmarket for *{} GP* each!",
+                        listing.len(),
+                        emojify(&name),
+                        name,
+                        seller,
+                        price
+                    )),
+                    "accessory": {
+                        "type": "image",
+                        "image_url": format!(
+                            "http://{}/gotchi/img/{}/{}.png",
+                            *URL,
+                            listing[0].kind.category(),
+                            filify(&name)
+                        ),
+                        "alt_text": "Hackpheus sitting on bags of money!",
+                    }
+                }),
+                comment("BULK BARGAINS"),
+            ])
+            .await?;
+        }
+
+        // someone might've already been waiting to buy these,
+        // and whatever's left, someone might be watching for
+        let mut unsold = 0;
+        for possession in listing {
+            if !market::match_listing(&db, possession, price).await? {
+                unsold += 1;
+            }
+        }
+        if unsold > 0 {
+            market::notify_watchers(&db, &seller, ah, price, unsold).await?;
+        }
+
+        Ok(())
+    }
+    .boxed()
+}
+
+lazy_static::lazy_static! {
+    pub static ref HACKMARKET_BULK_PURCHASE: InvoicePaymentTrigger = InvoicePaymentTrigger {
+        regex: Regex::new("hackmarket bulk purchase of ([0-9]+) (.+) at up to ([0-9]+)gp each :([0-9]+)").unwrap(),
+        then: &hackmarket_bulk_purchase
+    };
+}
+fn hackmarket_bulk_purchase<'a>(
+    c: regex::Captures<'a>,
+    _: Message<'a>,
+    paid_invoice: banker::PaidInvoice,
+) -> HandlerOutput<'a> {
+    async move {
+        let num = |i: usize| -> Result<u64, String> {
+            c.get(i)
+                .ok_or_else(|| format!("no capture {} in bulk purchase", i))?
+                .as_str()
+                .parse()
+                .map_err(|e| format!("bulk purchase number parsing: {}", e))
+        };
+        let (quantity, max_price) = (num(1)?, num(3)?);
+        let ah = num(4)? as config::ArchetypeHandle;
+        let buyer = paid_invoice.invoicee.clone();
+        let name = CONFIG
+            .possession_archetypes
+            .get(ah)
+            .map(|a| a.name.clone())
+            .ok_or_else(|| format!("bulk purchase of unknown archetype {}", ah))?;
+
+        let bought = market::bulk_purchase(&dyn_db(), buyer.clone(), ah, quantity, max_price)
+            .await
+            .map_err(|e| {
+                let a = format!("Couldn't complete bulk purchase of {}: {}", name, e);
+                error!("{}", a);
+                a
+            })?;
+        let spent: u64 = bought.iter().map(|(_, price)| price).sum();
+
+        let note = if bought.len() as u64 == quantity {
+            String::new()
+        } else {
+            format!(
+                " There weren't enough listed for *{} GP* or less to get all *{}*, \
+                so the GP for the rest has been refunded.",
+                max_price, quantity
+            )
+        };
+        dm_blocks(
+            buyer.clone(),
+            vec![json!({
+                "type": "section",
+                "text": mrkdwn(format!(
+                    "You've bought *{}* {} *{}* on the hackmarket for *{} GP* in total!{}",
+                    bought.len(),
+                    emojify(&name),
+                    name,
+                    spent,
+                    note
+                )),
+            })],
+        )
+        .await?;
+
+        if !bought.is_empty() {
+            market::log_blocks(vec![
+                json!({
+                    "type": "section",
+                    "text": mrkdwn(format!(
+                        "<@{}> bought *{}* {} *{}* on the hackmarket for *{} GP* in total!",
+                        buyer,
+                        bought.len(),
+                        emojify(&name),
+                        name,
+                        spent
+                    )),
+                }),
+                comment("BUYIN IN BULK"),
+            ])
+            .await?;
+        }
+
+        Ok(())
+    }
+    .boxed()
+}
+
+lazy_static::lazy_static! {
+    pub static ref HACKMARKET_BID: InvoicePaymentTrigger = InvoicePaymentTrigger {
+        regex: Regex::new("hackmarket bid of ([0-9]+)gp on (.+) at auction :(.+)").unwrap(),
+        then: &hackmarket_bid
+    };
+}
+fn hackmarket_bid<'a>(
+    c: regex::Captures<'a>,
+    _: Message<'a>,
+    paid_invoice: banker::PaidInvoice,
+) -> HandlerOutput<'a> {
+    async move {
+        let amount: u64 = c
+            .get(1)
+            .ok_or_else(|| "no amount in bid".to_string())?
+            .as_str()
+            .parse()
+            .map_err(|e| format!("bid amount number parsing: {}", e))?;
+        let name = c
+            .get(2)
+            .ok_or_else(|| "no name in bid".to_string())?
+            .as_str()
+            .to_string();
+        let auction_id = uuid::Uuid::parse_str(
+            c.get(3)
+                .ok_or_else(|| "no auction id in bid".to_string())?
+                .as_str(),
+        )
+        .map_err(|e| format!("invalid uuid in bid: {}", e))?;
+
+        let db = dyn_db();
+        let bid = core::market::Bid {
+            bidder: paid_invoice.invoicee.clone(),
+            amount,
+        };
+        let outbid = match market::bid_on_auction(&db, auction_id, bid).await? {
+            Some(before) => before.high_bid,
+            None => {
+                futures::try_join!(
+                    banker::pay(
+                        paid_invoice.invoicee.clone(),
+                        amount,
+                        format!("your bid on a {} couldn't be placed", name),
+                    ),
+                    dm_blocks(
+                        paid_invoice.invoicee.clone(),
+                        vec![json!({
+                            "type": "section",
+                            "text": mrkdwn(format!(
+                                concat!(
+                                    "Your {}gp bid on the {} *{}* couldn't be placed, ",
+                                    "either because someone's outbid you or because the ",
+                                    "auction has ended, so your GP has been refunded."
+                                ),
+                                amount,
+                                emojify(&name),
+                                name
+                            ))
+                        })]
+                    )
+                )?;
+                return Ok(());
+            }
+        };
+
+        dm_blocks(
+            paid_invoice.invoicee.clone(),
+            vec![json!({
+                "type": "section",
+                "text": mrkdwn(format!(
+                    concat!(
+                        "You're now the highest bidder on the {} *{}*, at *{} GP*! ",
+                        "Banker is holding onto your GP until the auction ends, ",
+                        "and will refund it if you're outbid."
+                    ),
+                    emojify(&name),
+                    name,
+                    amount
+                ))
+            })],
+        )
+        .await?;
+
+        // whoever was winning before gets their GP back
+        if let Some(core::market::Bid { bidder, amount }) = outbid {
+            futures::try_join!(
+                banker::pay(
+                    bidder.clone(),
+                    amount,
+                    format!("you've been outbid on a {}", name),
+                ),
+                dm_blocks(
+                    bidder.clone(),
+                    vec![json!({
+                        "type": "section",
+                        "text": mrkdwn(format!(
+                            concat!(
+                                "You've been outbid on the {} *{}*, ",
+                                "so your *{} GP* bid has been refunded. ",
+                                "There's still time to bid again from the /hackmarket!"
+                            ),
+                            emojify(&name),
+                            name,
+                            amount
+                        ))
+                    })]
+                )
+            )?;
+        }
+
+        Ok(())
+    }
+    .boxed()
+}
+
+lazy_static::lazy_static! {
+    pub static ref SHOP_PURCHASE: InvoicePaymentTrigger = InvoicePaymentTrigger {
+        regex: Regex::new(
+            "shop purchase of ([0-9]+) (.+) for ([0-9]+)gp :([0-9]+):([0-9]+):([0-9]+)"
+        )
+        .unwrap(),
+        then: &shop_purchase
+    };
+}
+fn shop_purchase<'a>(
+    c: regex::Captures<'a>,
+    _: Message<'a>,
+    paid_invoice: banker::PaidInvoice,
+) -> HandlerOutput<'a> {
+    async move {
+        let num = |i: usize| -> Result<u64, String> {
+            c.get(i)
+                .ok_or_else(|| format!("no capture {} in shop purchase", i))?
+                .as_str()
+                .parse()
+                .map_err(|e| format!("shop purchase number parsing: {}", e))
+        };
+        let quantity = num(1)?;
+        let ah = num(4)? as config::ArchetypeHandle;
+        // the day this was quoted on, and how many had been sold by then
+        let (day, sold) = (num(5)?, num(6)?);
+        let buyer = paid_invoice.invoicee.clone();
+        let item = CONFIG
+            .shop
+            .item(ah)
+            .ok_or_else(|| format!("shop purchase of unstocked archetype {}", ah))?;
+
+        let db = dyn_db();
+        if !shop::reserve(&db, ah, quantity, item.daily_stock, day, sold).await? {
+            return futures::try_join!(
+                banker::pay(
+                    buyer.clone(),
+                    paid_invoice.amount,
+                    format!(
+                        "the price of {} {} changed before you could buy them",
+                        quantity, item.name
+                    ),
+                ),
+                dm_blocks(
+                    buyer.clone(),
+                    vec![json!({
+                        "type": "section",
+                        "text": mrkdwn(format!(
+                            "{} had sold more {} or restocked by the time your order for \
+                            *{}* came in, so the price you were quoted is out of date \
+                            and your *{} GP* has been refunded. Have another look at the shop!",
+                            CONFIG.shop.keeper, item.name, quantity, paid_invoice.amount
+                        )),
+                    })],
+                )
+            )
+            .map(|_| ());
+        }
+
+        let acquisition = possess::Acquisition::Purchase {
+            price: paid_invoice.amount / quantity.max(1),
+        };
+        for _ in 0..quantity {
+            let possession = Possession::new(
+                ah,
+                possess::Owner {
+                    id: buyer.clone(),
+                    acquisition: acquisition.clone(),
+                },
+            );
+            Hacksteader::give_possession(&db, buyer.clone(), &possession)
+                .await
+                .map_err(|e| {
+                    let a = format!("Couldn't give {} from the shop: {}", item.name, e);
+                    error!("{}", a);
+                    a
+                })?;
+        }
+
+        dm_blocks(
+            buyer.clone(),
+            vec![
+                json!({
+                    "type": "section",
+                    "text": mrkdwn(format!(
+                        "You've bought *{}* {} *{}* from {} for *{} GP*!",
+                        quantity,
+                        emojify(&item.name),
+                        item.name,
+                        CONFIG.shop.keeper,
+                        paid_invoice.amount
+                    )),
+                }),
+                comment("THANK U COME AGEN"),
+            ],
+        )
+        .await?;
+
+        Ok(())
+    }
+    .boxed()
+}
+
+lazy_static::lazy_static! {
+    pub static ref TRADE_OFFER: InvoicePaymentTrigger = InvoicePaymentTrigger {
+        regex: Regex::new("trade offer of ([0-9]+)gp to <@([A-z|0-9]+)> :(.+)").unwrap(),
+        then: &trade_offer
+    };
+}
+fn trade_offer<'a>(
+    c: regex::Captures<'a>,
+    _: Message<'a>,
+    paid_invoice: banker::PaidInvoice,
+) -> HandlerOutput<'a> {
+    async move {
+        let offer_id = uuid::Uuid::parse_str(
+            c.get(3)
+                .ok_or_else(|| "no offer id in trade offer".to_string())?
+                .as_str(),
+        )
+        .map_err(|e| format!("invalid uuid in trade offer: {}", e))?;
+
+        let db = dyn_db();
+        match trade::mark_sent(&db, offer_id).await? {
+            Some(offer) => trade::send(&db, &offer).await?,
+            // they called it off before paying, so give them their GP back
+            None => {
+                banker::pay(
+                    paid_invoice.invoicee.clone(),
+                    paid_invoice.amount,
+                    "a trade offer you called off".to_string(),
+                )
+                .await?
+            }
+        }
+
+        Ok(())
+    }
+    .boxed()
+}
+
 lazy_static::lazy_static! {
     pub static ref START_HACKSTEAD_INVOICE_PAYMENT: InvoicePaymentTrigger = InvoicePaymentTrigger {
         regex: Regex::new("let's hackstead, fred!").unwrap(),
@@ -275,15 +786,17 @@ fn start_hackstead_invoice_payment<'a>(
             dm_blocks(paid_invoice.invoicee.clone(), vec![
                  json!({
                      "type": "section",
-                     "text": mrkdwn(
+                     "text": mrkdwn(format!(
                          "Congratulations, new Hacksteader! \
                          Welcome to the community!\n\n\
                          :house: Manage your hackstead in the home tab above\n\
                          :harder-flex: Show anyone a snapshot of your hackstead with /hackstead\n\
                          :sleuth_or_spy: Look up anyone else's hackstead with /hackstead @<their name>\n\
-                         :money_with_wings: Shop from the user-run hacksteaders' market with /hackmarket\n\n\
-                         You might want to start by buying some seeds there and planting them at your hackstead!"
-                     ),
+                         :money_with_wings: Shop from the user-run hacksteaders' market with /hackmarket\n\
+                         :seedling: Pick up seeds any day from {}'s shop, right on the /hackmarket\n\n\
+                         You might want to start by buying some seeds and planting them at your hackstead!",
+                         CONFIG.shop.keeper
+                     )),
                  }),
                  comment("LET'S HACKSTEAD, FRED!")
             ]).await?;
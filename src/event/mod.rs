@@ -26,7 +26,7 @@ mod prelude {
     pub use futures::stream::{self, StreamExt, TryStreamExt};
     // us
     pub use super::{HandlerOutput, Message, Trigger};
-    pub use crate::{banker, hacksteader, market};
+    pub use crate::{banker, hacksteader, market, shop, trade};
     pub use crate::{FarmingInputEvent, URL};
     pub use config::CONFIG;
     pub use core::config;
@@ -145,17 +145,24 @@ pub type InvoicePaymentTrigger = Trigger<&'static PaidInvoiceHandler>;
 pub type BankerMessageTrigger = Trigger<&'static CaptureHandler>;
 
 lazy_static::lazy_static! {
-    static ref SPECIAL_USER_MESSAGE_TRIGGERS: [&'static SpecialUserMessageTrigger; 6] = [
+    static ref SPECIAL_USER_MESSAGE_TRIGGERS: [&'static SpecialUserMessageTrigger; 7] = [
         &*special_user_message::SPAWN_COMMAND,
         &*special_user_message::GP_DUMP_COMMAND,
         &*special_user_message::STOMP_COMMAND,
         &*special_user_message::SLAUGHTER_COMMAND,
         &*special_user_message::NAB_COMMAND,
         &*special_user_message::YANK_CONFIG,
+        &*special_user_message::HARVEST_PREVIEW_COMMAND,
     ];
-    static ref INVOICE_PAYMENT_TRIGGERS: [&'static InvoicePaymentTrigger; 3] = [
+    static ref INVOICE_PAYMENT_TRIGGERS: [&'static InvoicePaymentTrigger; 9] = [
         &*invoice_payment::HACKMARKET_FEES,
         &*invoice_payment::HACKMARKET_PURCHASE,
+        &*invoice_payment::HACKMARKET_BUY_ORDER,
+        &*invoice_payment::HACKMARKET_BULK_FEES,
+        &*invoice_payment::HACKMARKET_BULK_PURCHASE,
+        &*invoice_payment::HACKMARKET_BID,
+        &*invoice_payment::SHOP_PURCHASE,
+        &*invoice_payment::TRADE_OFFER,
         &*invoice_payment::START_HACKSTEAD_INVOICE_PAYMENT,
     ];
     static ref BANKER_MESSAGE_TRIGGERS: [&'static BankerMessageTrigger; 1] = [
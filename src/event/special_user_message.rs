@@ -306,3 +306,85 @@ fn nab_command<'a>(
     }
     .boxed()
 }
+
+lazy_static::lazy_static! {
+    pub static ref HARVEST_PREVIEW_COMMAND: SpecialUserMessageTrigger = SpecialUserMessageTrigger {
+        regex: Regex::new("<@([A-z|0-9]+)> harvest preview ([0-9]+)").unwrap(),
+        then: &harvest_preview_command
+    };
+}
+/// Computes what a harvest would pay out given a balance,
+/// without paying anyone or touching any harvest logs.
+fn harvest_preview_command<'a>(
+    c: regex::Captures<'a>,
+    _: Message<'a>,
+    _: &'a Sender<FarmingInputEvent>,
+) -> HandlerOutput<'a> {
+    use std::collections::HashMap;
+
+    async move {
+        let balance = c
+            .get(2)
+            .ok_or_else(|| "no gp amount".to_string())?
+            .as_str()
+            .parse::<u64>()
+            .map_err(|e| format!("invalid harvest preview balance: {}", e))?;
+
+        let gotchis = super::banker_message::harvestable_gotchis(&dyn_db()).await?;
+        let total_happiness: u64 = gotchis.iter().map(|x| x.inner.base_happiness).sum();
+        if total_happiness == 0 {
+            return banker::message(
+                "harvest preview: no gotchi are happy enough to harvest".to_string(),
+            )
+            .await;
+        }
+
+        // the harvest pays out in rounds, giving each gotchi its
+        // base happiness every round until the balance runs out.
+        let rounds = balance / total_happiness;
+
+        let mut steaders: HashMap<&str, Vec<&Possessed<Gotchi>>> = HashMap::new();
+        for gotchi in gotchis.iter() {
+            steaders.entry(gotchi.steader.as_str()).or_default().push(gotchi);
+        }
+        let mut steaders = steaders.into_iter().collect::<Vec<_>>();
+        steaders.sort_by_key(|(_, gs)| {
+            std::cmp::Reverse(gs.iter().map(|g| g.inner.base_happiness).sum::<u64>())
+        });
+
+        banker::message(format!(
+            concat!(
+                "*Harvest preview* for {}gp _(nothing has been paid)_\n",
+                "total happiness: {}, rounds: {}, paid out: {}gp, left over: {}gp\n\n",
+                "{}",
+            ),
+            balance,
+            total_happiness,
+            rounds,
+            rounds * total_happiness,
+            balance - rounds * total_happiness,
+            steaders
+                .into_iter()
+                .map(|(steader, gs)| {
+                    format!(
+                        "<@{}> gets *{}gp*: {}",
+                        steader,
+                        gs.iter().map(|g| g.inner.base_happiness).sum::<u64>() * rounds,
+                        gs.iter()
+                            .map(|g| format!(
+                                "{} _{}_ {}gp",
+                                emojify(&g.name),
+                                g.inner.nickname,
+                                g.inner.base_happiness * rounds
+                            ))
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        ))
+        .await
+    }
+    .boxed()
+}
@@ -1,7 +1,11 @@
 use config::{ArchetypeHandle, PlantArchetype, CONFIG};
 use core::config;
+use core::market::{Auction, BuyOrder};
 use core::possess;
+use core::trade::TradeOffer;
 use core::{AttributeParseError, Category, Item, Key, Profile, TABLE_NAME};
+pub use core::Craft;
+pub use core::Effect;
 use possess::{Possessed, Possession};
 use rusoto_core::RusotoError;
 use rusoto_dynamodb::{AttributeValue, DynamoDb, DynamoDbClient, PutItemError};
@@ -40,6 +44,23 @@ pub async fn get_possession(db: &DynamoDbClient, key: Key) -> Result<Possession,
     })
 }
 
+pub async fn get_profile(db: &DynamoDbClient, user_id: String) -> Result<Profile, String> {
+    db.get_item(rusoto_dynamodb::GetItemInput {
+        key: Profile::key_item(user_id.clone()),
+        table_name: TABLE_NAME.to_string(),
+        ..Default::default()
+    })
+    .await
+    .map_err(|e| format!("couldn't read {}'s profile from db: {}", user_id, e))
+    .and_then(|x| {
+        Profile::from_item(
+            &x.item
+                .ok_or_else(|| format!("no profile for {}", user_id))?,
+        )
+        .map_err(|e| format!("couldn't parse {}'s profile: {}", user_id, e))
+    })
+}
+
 pub async fn get_tile(db: &DynamoDbClient, id: uuid::Uuid) -> Result<Tile, String> {
     db.get_item(rusoto_dynamodb::GetItemInput {
         key: Key::tile(id).into_item(),
@@ -243,20 +264,18 @@ impl Tile {
     }
 }
 
-#[derive(Debug, Clone)]
-pub struct Craft {
-    pub until_finish: f32,
-    pub total_cycles: f32,
-    pub destroys_plant: bool,
-    pub makes: ArchetypeHandle,
-}
-
 #[derive(Debug, Clone)]
 pub struct Plant {
     pub xp: u64,
     pub until_yield: f32,
     pub craft: Option<Craft>,
+    /// Crafts waiting for the current one to finish.
+    /// Their resources have already been taken.
+    pub craft_queue: Vec<Craft>,
+    /// What's left of the items that have been applied to this plant.
+    pub effects: Vec<Effect>,
     pub pedigree: Vec<possess::seed::SeedGrower>,
+    pub traits: possess::seed::PlantTraits,
     pub archetype_handle: ArchetypeHandle,
 }
 
@@ -270,99 +289,80 @@ impl std::ops::Deref for Plant {
             .expect("invalid archetype handle")
     }
 }
-impl Craft {
-    pub fn from_av(av: &AttributeValue) -> Result<Self, AttributeParseError> {
-        use AttributeParseError::*;
-
-        let m = av.m.as_ref().ok_or(WrongType)?;
-
-        Ok(Self {
-            until_finish: m
-                .get("until_finish")
-                .ok_or(MissingField("until_finish"))?
-                .n
-                .as_ref()
-                .ok_or(WronglyTypedField("until_finish"))?
-                .parse()
-                .map_err(|e| FloatFieldParse("until_finish", e))?,
-            total_cycles: m
-                .get("total_cycles")
-                .ok_or(MissingField("total_cycles"))?
-                .n
-                .as_ref()
-                .ok_or(WronglyTypedField("total_cycles"))?
-                .parse()
-                .map_err(|e| FloatFieldParse("total_cycles", e))?,
-            makes: m
-                .get("makes")
-                .ok_or(MissingField("makes"))?
-                .n
-                .as_ref()
-                .ok_or(WronglyTypedField("makes"))?
-                .parse()
-                .map_err(|e| IntFieldParse("makes", e))?,
-            destroys_plant: match m.get("destroys_plant") {
-                None => false,
-                Some(x) => x.bool.ok_or(WronglyTypedField("destroys_plant"))?,
-            },
-        })
-    }
-
-    pub fn into_av(self) -> AttributeValue {
-        AttributeValue {
-            m: Some(
-                [
-                    (
-                        "until_finish".to_string(),
-                        AttributeValue {
-                            n: Some(self.until_finish.to_string()),
-                            ..Default::default()
-                        },
-                    ),
-                    (
-                        "total_cycles".to_string(),
-                        AttributeValue {
-                            n: Some(self.total_cycles.to_string()),
-                            ..Default::default()
-                        },
-                    ),
-                    (
-                        "makes".to_string(),
-                        AttributeValue {
-                            n: Some(self.makes.to_string()),
-                            ..Default::default()
-                        },
-                    ),
-                    (
-                        "destroys_plant".to_string(),
-                        AttributeValue {
-                            bool: Some(self.destroys_plant),
-                            ..Default::default()
-                        },
-                    ),
-                ]
-                .iter()
-                .cloned()
-                .collect(),
-            ),
-            ..Default::default()
-        }
-    }
-}
-
 impl Plant {
     pub fn from_seed(seed: Possessed<possess::Seed>) -> Self {
         let mut s = Self {
             xp: 0,
             until_yield: 0.0,
             craft: None,
+            craft_queue: vec![],
+            effects: vec![],
             archetype_handle: CONFIG.find_plant_handle(&seed.inner.grows_into).unwrap(),
             pedigree: seed.inner.pedigree,
+            traits: seed.inner.traits,
         };
         s.until_yield = s.base_yield_duration;
         s
     }
 
+    /// Whether this plant's current yield has grown far enough along to be used up
+    /// crossbreeding. Crossbreeding starts the yield over, so this keeps a pair of plants
+    /// from being crossbred again until they've regrown half of a yield.
+    pub fn ready_to_crossbreed(&self) -> bool {
+        self.until_yield <= self.base_yield_duration / 2.0
+    }
+
+    /// The archetype of the seed this plant grows from.
+    pub fn seed_archetype_handle(&self) -> Option<ArchetypeHandle> {
+        let seeds = || {
+            CONFIG
+                .possession_archetypes
+                .iter()
+                .enumerate()
+                .filter_map(|(ah, a)| match &a.kind {
+                    config::ArchetypeKind::Seed(sa) if sa.grows_into == self.name => Some((ah, sa)),
+                    _ => None,
+                })
+        };
+        // prefer the plain seed over any hybrids that grow into the same thing
+        seeds()
+            .find(|(_, sa)| sa.hybrid_of.is_none())
+            .or_else(|| seeds().next())
+            .map(|(ah, _)| ah)
+    }
+
+    /// Turns this plant back into a seed of the kind it grows from,
+    /// keeping its lineage and traits but losing its xp.
+    pub fn into_seed(self, owner_id: String) -> Option<Possession> {
+        let ah = self.seed_archetype_handle()?;
+
+        let mut p = Possession::new(ah, possess::Owner::farmer(owner_id));
+        let s = p.kind.seed_mut()?;
+        s.pedigree = self.pedigree;
+        s.traits = self.traits;
+        Some(p)
+    }
+
+    /// If this plant isn't crafting anything, starts on the next craft in its queue.
+    pub fn start_next_craft(&mut self) {
+        if self.craft.is_none() && !self.craft_queue.is_empty() {
+            self.craft = Some(self.craft_queue.remove(0));
+        }
+    }
+
+    /// Empties this plant's craft queue, giving back to `owner`
+    /// whatever was reserved for the crafts that won't ever get to happen now.
+    pub fn refund_queued_crafts(&mut self, owner: &str) -> Vec<Possession> {
+        self.craft_queue
+            .drain(..)
+            .flat_map(|queued| queued.needs)
+            .flat_map(|(count, ah)| {
+                (0..count)
+                    .map(move |_| Possession::new(ah, possess::Owner::crafter(owner.to_string())))
+            })
+            .collect()
+    }
+
     pub fn current_advancement(&self) -> &config::PlantAdvancement {
         self.advancements.current(self.xp)
     }
@@ -371,6 +371,17 @@ impl Plant {
         self.advancements.next(self.xp)
     }
 
+    /// Sums this plant's unlocked advancements and any extras (like neighbor bonuses),
+    /// taking the traits it inherited from its seed into account.
+    pub fn advancements_sum<'a>(
+        &'a self,
+        extra_advancements: impl Iterator<Item = &'a config::PlantAdvancement>,
+    ) -> config::PlantAdvancementSum {
+        self.advancements
+            .sum(self.xp, extra_advancements)
+            .with_traits(self.traits)
+    }
+
     pub fn increment_xp(&mut self) -> Option<&'static config::PlantAdvancement> {
         CONFIG
             .plant_archetypes
@@ -414,6 +425,26 @@ impl Plant {
                 Some(c) => Some(Craft::from_av(c)?),
                 None => None,
             },
+            craft_queue: match m.get("craft_queue") {
+                Some(q) => q
+                    .l
+                    .as_ref()
+                    .ok_or(WronglyTypedField("craft_queue"))?
+                    .iter()
+                    .map(|c| Craft::from_av(c))
+                    .collect::<Result<_, _>>()?,
+                None => vec![],
+            },
+            effects: match m.get("effects") {
+                Some(e) => e
+                    .l
+                    .as_ref()
+                    .ok_or(WronglyTypedField("effects"))?
+                    .iter()
+                    .map(|e| Effect::from_av(e))
+                    .collect::<Result<_, _>>()?,
+                None => vec![],
+            },
             pedigree: m
                 .get("pedigree")
                 .ok_or(MissingField("pedigree"))?
@@ -432,6 +463,11 @@ impl Plant {
                     None
                 })
                 .collect(),
+            // plants from before traits existed are perfectly average
+            traits: match m.get("traits") {
+                Some(av) => possess::seed::PlantTraits::from_av(av)?,
+                None => Default::default(),
+            },
         })
     }
 
@@ -467,6 +503,7 @@ impl Plant {
                             ..Default::default()
                         },
                     ),
+                    ("traits".to_string(), self.traits.into()),
                 ]
                 .iter()
                 .cloned()
@@ -475,6 +512,24 @@ impl Plant {
                 if let Some(craft) = self.craft {
                     attrs.insert("craft".to_string(), craft.into_av());
                 }
+                if !self.craft_queue.is_empty() {
+                    attrs.insert(
+                        "craft_queue".to_string(),
+                        AttributeValue {
+                            l: Some(self.craft_queue.into_iter().map(|c| c.into_av()).collect()),
+                            ..Default::default()
+                        },
+                    );
+                }
+                if !self.effects.is_empty() {
+                    attrs.insert(
+                        "effects".to_string(),
+                        AttributeValue {
+                            l: Some(self.effects.into_iter().map(|e| e.into_av()).collect()),
+                            ..Default::default()
+                        },
+                    );
+                }
 
                 attrs
             }),
@@ -518,6 +573,12 @@ pub struct Hacksteader {
     pub land: Vec<Tile>,
     pub inventory: Vec<Possession>,
     pub gotchis: Vec<Possessed<possess::Gotchi>>,
+    /// The orders this user has standing on the hackmarket.
+    pub buy_orders: Vec<BuyOrder>,
+    /// The things this user has up for auction.
+    pub auctions: Vec<Auction>,
+    /// The trades this user has offered to other hacksteaders.
+    pub trade_offers: Vec<TradeOffer>,
 }
 impl Hacksteader {
     pub async fn new_in_db(db: &DynamoDbClient, user_id: String) -> Result<(), String> {
@@ -730,6 +791,9 @@ impl Hacksteader {
         let mut gotchis = Vec::new();
         let mut inventory = Vec::new();
         let mut land = Vec::new();
+        let mut buy_orders = Vec::new();
+        let mut auctions = Vec::new();
+        let mut trade_offers = Vec::new();
 
         for item in items.iter() {
             use AttributeParseError::*;
@@ -760,6 +824,16 @@ impl Hacksteader {
                 Category::Land => {
                     land.push(Tile::from_item(item).map_err(|e| format!("tile parse err: {}", e))?)
                 }
+                Category::BuyOrder => buy_orders.push(
+                    BuyOrder::from_item(item).map_err(|e| format!("buy order parse err: {}", e))?,
+                ),
+                Category::Auction => auctions.push(
+                    Auction::from_item(item).map_err(|e| format!("auction parse err: {}", e))?,
+                ),
+                Category::TradeOffer => trade_offers.push(
+                    TradeOffer::from_item(item)
+                        .map_err(|e| format!("trade offer parse err: {}", e))?,
+                ),
                 _ => unreachable!(),
             }
         }
@@ -770,6 +844,9 @@ impl Hacksteader {
             gotchis,
             inventory,
             land,
+            buy_orders,
+            auctions,
+            trade_offers,
         })
     }
 }
@@ -5,6 +5,7 @@
 use config::CONFIG;
 use core::config;
 use core::frontend::emojify;
+use core::market::{ListingKind, MarketQuery, MarketSort, Watch};
 use core::possess;
 use core::{Category, Key};
 use crossbeam_channel::Sender;
@@ -23,6 +24,8 @@ pub mod banker;
 pub mod event;
 pub mod hacksteader;
 pub mod market;
+pub mod shop;
+pub mod trade;
 mod yank_config;
 
 use hacksteader::Hacksteader;
@@ -42,6 +45,8 @@ pub fn dyn_db() -> DynamoDbClient {
 const FARM_CYCLE_SECS: u64 = 5;
 const FARM_CYCLE_MILLIS: u64 = FARM_CYCLE_SECS * 1000;
 const FARM_CYCLES_PER_MIN: u64 = 60 / FARM_CYCLE_SECS;
+const AUCTION_SETTLE_SECS: u64 = 60;
+const LISTING_EXPIRY_SECS: u64 = 60 * 5;
 
 lazy_static::lazy_static! {
     pub static ref TOKEN: String = std::env::var("TOKEN").unwrap();
@@ -181,8 +186,9 @@ impl PossessionOverviewPage {
                 })
                 .collect(),
         };
-        // caching this so I can use it later after .into_iter() is called
+        // caching these so I can use them later after .into_iter() is called
         let inventory_len = inventory.len();
+        let unsold = inventory.iter().filter(|p| p.sale.is_none()).count();
 
         let mut blocks = inventory
             .into_iter()
@@ -195,10 +201,15 @@ impl PossessionOverviewPage {
                         "{} _{}_{}",
                         emojify(&item_name),
                         item_name,
-                        if let Some(core::market::Sale { price, .. }) = possession.sale {
+                        if let Some(core::market::Sale { price, expires, .. }) = possession.sale {
                             match source {
-                                PossessionOverviewSource::Hacksteader(..) => {
-                                    format!(" (selling at *{}gp*)", price)
+                                PossessionOverviewSource::Hacksteader(..) => match expires {
+                                    Some(expires) => format!(
+                                        " (selling at *{}gp*, {})",
+                                        price,
+                                        listing_time_left(expires)
+                                    ),
+                                    None => format!(" (selling at *{}gp*)", price),
                                 },
                                 PossessionOverviewSource::Market(..) => {
                                     format!(
@@ -268,6 +279,48 @@ impl PossessionOverviewPage {
             }));
         }
 
+        if let Ok(ah) = CONFIG.find_possession_handle(item_name) {
+            let mut buttons = vec![];
+            match source {
+                PossessionOverviewSource::Market(..) => {
+                    buttons.push(json!({
+                        "type": "button",
+                        "text": plain_text("Price History"),
+                        "value": ah.to_string(),
+                        "action_id": "market_stats",
+                    }));
+                    if *credentials == Credentials::Hacksteader && inventory_len > 1 {
+                        buttons.push(json!({
+                            "type": "button",
+                            "text": plain_text("Buy Many"),
+                            "value": ah.to_string(),
+                            "action_id": "bulk_buy",
+                        }));
+                    }
+                }
+                PossessionOverviewSource::Hacksteader(..) => {
+                    if *credentials == Credentials::Owner && interactivity.write() && unsold > 1 {
+                        buttons.push(json!({
+                            "type": "button",
+                            "text": plain_text("Sell Many"),
+                            "value": ah.to_string(),
+                            "action_id": "bulk_sell",
+                        }));
+                    }
+                }
+            }
+
+            if !buttons.is_empty() {
+                blocks.insert(
+                    0,
+                    json!({
+                        "type": "actions",
+                        "elements": buttons,
+                    }),
+                );
+            }
+        }
+
         Ok(blocks)
     }
 }
@@ -392,6 +445,15 @@ impl PossessionPage {
         if let Some(g) = possession.kind.gotchi() {
             text_fields.push(("base happiness", g.base_happiness.to_string()));
         }
+        if let Some(s) = possession.kind.seed() {
+            text_fields.push((
+                "traits",
+                format!(
+                    "x{:.3} yield speed, x{:.3} yield size, x{:.3} xp rate",
+                    s.traits.yield_speed, s.traits.yield_size, s.traits.xp_rate
+                ),
+            ));
+        }
 
         let text = text_fields
             .iter()
@@ -414,7 +476,52 @@ impl PossessionPage {
             }
         }));
 
-        blocks.push(actions("possession", &[("Give", None), ("Sell", None)]));
+        blocks.push(actions(
+            "possession",
+            &[("Give", None), ("Sell", None), ("Auction", None)],
+        ));
+        // studying a blueprint uses it up, so it can't be up for sale at the time
+        if possession.sale.is_none()
+            && CONFIG
+                .possession_archetypes
+                .get(possession.archetype_handle)
+                .and_then(|a| a.kind.keepsake()?.blueprint.as_ref())
+                .is_some()
+        {
+            blocks.push(actions(
+                "blueprint",
+                &[("Study", Some(json!(possession.id.to_simple().to_string())))],
+            ));
+        }
+        // anyone can look at where something came from, and what it's good for
+        blocks.push(json!({
+            "type": "actions",
+            "elements": [
+                {
+                    "type": "button",
+                    "text": plain_text("History"),
+                    "value": serde_json::to_string(&possession.key()).unwrap(),
+                    "action_id": "possession_history",
+                },
+                {
+                    "type": "button",
+                    "text": plain_text("Encyclopedia"),
+                    "value": serde_json::to_string(&[possession.archetype_handle]).unwrap(),
+                    "action_id": "encyclopedia",
+                }
+            ]
+        }));
+        if interactivity.write() {
+            blocks.push(json!({
+                "type": "actions",
+                "elements": [{
+                    "type": "button",
+                    "text": plain_text("Fill Buy Order"),
+                    "value": serde_json::to_string(&possession.key()).unwrap(),
+                    "action_id": "buy_order_fill_list",
+                }]
+            }));
+        }
 
         if let Some(g) = possession.kind.gotchi() {
             blocks.push(comment(format!(
@@ -472,102 +579,967 @@ async fn update_home_tab(hs: Option<Hacksteader>, user_id: String) -> Result<(),
     Ok(())
 }
 
-fn progress_bar(size: usize, progress_ratio: f32) -> String {
-    format!(
-        "`\u{2062}{}\u{2062}`",
-        (0..size)
-            .map(|i| {
-                if (i as f32 / size as f32) < progress_ratio {
-                    '\u{2588}'
-                } else {
-                    ' '
-                }
-            })
-            .collect::<String>()
-    )
+/// Renders who has owned a possession and how they came to own it, oldest first.
+fn ownership_log_blocks(ownership_log: &[possess::Owner]) -> Vec<Value> {
+    use possess::Acquisition::*;
+
+    std::iter::once(json!({
+        "type": "section",
+        "text": mrkdwn("*Ownership Log*"),
+    }))
+    .chain(ownership_log.iter().enumerate().map(|(i, owner)| {
+        comment(format!(
+            "*{}.* <@{}> {}",
+            i + 1,
+            owner.id,
+            match owner.acquisition {
+                Trade => "got it in a trade".to_string(),
+                Farmed => "farmed it".to_string(),
+                Crafted => "crafted it".to_string(),
+                Purchase { price } => format!("bought it for *{}gp*", price),
+            }
+        ))
+    }))
+    .collect()
 }
 
-fn hackstead_blocks(
-    hs: Hacksteader,
-    interactivity: Interactivity,
-    credentials: Credentials,
+/// Renders the generation-by-generation lineage of a seed or plant.
+fn pedigree_blocks(pedigree: &[possess::seed::SeedGrower]) -> Vec<Value> {
+    let mut generation = 0;
+
+    std::iter::once(json!({
+        "type": "section",
+        "text": mrkdwn(format!(
+            "*Lineage*\n_{} generations in total_",
+            pedigree.iter().map(|sg| sg.generations).sum::<u64>()
+        )),
+    }))
+    .chain(pedigree.iter().map(|sg| {
+        let first = generation + 1;
+        generation += sg.generations;
+        comment(match sg.generations {
+            0 => format!("<@{}> got it before it had ever been grown", sg.id),
+            1 => format!("*Generation {}:* grown by <@{}>", first, sg.id),
+            _ => format!(
+                "*Generations {}-{}:* grown by <@{}>",
+                first, generation, sg.id
+            ),
+        })
+    }))
+    .collect()
+}
+
+/// An encyclopedia entry for the last possession archetype in `trail`, listing how
+/// to get it and what it's used for. `trail` is every entry the user has
+/// expanded to get here, so that they can go back. Only the hidden recipes
+/// for the archetypes in `discovered` are shown in full.
+fn encyclopedia_blocks(
+    trail: &[config::ArchetypeHandle],
+    discovered: &[config::ArchetypeHandle],
 ) -> Vec<Value> {
-    use humantime::format_duration;
-    use std::time::SystemTime;
+    let name = |ah: config::ArchetypeHandle| {
+        CONFIG
+            .possession_archetypes
+            .get(ah)
+            .map(|a| a.name.as_str())
+            .unwrap_or("unknown")
+    };
+    let arch = match trail.last().and_then(|&ah| CONFIG.possession_archetypes.get(ah)) {
+        Some(arch) => arch,
+        None => return vec![comment("There's nothing in the encyclopedia about that.")],
+    };
+    let ah = *trail.last().unwrap();
 
-    // TODO: with_capacity optimization
-    let mut blocks: Vec<Value> = Vec::new();
+    let mut blocks = vec![json!({
+        "type": "section",
+        "text": mrkdwn(format!(
+            "{} *{}*\n_{}_",
+            emojify(&arch.name),
+            arch.name,
+            arch.description
+        )),
+        "accessory": {
+            "type": "image",
+            "image_url": format!(
+                "http://{}/gotchi/img/{}/{}.png",
+                *URL,
+                format!("{:?}", arch.kind.category()).to_lowercase(),
+                filify(&arch.name)
+            ),
+            "alt_text": "encyclopedia illustration",
+        }
+    })];
+    let mut header_buttons = vec![];
+    if trail.len() > 1 {
+        blocks.push(comment(
+            trail.iter().map(|&ah| name(ah)).collect::<Vec<_>>().join(" > "),
+        ));
+        header_buttons.push(json!({
+            "type": "button",
+            "text": plain_text(format!("Back to {}", name(trail[trail.len() - 2]))),
+            "value": serde_json::to_string(&trail[..trail.len() - 1]).unwrap(),
+            "action_id": "encyclopedia",
+        }));
+    }
+    if CONFIG
+        .recipes_making(ah)
+        .iter()
+        .any(|(_, r)| r.discovered_by(discovered))
+    {
+        header_buttons.push(json!({
+            "type": "button",
+            "style": "primary",
+            "text": plain_text("Plan Crafting"),
+            "value": serde_json::to_string(&PlanFor::Archetype(ah)).unwrap(),
+            "action_id": "crafting_planner",
+        }));
+    }
+    if !header_buttons.is_empty() {
+        blocks.push(json!({
+            "type": "actions",
+            "elements": header_buttons,
+        }));
+    }
 
-    let neighbor_bonuses = hs.neighbor_bonuses();
-    let Hacksteader {
-        profile,
-        mut inventory,
-        land,
-        user_id,
-        gotchis,
-        ..
-    } = hs;
+    blocks.push(json!({ "type": "divider" }));
+    blocks.push(json!({
+        "type": "section",
+        "text": mrkdwn("*Yielded by*"),
+    }));
+    let yielders = CONFIG.yielders_of(ah);
+    if yielders.is_empty() {
+        blocks.push(comment("_No plant yields this._"));
+    }
+    for (plant_ah, level) in yielders {
+        if let Some(plant) = CONFIG.plant_archetypes.get(plant_ah) {
+            blocks.push(comment(format!(
+                "{} *{}* from level *{}*",
+                emojify(&plant.name),
+                plant.name,
+                level
+            )));
+        }
+    }
 
-    let hs_adv = profile.current_advancement();
-    let next_hs_adv = profile.next_advancement();
-    let hs_adv_sum = profile.advancements_sum();
+    // every button in a view needs its own action_id
+    let mut buttons = 0;
+    let mut recipe_section = |title: &str, recipes: Vec<(config::RecipeSource, config::Recipe<_>)>| {
+        let mut blocks = vec![
+            json!({ "type": "divider" }),
+            json!({
+                "type": "section",
+                "text": mrkdwn(format!("*{}*", title)),
+            }),
+        ];
+        if recipes.is_empty() {
+            blocks.push(comment("_No recipes._"));
+        }
+        for (source, raw_recipe) in recipes {
+            if !raw_recipe.discovered_by(discovered) {
+                blocks.push(comment(format!(
+                    "{}\n_A hidden recipe, yet to be discovered ..._",
+                    source
+                )));
+                continue;
+            }
+
+            let related: Vec<config::ArchetypeHandle> = raw_recipe
+                .needs
+                .iter()
+                .map(|&(_, x)| x)
+                .chain(raw_recipe.catalysts.iter().copied())
+                .chain(raw_recipe.makes.outputs())
+                .filter(|&x| x != ah)
+                .fold(vec![], |mut acc, x| {
+                    if !acc.contains(&x) {
+                        acc.push(x);
+                    }
+                    acc
+                });
+            let recipe = match raw_recipe.lookup_handles() {
+                Some(r) => r,
+                None => continue,
+            };
+
+            blocks.push(json!({
+                "type": "section",
+                "text": mrkdwn(format!(
+                    "{}\nmakes {} in *{:.2} minutes*",
+                    source,
+                    recipe.makes,
+                    recipe.time / FARM_CYCLES_PER_MIN as f32
+                )),
+            }));
+            blocks.append(&mut recipe_requirement_blocks(&recipe, &[]));
+            if !related.is_empty() {
+                blocks.push(json!({
+                    "type": "actions",
+                    "elements": related
+                        .into_iter()
+                        .map(|x| {
+                            buttons += 1;
+                            json!({
+                                "type": "button",
+                                "text": plain_text(name(x)),
+                                "value": serde_json::to_string(
+                                    &trail.iter().copied().chain(Some(x)).collect::<Vec<_>>()
+                                ).unwrap(),
+                                "action_id": format!("encyclopedia_{}", buttons),
+                            })
+                        })
+                        .collect::<Vec<_>>()
+                }));
+            }
+        }
+        blocks
+    };
+    blocks.append(&mut recipe_section("Made by", CONFIG.recipes_making(ah)));
+    blocks.append(&mut recipe_section("Used in", CONFIG.recipes_using(ah)));
+
+    // slack won't show a modal with more than 100 blocks
+    blocks.truncate(100);
+    blocks
+}
+
+/// What the crafting planner has been asked to plan out.
+#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize)]
+enum PlanFor {
+    /// Crafting this archetype, with whichever recipe can be relied upon to make it
+    Archetype(config::ArchetypeHandle),
+    /// Crafting the recipe at this index in `CONFIG.recipes()`
+    Recipe(usize),
+}
+
+/// Lays out everything `user_id` would need to do to craft what they're planning for,
+/// including what they'd have to buy off of the market, and for how much.
+async fn crafting_planner_blocks(plan_for: PlanFor, user_id: String) -> Result<Vec<Value>, String> {
+    use core::planner::CraftPlan;
+
+    let db = dyn_db();
+    let (inventory, discovered) = Hacksteader::from_db(&db, user_id.clone())
+        .await
+        .map(|hs| (hs.inventory, hs.profile.discovered_recipes))
+        .unwrap_or_default();
+    let (makes, plan) = match plan_for {
+        PlanFor::Archetype(target) => {
+            let arch = CONFIG
+                .possession_archetypes
+                .get(target)
+                .ok_or_else(|| format!("can't plan crafting unknown archetype {}", target))?;
+            (
+                format!("a {} _{}_", emojify(&arch.name), arch.name),
+                CraftPlan::new(target, &inventory, &discovered),
+            )
+        }
+        PlanFor::Recipe(index) => {
+            let (source, recipe) = CONFIG
+                .recipes()
+                .into_iter()
+                .nth(index)
+                .ok_or_else(|| format!("can't plan crafting unknown recipe {}", index))?;
+            let makes = recipe
+                .makes
+                .clone()
+                .lookup_handles()
+                .ok_or_else(|| format!("recipe {} makes unknown archetypes", index))?
+                .to_string();
+            (
+                makes,
+                CraftPlan::for_recipe(source, recipe, &inventory, &discovered),
+            )
+        }
+    };
+
+    let mut blocks = vec![
+        json!({
+            "type": "section",
+            "text": mrkdwn(format!(
+                "*Crafting plan for {}*\nstarting from what's in your inventory now",
+                makes
+            )),
+        }),
+        json!({ "type": "divider" }),
+        json!({
+            "type": "section",
+            "text": mrkdwn("*Crafts*"),
+        }),
+    ];
+    if plan.crafts.is_empty() {
+        blocks.push(comment("_Nothing to craft, it'll all have to be bought._"));
+    }
+    for (times, source, recipe) in plan.crafts.iter().cloned() {
+        if let Some(recipe) = recipe.lookup_handles() {
+            blocks.push(comment(format!(
+                "*{}x* {} on {}, *{:.2} minutes* each",
+                times,
+                recipe.makes,
+                source,
+                recipe.time / FARM_CYCLES_PER_MIN as f32
+            )));
+        }
+    }
+
+    // only search each category of the market once
+    let mut listings: Vec<(core::market::Sale, Possession)> = vec![];
+    let mut searched: Vec<Category> = vec![];
+    for &(_, ah) in plan.shortfall.iter() {
+        let cat = match CONFIG.possession_archetypes.get(ah) {
+            Some(a) => a.kind.category(),
+            None => continue,
+        };
+        if !searched.contains(&cat) {
+            searched.push(cat);
+            listings.append(&mut market::market_search(&db, cat).await?);
+        }
+    }
+    listings.sort_by_key(|(sale, _)| sale.price);
 
+    blocks.push(json!({ "type": "divider" }));
     blocks.push(json!({
         "type": "section",
-        "text": mrkdwn(format!(
-            "*_<@{}>'s {}_* - *{}lvl* - _{}xp_",
-            user_id,
-            hs_adv.achiever_title,
-            profile.advancements.current_position(profile.xp),
-            profile.xp,
-        )),
+        "text": mrkdwn("*Shopping List*"),
     }));
+    if plan.shortfall.is_empty() {
+        blocks.push(comment("_You've already got everything you need!_"));
+    }
+    let mut total_gp = 0;
+    let mut unavailable = 0;
+    for &(count, ah) in plan.shortfall.iter() {
+        let name = CONFIG
+            .possession_archetypes
+            .get(ah)
+            .map(|a| a.name.as_str())
+            .unwrap_or("unknown");
+        let cheapest: Vec<_> = listings
+            .iter()
+            .filter(|(_, p)| p.archetype_handle == ah && p.steader != user_id)
+            .take(count)
+            .cloned()
+            .collect();
+        let cost: u64 = cheapest.iter().map(|(sale, _)| sale.price).sum();
+        total_gp += cost;
+        unavailable += count - cheapest.len();
 
-    blocks.push(comment(format!(
-        "founded {} ago (roughly)",
-        format_duration(SystemTime::now().duration_since(profile.joined).unwrap()),
-    )));
-    if let Some(na) = next_hs_adv {
-        blocks.push({
-            let (have, need) = (profile.xp - hs_adv_sum.xp, na.xp);
+        blocks.push(json!({
+            "type": "section",
+            "text": mrkdwn(format!(
+                "*{}* {} _{}_\n*{}gp* for the *{}* on the market",
+                count,
+                emojify(name),
+                name,
+                cost,
+                cheapest.len()
+            )),
+        }));
+        // one section for each listing, since action_ids can't repeat within a block
+        blocks.extend(cheapest.into_iter().take(5).map(|(sale, mut possession)| {
+            let price = sale.price;
+            possession.sale.replace(sale);
             json!({
                 "type": "section",
                 "text": mrkdwn(format!(
-                    "Next: *{}*\n{}  {}xp to go\n_{}_",
-                    na.title,
-                    progress_bar(50, have as f32 / need as f32),
-                    need - have,
-                    na.description
+                    "_{}gp from <@{}>_",
+                    price, possession.steader
                 )),
+                "accessory": {
+                    "type": "button",
+                    "text": plain_text("View"),
+                    "value": serde_json::to_string(&(
+                        possession,
+                        Interactivity::Buy,
+                        Credentials::Hacksteader
+                    )).unwrap(),
+                    "action_id": "push_possession_page",
+                }
             })
-        });
+        }));
     }
-    blocks.push(comment(format!("Last Advancement: \"{}\"", hs_adv.title)));
-    blocks.push(comment(format!(
-        "If you had enough Land Deeds, you could buy {} pieces of land",
-        hs_adv_sum.land
-    )));
 
     blocks.push(json!({ "type": "divider" }));
-    let tiles_owned = land.len();
-    for tile in land.into_iter() {
-        if let Some(p) = tile.plant.as_ref() {
-            let neighbor_bonuses = neighbor_bonuses
-                .clone()
-                .bonuses_for_plant(
-                    tile.id,
-                    p.archetype_handle
-                );
-            let sum = p.advancements.sum(p.xp, neighbor_bonuses.iter());
-            let unboosted_sum = p.advancements.raw_sum(p.xp);
-            let ca = p.current_advancement();
+    blocks.push(json!({
+        "type": "section",
+        "text": mrkdwn(format!(
+            "*Total: {}gp*{}",
+            total_gp,
+            if unavailable > 0 {
+                format!(
+                    "\n_{} of the things you need aren't on the market right now._",
+                    unavailable
+                )
+            } else {
+                "".to_string()
+            }
+        )),
+    }));
 
-            blocks.push(json!({
+    blocks.truncate(100);
+    Ok(blocks)
+}
+
+/// The buy orders someone could fill with this possession of theirs.
+fn buy_order_fill_blocks(possession: &Possession, orders: &[core::market::BuyOrder]) -> Vec<Value> {
+    let mut blocks: Vec<Value> = orders
+        .iter()
+        .filter(|o| o.buyer != possession.steader)
+        .take(20)
+        .map(|order| {
+            let fee = CONFIG
+                .market
+                .fees
+                .fee(possession.kind.category(), order.price);
+            let (earnings, royalties) = market::sale_shares(possession, order.price, fee);
+            json!({
                 "type": "section",
-                "text": mrkdwn({
-                    let mut s = String::new();
-                    s.push_str(&format!(
-                        "*{}* - _{}_ - *{}lvl* - {}xp\n\n",
+                "text": mrkdwn(format!(
+                    "<@{}> wants *{}* more, for *{}gp* each",
+                    order.buyer, order.quantity, order.price
+                )),
+                "accessory": {
+                    "type": "button",
+                    "style": "primary",
+                    "text": plain_text(format!("Sell for {}gp", earnings)),
+                    "value": serde_json::to_string(&(order.id, possession.key())).unwrap(),
+                    "action_id": "buy_order_fill",
+                    "confirm": {
+                        "title": plain_text("Sell it?"),
+                        "text": mrkdwn(format!(
+                            "<@{}> will get your {} *{}*, and you'll get *{}gp* after the market fee{}.",
+                            order.buyer,
+                            emojify(&possession.name),
+                            possession.name,
+                            earnings,
+                            if royalties.is_empty() { "" } else { " and royalties" },
+                        )),
+                        "deny": plain_text("Keep it"),
+                        "confirm": plain_text("Sell it!"),
+                    }
+                }
+            })
+        })
+        .collect();
+
+    if blocks.is_empty() {
+        blocks.push(comment(format!(
+            "Nobody's looking to buy a {} right now. Check back later!",
+            possession.name
+        )));
+    }
+    blocks
+}
+
+/// Asks what price to put something of the given category up on the market for.
+fn possession_sell_blocks(category: Category) -> Vec<Value> {
+    vec![
+        json!({
+            "type": "input",
+            "block_id": "possession_sell_price_block",
+            "label": plain_text("Price (gp)"),
+            "element": {
+                "type": "plain_text_input",
+                "action_id": "possession_sell_price_input",
+                "placeholder": plain_text("Price Item"),
+                "initial_value": "50",
+            }
+        }),
+        json!({ "type": "divider" }),
+        comment(format!(
+            "As a form of confirmation, you'll get an invoice to pay before your Item goes up on the market. \
+            To fund Harvests and to encourage Hacksteaders to keep prices sensible, \
+            this invoice is {}.",
+            CONFIG.market.fees.describe(category)
+        )),
+    ]
+}
+
+/// How long a listing has left before it comes off the market.
+fn listing_time_left(expires: std::time::SystemTime) -> String {
+    match expires
+        .duration_since(std::time::SystemTime::now())
+        .map(|left| left.as_secs())
+    {
+        Ok(secs) if secs >= 60 * 60 => format!(
+            "expires in {}",
+            humantime::format_duration(std::time::Duration::from_secs(secs / 3600 * 3600))
+        ),
+        Ok(secs) if secs >= 60 => format!("expires in {} minutes", secs / 60),
+        _ => "expiring any moment now".to_string(),
+    }
+}
+
+/// How long an auction has left to go, in words.
+fn auction_time_left(auction: &core::market::Auction) -> String {
+    match auction.time_left().map(|left| left.as_secs() / 60) {
+        Some(0) => "less than a minute left".to_string(),
+        Some(minutes) => format!(
+            "{} left",
+            humantime::format_duration(std::time::Duration::from_secs(minutes * 60))
+        ),
+        None => "ending any moment now".to_string(),
+    }
+}
+
+/// Where the bidding on an auction is at, and how much the next bid has to be.
+fn auction_bid_blocks(auction: &core::market::Auction) -> Vec<Value> {
+    vec![
+        json!({
+            "type": "section",
+            "text": mrkdwn(format!(
+                "<@{}> is auctioning a {} *{}*\n{}",
+                auction.seller,
+                emojify(auction.name()),
+                auction.name(),
+                match &auction.high_bid {
+                    Some(bid) => format!("Highest bid: *{}gp* by <@{}>", bid.amount, bid.bidder),
+                    None => format!("No bids yet, reserve price: *{}gp*", auction.reserve),
+                }
+            )),
+            "accessory": {
+                "type": "image",
+                "image_url": format!(
+                    "http://{}/gotchi/img/{}/{}.png",
+                    *URL,
+                    auction.possession_category,
+                    filify(auction.name())
+                ),
+                "alt_text": "hackagotchi img",
+            }
+        }),
+        comment(auction_time_left(auction)),
+        json!({
+            "type": "input",
+            "block_id": "auction_bid_amount_block",
+            "label": plain_text("Your bid (gp)"),
+            "element": {
+                "type": "plain_text_input",
+                "action_id": "auction_bid_amount_input",
+                "initial_value": auction.min_bid().to_string(),
+            }
+        }),
+        json!({ "type": "divider" }),
+        comment(concat!(
+            "You'll get an invoice for your bid. ",
+            "Banker holds onto that GP until the auction ends, ",
+            "and refunds it if someone outbids you."
+        )),
+    ]
+}
+
+/// Options for picking which of a hacksteader's possessions to trade.
+/// Anything that's up on the market can't be traded.
+fn trade_options(hs: Hacksteader) -> Vec<Value> {
+    hs.gotchis
+        .into_iter()
+        .map(|g| g.into_possession())
+        .chain(hs.inventory)
+        .filter(|p| p.sale.is_none())
+        .take(100)
+        .map(|p| {
+            json!({
+                "text": plain_text(format!("{} {}", emojify(&p.name), p.nickname())),
+                "value": serde_json::to_string(&p.key()).unwrap(),
+            })
+        })
+        .collect()
+}
+
+/// Asks what each side of a trade with `partner` will give up.
+fn trade_offer_blocks(partner: &str, mine: Vec<Value>, theirs: Vec<Value>) -> Vec<Value> {
+    let picker = |name: &str, label: String, options: Vec<Value>| {
+        if options.is_empty() {
+            return comment(format!("_{} nothing to trade._", label));
+        }
+        json!({
+            "type": "input",
+            "optional": true,
+            "block_id": format!("trade_{}_block", name),
+            "label": plain_text(label),
+            "element": {
+                "type": "multi_static_select",
+                "action_id": format!("trade_{}_input", name),
+                "placeholder": plain_text("Pick some things"),
+                "options": options,
+            }
+        })
+    };
+
+    vec![
+        json!({
+            "type": "section",
+            "text": mrkdwn(format!("*Trading with <@{}>*", partner)),
+        }),
+        picker("offered", "You give".to_string(), mine),
+        picker("requested", "They give".to_string(), theirs),
+        json!({
+            "type": "input",
+            "optional": true,
+            "block_id": "trade_gp_block",
+            "label": plain_text("GP you'll throw in"),
+            "element": {
+                "type": "plain_text_input",
+                "action_id": "trade_gp_input",
+                "initial_value": "0",
+            }
+        }),
+        json!({ "type": "divider" }),
+        comment(concat!(
+            "If you throw in GP, you'll get an invoice for it before your offer is sent. ",
+            "Banker holds onto that GP until the trade goes through, ",
+            "or refunds it if the trade is called off."
+        )),
+    ]
+}
+
+/// Throws out a trade offer on behalf of either side of it,
+/// refunding any GP being held for it and letting the other side know.
+async fn call_off_trade(offer_id: uuid::Uuid, user_id: String) -> Result<(), String> {
+    let offer = match trade::call_off(&dyn_db(), offer_id, user_id.clone()).await? {
+        Some(offer) => offer,
+        None => return Ok(()),
+    };
+    trade::refund(&offer).await?;
+
+    if offer.sent {
+        let (other, text) = if user_id == offer.proposer {
+            (
+                offer.recipient.clone(),
+                format!("<@{}> has called off their trade offer.", offer.proposer),
+            )
+        } else {
+            (
+                offer.proposer.clone(),
+                format!("<@{}> has turned down your trade offer.", offer.recipient),
+            )
+        };
+        dm_blocks(
+            other,
+            vec![json!({ "type": "section", "text": mrkdwn(text) })],
+        )
+        .await?;
+    }
+
+    update_user_home_tab(offer.proposer).await
+}
+
+/// What something's been selling for on the market lately.
+fn market_stats_blocks(
+    ah: config::ArchetypeHandle,
+    records: &[core::market::SaleRecord],
+) -> Vec<Value> {
+    use core::market::PriceStats;
+    use humantime::format_duration;
+    use std::time::{Duration, SystemTime};
+
+    let name = CONFIG
+        .possession_archetypes
+        .get(ah)
+        .map(|a| a.name.as_str())
+        .unwrap_or("unknown");
+    // to the minute, since nobody needs to know how many nanoseconds ago something sold
+    let ago = |when: SystemTime| match SystemTime::now().duration_since(when) {
+        Ok(d) if d.as_secs() >= 60 => format!(
+            "{} ago",
+            format_duration(Duration::from_secs(d.as_secs() / 60 * 60))
+        ),
+        _ => "just now".to_string(),
+    };
+
+    let mut blocks = vec![json!({
+        "type": "section",
+        "text": mrkdwn(match records.first() {
+            Some(last) => format!(
+                "{} *{}*\nLast sold for *{}gp*, {}",
+                emojify(name),
+                name,
+                last.price,
+                ago(last.sold_at)
+            ),
+            None => format!("{} *{}*\nNone sold this week.", emojify(name), name),
+        }),
+    })];
+
+    for &(window, days) in [("Past day", 1), ("Past week", 7)].iter() {
+        let since = SystemTime::now() - Duration::from_secs(60 * 60 * 24 * days);
+        let prices = records
+            .iter()
+            .filter(|r| r.sold_at >= since)
+            .map(|r| r.price)
+            .collect();
+
+        blocks.push(json!({ "type": "divider" }));
+        blocks.push(json!({
+            "type": "section",
+            "text": mrkdwn(match PriceStats::from_prices(prices) {
+                Some(s) => format!(
+                    "*{}*\n*{}* sold for *{}gp* in total\nmin *{}gp*, median *{}gp*, max *{}gp*",
+                    window, s.sales, s.volume, s.min, s.median, s.max
+                ),
+                None => format!("*{}*\n_None sold._", window),
+            }),
+        }));
+    }
+
+    if !records.is_empty() {
+        blocks.push(json!({ "type": "divider" }));
+        blocks.push(json!({
+            "type": "section",
+            "text": mrkdwn("*Recent Sales*"),
+        }));
+    }
+    for record in records.iter().take(10) {
+        blocks.push(comment(format!(
+            "<@{}> bought one from <@{}> for *{}gp*, {}",
+            record.buyer,
+            record.seller,
+            record.price,
+            ago(record.sold_at)
+        )));
+    }
+
+    blocks
+}
+
+/// How much is left of an item's effect on a plant, in words.
+fn effect_remaining(effect: &hacksteader::Effect) -> String {
+    use config::ApplicationEffect::*;
+
+    let minutes = |cycles: u64| cycles / FARM_CYCLES_PER_MIN;
+    match effect.application_effect() {
+        Some(TimeIncrease {
+            extra_cycles,
+            duration_cycles,
+        }) => format!(
+            "~{:.1} hours pass in the next {} minutes",
+            effect.remaining as f32 / FARM_CYCLES_PER_MIN as f32 / 60.0,
+            minutes(effect.remaining / (extra_cycles / duration_cycles).max(1)),
+        ),
+        Some(YieldSizeBoost { multiplier, .. }) => format!(
+            "{}x yields for {} more minutes",
+            multiplier,
+            minutes(effect.remaining)
+        ),
+        Some(XpBoost { extra_xp, .. }) => format!(
+            "{}x xp for {} more minutes",
+            extra_xp + 1,
+            minutes(effect.remaining)
+        ),
+        Some(FinishCraft) => format!("finishes the next {} craft(s)", effect.remaining),
+        Some(GuaranteedSeed) => format!("a seed in the next {} yield(s)", effect.remaining),
+        None => "unknown effect".to_string(),
+    }
+}
+
+/// Lets someone know they've found the hidden recipes for making `ah`.
+fn recipe_discovery_blocks(ah: config::ArchetypeHandle) -> [Value; 2] {
+    let name = CONFIG
+        .possession_archetypes
+        .get(ah)
+        .map(|a| a.name.as_str())
+        .unwrap_or("unknown");
+    [
+        json!({
+            "type": "section",
+            "text": mrkdwn(format!(
+                concat!(
+                    ":scroll: You've discovered a hidden recipe for {} *{}*!\n",
+                    "It'll show up alongside your other recipes from now on."
+                ),
+                emojify(name),
+                name
+            )),
+        }),
+        comment("EUREKA"),
+    ]
+}
+
+/// Lists what a recipe uses up, what it needs on hand, and what's missing to craft it.
+fn recipe_requirement_blocks(
+    recipe: &config::Recipe<&'static config::Archetype>,
+    missing: &[config::MissingRequirement],
+) -> Vec<Value> {
+    let mut blocks: Vec<Value> = recipe
+        .needs
+        .iter()
+        .map(|(count, resource)| {
+            comment(format!(
+                "*{}* {} _{}_",
+                count,
+                emojify(&resource.name),
+                resource.name
+            ))
+        })
+        .chain(recipe.catalysts.iter().map(|catalyst| {
+            comment(format!(
+                "{} _{}_ _(not used up)_",
+                emojify(&catalyst.name),
+                catalyst.name
+            ))
+        }))
+        .collect();
+
+    if recipe.min_level > 0 {
+        blocks.push(comment(format!("requires level *{}*", recipe.min_level)));
+    }
+    if !missing.is_empty() {
+        blocks.push(comment(format!(
+            "_Missing:_ {}",
+            missing
+                .iter()
+                .map(|m| m.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )));
+    }
+
+    blocks
+}
+
+fn progress_bar(size: usize, progress_ratio: f32) -> String {
+    format!(
+        "`\u{2062}{}\u{2062}`",
+        (0..size)
+            .map(|i| {
+                if (i as f32 / size as f32) < progress_ratio {
+                    '\u{2588}'
+                } else {
+                    ' '
+                }
+            })
+            .collect::<String>()
+    )
+}
+
+fn hackstead_blocks(
+    hs: Hacksteader,
+    interactivity: Interactivity,
+    credentials: Credentials,
+) -> Vec<Value> {
+    use humantime::format_duration;
+    use std::time::SystemTime;
+
+    // TODO: with_capacity optimization
+    let mut blocks: Vec<Value> = Vec::new();
+
+    let neighbor_bonuses = hs.neighbor_bonuses();
+    let Hacksteader {
+        profile,
+        mut inventory,
+        land,
+        user_id,
+        gotchis,
+        trade_offers,
+        ..
+    } = hs;
+
+    let hs_adv = profile.current_advancement();
+    let next_hs_adv = profile.next_advancement();
+    let mut hs_adv_sum = profile.advancements_sum();
+    hs_adv_sum
+        .recipes
+        .retain(|r| r.discovered_by(&profile.discovered_recipes));
+
+    blocks.push(json!({
+        "type": "section",
+        "text": mrkdwn(format!(
+            "*_<@{}>'s {}_* - *{}lvl* - _{}xp_",
+            user_id,
+            hs_adv.achiever_title,
+            profile.advancements.current_position(profile.xp),
+            profile.xp,
+        )),
+    }));
+
+    blocks.push(comment(format!(
+        "founded {} ago (roughly)",
+        format_duration(SystemTime::now().duration_since(profile.joined).unwrap()),
+    )));
+    if let Some(na) = next_hs_adv {
+        blocks.push({
+            let (have, need) = (profile.xp - hs_adv_sum.xp, na.xp);
+            json!({
+                "type": "section",
+                "text": mrkdwn(format!(
+                    "Next: *{}*\n{}  {}xp to go\n_{}_",
+                    na.title,
+                    progress_bar(50, have as f32 / need as f32),
+                    need - have,
+                    na.description
+                )),
+            })
+        });
+    }
+    blocks.push(comment(format!("Last Advancement: \"{}\"", hs_adv.title)));
+    blocks.push(comment(format!(
+        "If you had enough Land Deeds, you could buy {} pieces of land",
+        hs_adv_sum.land
+    )));
+
+    if let Some(craft) = &profile.workbench {
+        blocks.push(json!({
+            "type": "section",
+            "text": mrkdwn(format!(
+                "*Workbench: Crafting {}*\n{}  {:.3} minutes to go",
+                CONFIG
+                    .possession_archetypes
+                    .get(craft.makes)
+                    .map(|x| x.name.as_str())
+                    .unwrap_or("unknown"),
+                progress_bar(30, 1.0 - craft.until_finish/craft.total_cycles),
+                craft.until_finish / FARM_CYCLES_PER_MIN as f32
+            ))
+        }));
+    } else if !hs_adv_sum.recipes.is_empty() {
+        let level = profile.advancements.current_position(profile.xp);
+        blocks.push(json!({
+            "type": "section",
+            "text": mrkdwn(format!(
+                "*Workbench*\n*{}/{}* recipes craftable",
+                hs_adv_sum.recipes.iter().filter(|r| r.satisfies(&inventory, level)).count(),
+                hs_adv_sum.recipes.len()
+            )),
+            "accessory": {
+                "type": "button",
+                "text": plain_text("Workbench"),
+                "value": serde_json::to_string(&(
+                    &user_id,
+                    hs_adv_sum
+                        .recipes
+                        .iter()
+                        .map(|r| (r.missing(&inventory, level), r))
+                        .collect::<Vec<_>>()
+                )).unwrap(),
+                "action_id": "workbench",
+            }
+        }));
+    }
+
+    blocks.push(json!({ "type": "divider" }));
+    let tiles_owned = land.len();
+    // which plants are where, so we can tell which of them could be crossbred
+    let planted: Vec<(uuid::Uuid, String)> = land
+        .iter()
+        .filter_map(|t| Some((t.id, t.plant.as_ref()?)))
+        .filter(|(_, p)| p.ready_to_crossbreed())
+        .map(|(id, p)| (id, p.name.clone()))
+        .collect();
+    for tile in land.into_iter() {
+        if let Some(p) = tile.plant.as_ref() {
+            let neighbor_bonuses = neighbor_bonuses
+                .clone()
+                .bonuses_for_plant(
+                    tile.id,
+                    p.archetype_handle
+                );
+            let mut sum = p.advancements_sum(neighbor_bonuses.iter());
+            sum.recipes.retain(|r| r.discovered_by(&profile.discovered_recipes));
+            let unboosted_sum = p.advancements.raw_sum(p.xp);
+            let ca = p.current_advancement();
+
+            blocks.push(json!({
+                "type": "section",
+                "text": mrkdwn({
+                    let mut s = String::new();
+                    s.push_str(&format!(
+                        "*{}* - _{}_ - *{}lvl* - {}xp\n\n",
                         p.name,
                         ca.achiever_title,
                         p.advancements.current_position(p.xp),
@@ -607,10 +1579,24 @@ fn hackstead_blocks(
                     }
                 }));
             }
+            for effect in p.effects.iter() {
+                let name = CONFIG
+                    .possession_archetypes
+                    .get(effect.item_archetype_handle)
+                    .map(|x| x.name.as_str())
+                    .unwrap_or("unknown");
+                blocks.push(comment(format!(
+                    "_Effect:_ {} *{}* - {}",
+                    emojify(name),
+                    name,
+                    effect_remaining(effect)
+                )));
+            }
             if !sum.recipes.is_empty() {
-                let recipes = sum.recipes.iter().map(|r| (r.satisfies(&inventory), r));
+                let level = p.advancements.current_position(p.xp);
+                let recipes = sum.recipes.iter().map(|r| (r.missing(&inventory, level), r));
                 if let Some(craft) = &p.craft {
-                    blocks.push(json!({
+                    let mut section = json!({
                         "type": "section",
                         "text": mrkdwn(format!(
                             "*Crafting {}*\n{}  {:.3} minutes to go",
@@ -622,14 +1608,76 @@ fn hackstead_blocks(
                             progress_bar(30, 1.0 - craft.until_finish/craft.total_cycles),
                             (craft.until_finish / sum.yield_speed_multiplier) / FARM_CYCLES_PER_MIN as f32
                         ))
-                    }));
-                } else {
+                    });
+                    if interactivity.write() {
+                        let refund = CONFIG.crafting.cancel_refund(&craft.needs);
+                        section.as_object_mut().unwrap().insert(
+                            "accessory".to_string(),
+                            json!({
+                                "type": "button",
+                                "style": "danger",
+                                "text": plain_text("Cancel Craft"),
+                                "value": tile.id.to_simple().to_string(),
+                                "action_id": "craft_cancel",
+                                "confirm": {
+                                    "style": "danger",
+                                    "title": plain_text("Cancel this craft?"),
+                                    "text": mrkdwn(if refund.is_empty() {
+                                        "You won't get any of the resources it used back.".to_string()
+                                    } else {
+                                        format!(
+                                            "You'll only get back\n{}",
+                                            refund
+                                                .iter()
+                                                .filter_map(|&(n, ah)| {
+                                                    let arch = CONFIG.possession_archetypes.get(ah)?;
+                                                    Some(format!("*{}* {} _{}_", n, emojify(&arch.name), arch.name))
+                                                })
+                                                .collect::<Vec<_>>()
+                                                .join("\n")
+                                        )
+                                    }),
+                                    "deny": plain_text("Keep crafting"),
+                                    "confirm": plain_text("Cancel it!"),
+                                }
+                            }),
+                        );
+                    }
+                    blocks.push(section);
+
+                    // the crafts waiting for this one to finish
+                    let mut cycles_ahead = craft.until_finish;
+                    for queued in p.craft_queue.iter() {
+                        cycles_ahead += queued.total_cycles;
+                        let name = CONFIG
+                            .possession_archetypes
+                            .get(queued.makes)
+                            .map(|x| x.name.as_str())
+                            .unwrap_or("unknown");
+                        blocks.push(comment(format!(
+                            "_Queued:_ {} *{}* - done in {:.3} minutes",
+                            emojify(name),
+                            name,
+                            (cycles_ahead / sum.yield_speed_multiplier) / FARM_CYCLES_PER_MIN as f32
+                        )));
+                    }
+                }
+                if p.craft.is_none() || p.craft_queue.len() < sum.craft_queue_slots {
                     blocks.push(json!({
                         "type": "section",
                         "text": mrkdwn(format!(
-                            "*{}/{}* recipes craftable",
-                            sum.recipes.iter().filter(|r| r.satisfies(&inventory)).count(),
-                            sum.recipes.len()
+                            "*{}/{}* recipes craftable{}",
+                            sum.recipes.iter().filter(|r| r.satisfies(&inventory, level)).count(),
+                            sum.recipes.len(),
+                            if p.craft.is_some() {
+                                format!(
+                                    "\n_{}/{} queue slots used_",
+                                    p.craft_queue.len(),
+                                    sum.craft_queue_slots
+                                )
+                            } else {
+                                "".to_string()
+                            }
                         )),
                         "accessory": {
                             "type": "button",
@@ -677,12 +1725,55 @@ fn hackstead_blocks(
                         "action_id": "item_apply",
                     }))
                 }
+                let can_crossbreed = p.ready_to_crossbreed()
+                    && planted.iter().any(|(id, name)| {
+                        *id != tile.id && CONFIG.find_hybrids(&p.name, name).next().is_some()
+                    });
+                if can_crossbreed && interactivity.write() {
+                    actions.push(json!({
+                        "type": "button",
+                        "text": plain_text("Crossbreed"),
+                        "value": tile.id.to_simple().to_string(),
+                        "action_id": "crossbreed",
+                    }))
+                }
                 actions.push(json!({
                     "type": "button",
                     "text": plain_text("Levels"),
                     "value": serde_json::to_string(&(p.archetype_handle, p.xp)).unwrap(),
                     "action_id": "levels",
                 }));
+                actions.push(json!({
+                    "type": "button",
+                    "text": plain_text("History"),
+                    "value": tile.id.to_simple().to_string(),
+                    "action_id": "plant_history",
+                }));
+                if interactivity.write() {
+                    actions.push(json!({
+                        "type": "button",
+                        "text": plain_text("Uproot"),
+                        "style": "danger",
+                        "value": tile.id.to_simple().to_string(),
+                        "action_id": "uproot",
+                        "confirm": {
+                            "style": "danger",
+                            "title": plain_text("Uproot this plant?"),
+                            "text": mrkdwn(format!(
+                                concat!(
+                                    "You'll get your *{}* back as a seed, ",
+                                    "but it'll lose all *{}xp* it's earned ",
+                                    "and whatever it's crafting right now. ",
+                                    "Anything it has queued up to craft will be given back.",
+                                ),
+                                p.name,
+                                p.xp
+                            )),
+                            "deny": plain_text("Leave it be"),
+                            "confirm": plain_text("Uproot!"),
+                        }
+                    }));
+                }
 
                 blocks.push(json!({
                     "type": "actions",
@@ -869,6 +1960,43 @@ fn hackstead_blocks(
         ));
     }
 
+    if interactivity.write() {
+        blocks.push(json!({ "type": "divider" }));
+
+        blocks.push(json!({
+            "type": "section",
+            "text": mrkdwn("*Trades*"),
+            "accessory": {
+                "type": "button",
+                "text": plain_text("Propose Trade"),
+                "action_id": "trade_propose",
+            }
+        }));
+        for offer in trade_offers.iter() {
+            blocks.push(json!({
+                "type": "section",
+                "text": mrkdwn(format!(
+                    "_Your offer to <@{}>:_ *{}* of your things{} for *{}* of theirs{}",
+                    offer.recipient,
+                    offer.offered.len(),
+                    match offer.gp {
+                        0 => String::new(),
+                        gp => format!(" and *{}gp*", gp),
+                    },
+                    offer.requested.len(),
+                    if offer.sent { "" } else { " _(waiting on your payment)_" },
+                )),
+                "accessory": {
+                    "type": "button",
+                    "style": "danger",
+                    "text": plain_text("Call Off"),
+                    "value": offer.id.to_simple().to_string(),
+                    "action_id": "trade_call_off",
+                }
+            }));
+        }
+    }
+
     if let Interactivity::Read = interactivity {
         blocks.push(json!({ "type": "divider" }));
 
@@ -948,14 +2076,37 @@ fn hacksteader_greeting_blocks(
     o
 }
 
-async fn hackmarket_blocks(cat: Category, viewer: String) -> Vec<Value> {
+async fn hackmarket_blocks(query: &MarketQuery, viewer: String) -> Vec<Value> {
     use config::ArchetypeHandle;
+    use std::cmp::Reverse;
     use std::collections::HashMap;
+    use std::time::SystemTime;
+
+    const ENTRIES_PER_PAGE: usize = 20;
 
+    let cat = query.category;
     let sales = market::market_search(&dyn_db(), cat)
         .await
         .map_err(|e| error!("couldn't search market: {}", e))
         .unwrap_or_default();
+    let orders = market::buy_orders(&dyn_db())
+        .await
+        .map_err(|e| error!("couldn't search buy orders: {}", e))
+        .unwrap_or_default();
+    // the auctions ending soonest come first
+    let mut auctions: Vec<_> = market::auctions(&dyn_db())
+        .await
+        .map_err(|e| error!("couldn't search auctions: {}", e))
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|a| a.possession_category == cat && a.time_left().is_some())
+        .collect();
+    auctions.sort_by_key(|a| a.ends);
+    let day = std::time::Duration::from_secs(60 * 60 * 24);
+    let sold_today = market::sale_records(&dyn_db(), None, std::time::SystemTime::now() - day)
+        .await
+        .map_err(|e| error!("couldn't search sale records: {}", e))
+        .unwrap_or_default();
 
     let (all_goods_count, all_goods_price) =
         (sales.len(), sales.iter().map(|(s, _)| s.price).sum::<u64>());
@@ -966,31 +2117,50 @@ async fn hackmarket_blocks(cat: Category, viewer: String) -> Vec<Value> {
         .map(|(s, _)| s.price)
         .fold((0, 0), |(n, sum), p| (n + 1, sum + p));
 
-    // things for sale, sorted by the type of thing they are.
-    let entries: Vec<(String, (u64, usize))> = {
-        let mut entries: HashMap<String, (u64, usize, ArchetypeHandle)> = Default::default();
+    struct Entry {
+        lowest_price: u64,
+        count: usize,
+        ah: ArchetypeHandle,
+        /// When the most recent listing expires; the latest to expire was listed last.
+        newest: Option<SystemTime>,
+    }
+
+    // things for sale that match the query, grouped by the type of thing they are.
+    let mut entries: Vec<(String, Entry)> = {
+        let mut entries: HashMap<String, Entry> = Default::default();
 
-        for (sale, p) in sales.into_iter() {
+        for (sale, p) in sales.into_iter().filter(|(s, p)| query.matches(s, p)) {
             entries
                 .entry(sale.market_name.clone())
                 .and_modify(|e| {
-                    if sale.price < e.0 {
-                        e.0 = sale.price
+                    if sale.price < e.lowest_price {
+                        e.lowest_price = sale.price
                     }
-                    e.1 += 1;
+                    e.count += 1;
+                    e.newest = e.newest.max(sale.expires);
                 })
-                .or_insert((sale.price, 1, p.archetype_handle));
+                .or_insert(Entry {
+                    lowest_price: sale.price,
+                    count: 1,
+                    ah: p.archetype_handle,
+                    newest: sale.expires,
+                });
         }
 
-        let mut v: Vec<_> = entries.into_iter().collect();
-        v.sort_by_key(|&(_, (_, _, ah))| ah);
-        v.into_iter()
-            .map(|(name, (lowest_price, count, _))| (name, (lowest_price, count)))
-            .collect()
+        entries.into_iter().collect()
     };
+    match query.sort {
+        MarketSort::Archetype => entries.sort_by_key(|(_, e)| e.ah),
+        MarketSort::Cheapest => entries.sort_by_key(|(_, e)| e.lowest_price),
+        MarketSort::Priciest => entries.sort_by_key(|(_, e)| Reverse(e.lowest_price)),
+        MarketSort::Newest => entries.sort_by_key(|(_, e)| Reverse(e.newest)),
+    }
 
     let entry_count = entries.len();
-    std::iter::once(comment(format!(
+    let shown_count = entry_count
+        .saturating_sub(query.page * ENTRIES_PER_PAGE)
+        .min(ENTRIES_PER_PAGE);
+    let mut blocks = vec![comment(format!(
         concat!(
             "Your *{}* goods cost *{}gp* in total, ",
             "*{}%* of the market's ",
@@ -1001,27 +2171,184 @@ async fn hackmarket_blocks(cat: Category, viewer: String) -> Vec<Value> {
         your_goods_price as f32 / all_goods_price as f32 * 100.0,
         all_goods_price,
         all_goods_count,
-    )))
-    .chain(
+    ))];
+
+    if query.filtered() {
+        let mut filters = vec![];
+        if let Some(search) = &query.search {
+            filters.push(format!("named like _{}_", search));
+        }
+        if let Some(kind) = query.kind {
+            filters.push(format!("{}s", kind.name()));
+        }
+        if let Some(seller) = &query.seller {
+            filters.push(format!("sold by <@{}>", seller));
+        }
+        if let Some(min) = query.min_price {
+            filters.push(format!("for at least {}gp", min));
+        }
+        if let Some(max) = query.max_price {
+            filters.push(format!("for at most {}gp", max));
+        }
+        blocks.push(comment(format!("Only showing {}.", filters.join(", "))));
+    }
+
+    blocks.push(json!({
+        "type": "actions",
+        "elements": ({
+            let mut buttons = vec![
+                json!({
+                    "type": "button",
+                    "text": plain_text("Post Buy Order"),
+                    "action_id": "buy_order_post",
+                }),
+                json!({
+                    "type": "button",
+                    "text": plain_text("Search & Filter"),
+                    "value": serde_json::to_string(query).unwrap(),
+                    "action_id": "hackmarket_filter",
+                }),
+                json!({
+                    "type": "button",
+                    "text": plain_text("Watchlist"),
+                    "action_id": "watchlist",
+                }),
+                json!({
+                    "type": "button",
+                    "text": plain_text(format!("{}'s Shop", CONFIG.shop.keeper)),
+                    "action_id": "shop",
+                }),
+            ];
+            if query.filtered() {
+                buttons.push(json!({
+                    "type": "button",
+                    "text": plain_text("Clear Filters"),
+                    "value": serde_json::to_string(&MarketQuery::new(cat)).unwrap(),
+                    "action_id": "hackmarket_page",
+                }));
+            }
+            buttons
+        })
+    }));
+    let own_orders: Vec<_> = orders.iter().filter(|o| o.buyer == viewer).collect();
+    for order in own_orders.iter().take(5) {
+        blocks.push(json!({
+            "type": "section",
+            "text": mrkdwn(format!(
+                "_Your buy order:_ *{}* {} _{}_ at up to *{}gp* each",
+                order.quantity,
+                emojify(order.name()),
+                order.name(),
+                order.price
+            )),
+            "accessory": {
+                "type": "button",
+                "style": "danger",
+                "text": plain_text("Cancel"),
+                "value": order.id.to_simple().to_string(),
+                "action_id": "buy_order_cancel",
+                "confirm": {
+                    "style": "danger",
+                    "title": plain_text("Cancel this buy order?"),
+                    "text": mrkdwn(format!(
+                        "The *{}gp* Banker is holding for it will be refunded to you.",
+                        order.escrow()
+                    )),
+                    "deny": plain_text("Keep it up"),
+                    "confirm": plain_text("Cancel it!"),
+                }
+            }
+        }));
+    }
+    if own_orders.len() > 5 {
+        blocks.push(comment(format!(
+            "...and {} more buy orders of yours.",
+            own_orders.len() - 5
+        )));
+    }
+    blocks.push(json!({ "type": "divider" }));
+
+    if !auctions.is_empty() {
+        blocks.push(json!({
+            "type": "section",
+            "text": mrkdwn("*Auctions*"),
+        }));
+        for auction in auctions.iter().take(10) {
+            let mut section = json!({
+                "type": "section",
+                "fields": [
+                    mrkdwn(format!(
+                        "{} _{}_\n_{}_",
+                        emojify(auction.name()),
+                        auction.name(),
+                        auction_time_left(auction)
+                    )),
+                    mrkdwn(match &auction.high_bid {
+                        Some(bid) => format!("*{}gp* bid by <@{}>", bid.amount, bid.bidder),
+                        None => format!("No bids yet, reserve *{}gp*", auction.reserve),
+                    }),
+                ],
+            });
+            if auction.seller != viewer {
+                section.as_object_mut().unwrap().insert(
+                    "accessory".to_string(),
+                    json!({
+                        "type": "button",
+                        "style": "primary",
+                        "text": plain_text(format!("Bid {}gp+", auction.min_bid())),
+                        "value": auction.id.to_simple().to_string(),
+                        "action_id": "auction_bid",
+                    }),
+                );
+            }
+            blocks.push(section);
+        }
+        blocks.push(json!({ "type": "divider" }));
+    }
+
+    if entry_count == 0 && query.filtered() {
+        blocks.push(json!({
+            "type": "section",
+            "text": mrkdwn("Nothing for sale matches that."),
+        }));
+    }
+
+    blocks.extend(
         entries
             .into_iter()
-            .flat_map(|(name, (lowest_price, count))| {
+            .skip(query.page * ENTRIES_PER_PAGE)
+            .take(ENTRIES_PER_PAGE)
+            .flat_map(|(name, entry)| {
+                let ah = entry.ah;
+                let mut fields = vec![mrkdwn(format!("{} _{}_", emojify(&name), name))];
+                let wanted = orders.iter().filter(|o| o.archetype_handle == ah);
+                if let Some(best) = wanted.clone().map(|o| o.price).max() {
+                    fields.push(mrkdwn(format!(
+                        "_{} wanted for up to {}gp_",
+                        wanted.map(|o| o.quantity).sum::<u64>(),
+                        best
+                    )));
+                }
+                // newest first, so the first one is the last one sold
+                let sold = sold_today.iter().filter(|r| r.archetype_handle == ah);
+                if let Some(last) = sold.clone().next() {
+                    fields.push(mrkdwn(format!(
+                        "_{} sold today, last for {}gp_",
+                        sold.count(),
+                        last.price
+                    )));
+                }
+
                 std::iter::once(json!({
                     "type": "section",
-                    "fields": [
-                        mrkdwn(format!(
-                            "{} _{}_",
-                            emojify(&name),
-                            name,
-                        )),
-                    ],
+                    "fields": fields,
                     "accessory": {
                         "type": "button",
                         "style": "primary",
                         "text": plain_text(format!(
                             "{} for sale starting at {}gp",
-                            count,
-                            lowest_price
+                            entry.count,
+                            entry.lowest_price
                         )),
                         "action_id": "possession_market_overview_page",
                         "value": serde_json::to_string(&(name, cat)).unwrap(),
@@ -1029,9 +2356,219 @@ async fn hackmarket_blocks(cat: Category, viewer: String) -> Vec<Value> {
                 }))
                 .chain(std::iter::once(json!({ "type": "divider" })))
             })
-            .take(entry_count * 2 - 1),
-    )
-    .collect()
+            .take((shown_count * 2).saturating_sub(1)),
+    );
+
+    let needs_back_page = query.page != 0;
+    let needs_next_page = entry_count > ENTRIES_PER_PAGE * (query.page + 1);
+    if needs_back_page || needs_next_page {
+        blocks.push(json!({
+            "type": "actions",
+            "elements": ({
+                let mut buttons = vec![];
+
+                if needs_back_page {
+                    buttons.push(json!({
+                        "type": "button",
+                        "text": plain_text("Back Page"),
+                        "style": "primary",
+                        "value": serde_json::to_string(&MarketQuery {
+                            page: query.page - 1,
+                            ..query.clone()
+                        })
+                        .unwrap(),
+                        "action_id": "hackmarket_page"
+                    }));
+                }
+                if needs_next_page {
+                    buttons.push(json!({
+                        "type": "button",
+                        "text": plain_text("Next Page"),
+                        "style": "primary",
+                        "value": serde_json::to_string(&MarketQuery {
+                            page: query.page + 1,
+                            ..query.clone()
+                        })
+                        .unwrap(),
+                        "action_id": "hackmarket_page"
+                    }));
+                }
+
+                buttons
+            })
+        }));
+    }
+
+    blocks
+}
+
+/// Inputs for narrowing down and sorting what's shown on the market.
+fn hackmarket_filter_blocks(query: &MarketQuery) -> Vec<Value> {
+    let option = |name: &str, value: String| {
+        json!({
+            "text": plain_text(name),
+            "value": value,
+        })
+    };
+    let kind_option =
+        |kind: ListingKind| option(kind.name(), serde_json::to_string(&kind).unwrap());
+    let sort_option = |sort: MarketSort| option(sort.name(), serde_json::to_string(&sort).unwrap());
+    let text_input = |block: &str, label: &str, initial: Option<String>| {
+        let mut element = json!({
+            "type": "plain_text_input",
+            "action_id": block.replace("_block", "_input"),
+        });
+        if let Some(initial) = initial {
+            element
+                .as_object_mut()
+                .unwrap()
+                .insert("initial_value".to_string(), json!(initial));
+        }
+        json!({
+            "type": "input",
+            "block_id": block,
+            "optional": true,
+            "label": plain_text(label),
+            "element": element,
+        })
+    };
+
+    let mut kind_select = json!({
+        "type": "static_select",
+        "action_id": "hackmarket_filter_kind_input",
+        "placeholder": plain_text("Anything"),
+        "options": ListingKind::ALL.iter().map(|&k| kind_option(k)).collect::<Vec<_>>(),
+    });
+    if let Some(kind) = query.kind {
+        kind_select
+            .as_object_mut()
+            .unwrap()
+            .insert("initial_option".to_string(), kind_option(kind));
+    }
+    let mut seller_select = json!({
+        "type": "users_select",
+        "action_id": "hackmarket_filter_seller_input",
+        "placeholder": plain_text("Anyone"),
+    });
+    if let Some(seller) = &query.seller {
+        seller_select
+            .as_object_mut()
+            .unwrap()
+            .insert("initial_user".to_string(), json!(seller));
+    }
+
+    vec![
+        text_input(
+            "hackmarket_filter_search_block",
+            "Name",
+            query.search.clone(),
+        ),
+        json!({
+            "type": "input",
+            "block_id": "hackmarket_filter_kind_block",
+            "optional": true,
+            "label": plain_text("Kind"),
+            "element": kind_select,
+        }),
+        json!({
+            "type": "input",
+            "block_id": "hackmarket_filter_seller_block",
+            "optional": true,
+            "label": plain_text("Seller"),
+            "element": seller_select,
+        }),
+        text_input(
+            "hackmarket_filter_min_price_block",
+            "Lowest price (gp)",
+            query.min_price.map(|p| p.to_string()),
+        ),
+        text_input(
+            "hackmarket_filter_max_price_block",
+            "Highest price (gp)",
+            query.max_price.map(|p| p.to_string()),
+        ),
+        json!({
+            "type": "input",
+            "block_id": "hackmarket_filter_sort_block",
+            "label": plain_text("Sort"),
+            "element": {
+                "type": "static_select",
+                "action_id": "hackmarket_filter_sort_input",
+                "options": MarketSort::ALL.iter().map(|&s| sort_option(s)).collect::<Vec<_>>(),
+                "initial_option": sort_option(query.sort),
+            }
+        }),
+    ]
+}
+
+/// What a hacksteader's watching the market for, and room to watch for something else.
+fn watchlist_blocks(watches: &[Watch]) -> Vec<Value> {
+    let mut blocks: Vec<Value> = watches
+        .iter()
+        .filter_map(|w| Some((w, CONFIG.possession_archetypes.get(w.archetype_handle)?)))
+        .map(|(w, a)| {
+            json!({
+                "type": "section",
+                "text": mrkdwn(format!(
+                    "{} *{}* for *{}gp* or less",
+                    emojify(&a.name),
+                    a.name,
+                    w.max_price
+                )),
+                "accessory": {
+                    "type": "button",
+                    "style": "danger",
+                    "text": plain_text("Stop Watching"),
+                    "value": w.archetype_handle.to_string(),
+                    "action_id": "watch_remove",
+                }
+            })
+        })
+        .collect();
+    if blocks.is_empty() {
+        blocks.push(comment("You aren't watching for anything yet."));
+    }
+
+    blocks.push(json!({ "type": "divider" }));
+    if watches.len() < Watch::MAX_PER_PROFILE {
+        blocks.push(json!({
+            "type": "input",
+            "block_id": "watch_archetype_block",
+            "label": plain_text("Watch for"),
+            "element": {
+                "type": "static_select",
+                "action_id": "watch_archetype_input",
+                "placeholder": plain_text("What are you hoping to find?"),
+                "options": CONFIG
+                    .possession_archetypes
+                    .iter()
+                    .enumerate()
+                    .take(100)
+                    .map(|(ah, a)| json!({
+                        "text": plain_text(format!("{} {}", emojify(&a.name), a.name)),
+                        "value": ah.to_string(),
+                    }))
+                    .collect::<Vec<_>>(),
+            }
+        }));
+        blocks.push(json!({
+            "type": "input",
+            "block_id": "watch_price_block",
+            "label": plain_text("Most you'd pay (gp)"),
+            "element": {
+                "type": "plain_text_input",
+                "action_id": "watch_price_input",
+                "initial_value": "50",
+            }
+        }));
+    }
+    blocks.push(comment(format!(
+        "You'll get a DM whenever something you're watching for goes up on the hackmarket \
+        for your price or less. You can watch for up to {} things at once.",
+        Watch::MAX_PER_PROFILE
+    )));
+
+    blocks
 }
 
 #[derive(FromForm, Debug)]
@@ -1053,20 +2590,26 @@ struct SlashCommand {
 async fn hackmarket<'a>(slash_command: LenientForm<SlashCommand>) -> Result<(), String> {
     info!("{} | {}", slash_command.command, slash_command.text);
 
+    // "/hackmarket g egg" searches the gotchis for eggs, "/hackmarket bread" the rest for bread
+    let text = slash_command.text.trim();
+    let mut words = text.splitn(2, ' ');
+    let (cat, search) = match (words.next(), words.next()) {
+        (Some("gotchi"), search) | (Some("g"), search) => {
+            (Category::Gotchi, search.unwrap_or("").trim())
+        }
+        _ => (Category::Misc, text),
+    };
+    let query = MarketQuery {
+        search: Some(search.to_string()).filter(|s| !s.is_empty()),
+        ..MarketQuery::new(cat)
+    };
     Modal {
         method: "open".to_string(),
         trigger_id: slash_command.trigger_id.clone(),
         callback_id: "hackstreet_modal".to_string(),
         title: "Hackstreet!".to_string(),
-        private_metadata: String::new(),
-        blocks: hackmarket_blocks(
-            match slash_command.text.as_str() {
-                "gotchi" | "g" => Category::Gotchi,
-                _ => Category::Misc,
-            },
-            slash_command.user_id.clone(),
-        )
-        .await,
+        private_metadata: serde_json::to_string(&query).unwrap(),
+        blocks: hackmarket_blocks(&query, slash_command.user_id.clone()).await,
         submit: None,
     }
     .launch()
@@ -1228,6 +2771,7 @@ pub struct Interaction {
 }
 #[derive(serde::Deserialize, Debug)]
 pub struct View {
+    id: String,
     private_metadata: String,
     callback_id: String,
     root_view_id: String,
@@ -1387,6 +2931,33 @@ pub enum Credentials {
     None,
 }
 
+/// Takes the item out of its owner's inventory and hands it off to the farming loop.
+async fn apply_item(
+    to_farming: &Sender<FarmingInputEvent>,
+    user_id: String,
+    tile_id: uuid::Uuid,
+    item_id: uuid::Uuid,
+    item_ah: config::ArchetypeHandle,
+) -> Result<(), String> {
+    info!("applying item!");
+    Hacksteader::delete(&dyn_db(), Key::misc(item_id))
+        .await
+        .map_err(|e| {
+            let a = format!("couldn't remove item after applying: {}", e);
+            error!("{}", a);
+            a
+        })?;
+
+    to_farming
+        .send(FarmingInputEvent::ApplyItem(tile_id, item_ah))
+        .unwrap();
+    to_farming
+        .send(FarmingInputEvent::ActivateUser(user_id))
+        .unwrap();
+
+    Ok(())
+}
+
 #[post("/interact", data = "<action_data>")]
 async fn action_endpoint(
     to_farming: State<'_, Sender<FarmingInputEvent>>,
@@ -1452,10 +3023,104 @@ async fn action_endpoint(
                         "response_action": "clear",
                     }))));
                 }
-                _ => {}
-            };
+                "possession_auction_modal" => {
+                    let number = |block: &str, input: &str| -> Option<u64> {
+                        values
+                            .get(block)?
+                            .get(input)?
+                            .get("value")?
+                            .as_str()?
+                            .parse()
+                            .ok()
+                    };
+                    let reserve = number(
+                        "possession_auction_reserve_block",
+                        "possession_auction_reserve_input",
+                    );
+                    let hours = number(
+                        "possession_auction_hours_block",
+                        "possession_auction_hours_input",
+                    )
+                    .filter(|h| (1..=7 * 24).contains(h));
+                    let errors = |block: &str, error: &str| {
+                        Ok(ActionResponse::Json(Json(json!({
+                            "response_action": "errors",
+                            "errors": { block: error },
+                        }))))
+                    };
 
-            if let Some(Value::String(nickname)) = values
+                    let (reserve, hours) = match (reserve, hours) {
+                        (Some(reserve), Some(hours)) => (reserve, hours),
+                        (None, _) => {
+                            return errors(
+                                "possession_auction_reserve_block",
+                                "That's not a price anyone would bid!",
+                            )
+                        }
+                        (_, None) => {
+                            return errors(
+                                "possession_auction_hours_block",
+                                "Auctions can last from 1 to 168 hours (a week).",
+                            )
+                        }
+                    };
+
+                    let db = dyn_db();
+                    let possession = hacksteader::get_possession(&db, key).await?;
+                    if possession.steader != user.id {
+                        return errors(
+                            "possession_auction_reserve_block",
+                            "You can't auction something that isn't yours!",
+                        );
+                    }
+                    if possession.sale.is_some() {
+                        return errors(
+                            "possession_auction_reserve_block",
+                            "Take this off the market before auctioning it.",
+                        );
+                    }
+
+                    let auction = core::market::Auction::new(
+                        &possession,
+                        reserve,
+                        std::time::Duration::from_secs(hours * 60 * 60),
+                    );
+                    if !market::start_auction(&db, &auction).await? {
+                        return errors(
+                            "possession_auction_reserve_block",
+                            "This is already up for auction!",
+                        );
+                    }
+
+                    market::log_blocks(vec![
+                        json!({
+                            "type": "section",
+                            "text": mrkdwn(format!(
+                                "A *{}* has gone up for auction! \
+                                <@{}> will take bids of *{} GP* or more for the next {} hours!",
+                                possession.name, user.id, reserve, hours
+                            )),
+                            "accessory": {
+                                "type": "image",
+                                "image_url": format!(
+                                    "http://{}/gotchi/img/{}/{}.png",
+                                    *URL,
+                                    possession.kind.category(),
+                                    filify(&possession.name)
+                                ),
+                                "alt_text": "Hackpheus sitting on bags of money!",
+                            }
+                        }),
+                        comment("DO I HEAR A BID"),
+                    ])
+                    .await?;
+
+                    return Ok(ActionResponse::Ok(()));
+                }
+                _ => {}
+            };
+
+            if let Some(Value::String(nickname)) = values
                 .get("gotchi_nickname_block")
                 .and_then(|i| i.get("gotchi_nickname_input"))
                 .and_then(|s| s.get("value"))
@@ -1520,19 +3185,28 @@ async fn action_endpoint(
                 .and_then(|s| s.parse::<u64>().ok())
             {
                 let possession = hacksteader::get_possession(&dyn_db(), key).await?;
+                let fee = CONFIG.market.fees.fee(possession.kind.category(), price);
 
-                banker::invoice(
-                    &user.id,
-                    price / 20_u64,
-                    &format!(
-                        "hackmarket fees for selling {} at {}gp :{}:{}",
-                        possession.name,
-                        price,
-                        possession.id,
-                        possession.kind.category() as u8
-                    ),
-                )
-                .await?;
+                // there's no sense in sending an invoice for nothing
+                if fee == 0 {
+                    if possession.steader == user.id && possession.sale.is_none() {
+                        let name = possession.name.clone();
+                        market::list(&dyn_db(), possession, price, name).await?;
+                    }
+                } else {
+                    banker::invoice(
+                        &user.id,
+                        fee,
+                        &format!(
+                            "hackmarket fees for selling {} at {}gp :{}:{}",
+                            possession.name,
+                            price,
+                            possession.id,
+                            possession.kind.category() as u8
+                        ),
+                    )
+                    .await?;
+                }
 
                 return Ok(ActionResponse::Ok(()));
             } else if let Some(Value::String(new_owner)) = values
@@ -1606,7 +3280,7 @@ async fn action_endpoint(
                     "response_action": "clear",
                 }))));
             }
-        } else if let Some((view, None, _trigger_id, values, user)) = view {
+        } else if let Some((view, None, trigger_id, values, user)) = view {
             debug!("view state values: {:#?}", values);
 
             match view.callback_id.as_str() {
@@ -1622,6 +3296,620 @@ async fn action_endpoint(
                         "response_action": "clear",
                     }))));
                 }
+                "item_apply_confirm_modal" => {
+                    let (tile_id, item_id, item_ah): (
+                        uuid::Uuid,
+                        uuid::Uuid,
+                        config::ArchetypeHandle,
+                    ) = serde_json::from_str(&view.private_metadata).unwrap();
+
+                    apply_item(&to_farming, user.id, tile_id, item_id, item_ah).await?;
+
+                    return Ok(ActionResponse::Json(Json(json!({
+                        "response_action": "clear",
+                    }))));
+                }
+                "buy_order_modal" => {
+                    let number = |block: &str, input: &str| -> Option<u64> {
+                        values
+                            .get(block)?
+                            .get(input)?
+                            .get("value")?
+                            .as_str()?
+                            .parse()
+                            .ok()
+                            .filter(|&n| n > 0)
+                    };
+                    let archetype = values
+                        .get("buy_order_archetype_block")
+                        .and_then(|i| i.get("buy_order_archetype_input"))
+                        .and_then(|s| s.get("selected_option"))
+                        .and_then(|s| s.get("value"))
+                        .and_then(|s| s.as_str())
+                        .and_then(|v| v.parse::<config::ArchetypeHandle>().ok())
+                        .and_then(|ah| Some((ah, CONFIG.possession_archetypes.get(ah)?)));
+                    let price = number("buy_order_price_block", "buy_order_price_input");
+                    let quantity = number("buy_order_quantity_block", "buy_order_quantity_input");
+
+                    match (archetype, price, quantity) {
+                        (Some((ah, archetype)), Some(price), Some(quantity)) => {
+                            banker::invoice(
+                                &user.id,
+                                price * quantity,
+                                &format!(
+                                    "hackmarket buy order for {} {} at {}gp each :{}",
+                                    quantity, archetype.name, price, ah,
+                                ),
+                            )
+                            .await?;
+
+                            return Ok(ActionResponse::Ok(()));
+                        }
+                        (archetype, price, quantity) => {
+                            let mut errors = serde_json::Map::new();
+                            if archetype.is_none() {
+                                errors.insert(
+                                    "buy_order_archetype_block".to_string(),
+                                    json!("What is it you want to buy?"),
+                                );
+                            }
+                            if price.is_none() {
+                                errors.insert(
+                                    "buy_order_price_block".to_string(),
+                                    json!("That's not a price anyone would sell for!"),
+                                );
+                            }
+                            if quantity.is_none() {
+                                errors.insert(
+                                    "buy_order_quantity_block".to_string(),
+                                    json!("You have to want at least one!"),
+                                );
+                            }
+
+                            return Ok(ActionResponse::Json(Json(json!({
+                                "response_action": "errors",
+                                "errors": errors,
+                            }))));
+                        }
+                    }
+                }
+                "shop_buy_modal" => {
+                    let ah: config::ArchetypeHandle =
+                        view.private_metadata.parse().map_err(|e| {
+                            let a = format!(
+                                "couldn't parse shop item {}: {}",
+                                view.private_metadata, e
+                            );
+                            error!("{}", a);
+                            a
+                        })?;
+                    let item = CONFIG
+                        .shop
+                        .item(ah)
+                        .ok_or_else(|| format!("the shop doesn't sell archetype {}", ah))?;
+                    let quantity = values
+                        .get("shop_buy_quantity_block")
+                        .and_then(|i| i.get("shop_buy_quantity_input"))
+                        .and_then(|s| s.get("value"))
+                        .and_then(|s| s.as_str())
+                        .and_then(|v| v.parse::<u64>().ok())
+                        .filter(|&n| n > 0);
+                    let day = shop::today();
+                    let sold = shop::sold_today(&dyn_db())
+                        .await?
+                        .get(&ah)
+                        .copied()
+                        .unwrap_or(0);
+                    let left = item.daily_stock.saturating_sub(sold);
+
+                    let error = match quantity {
+                        Some(quantity) if quantity <= left => {
+                            let price = CONFIG.shop.total(item, sold, quantity);
+                            banker::invoice(
+                                &user.id,
+                                price,
+                                &format!(
+                                    "shop purchase of {} {} for {}gp :{}:{}:{}",
+                                    quantity, item.name, price, ah, day, sold
+                                ),
+                            )
+                            .await?;
+
+                            return Ok(ActionResponse::Ok(()));
+                        }
+                        Some(_) => format!("There are only {} left today!", left),
+                        None => "You have to want at least one!".to_string(),
+                    };
+
+                    return Ok(ActionResponse::Json(Json(json!({
+                        "response_action": "errors",
+                        "errors": { "shop_buy_quantity_block": error },
+                    }))));
+                }
+                "watchlist_modal" => {
+                    let ah = values
+                        .get("watch_archetype_block")
+                        .and_then(|i| i.get("watch_archetype_input"))
+                        .and_then(|s| s.get("selected_option"))
+                        .and_then(|s| s.get("value"))
+                        .and_then(|s| s.as_str())
+                        .and_then(|v| v.parse::<config::ArchetypeHandle>().ok())
+                        .filter(|&ah| CONFIG.possession_archetypes.get(ah).is_some());
+                    let max_price = values
+                        .get("watch_price_block")
+                        .and_then(|i| i.get("watch_price_input"))
+                        .and_then(|s| s.get("value"))
+                        .and_then(|s| s.as_str())
+                        .and_then(|v| v.parse::<u64>().ok())
+                        .filter(|&n| n > 0);
+
+                    match (ah, max_price) {
+                        (Some(archetype_handle), Some(max_price)) => {
+                            to_farming
+                                .send(FarmingInputEvent::WatchMarket {
+                                    user_id: user.id.clone(),
+                                    archetype_handle,
+                                    max_price,
+                                })
+                                .unwrap();
+                            to_farming
+                                .send(FarmingInputEvent::ActivateUser(user.id.clone()))
+                                .unwrap();
+
+                            return Ok(ActionResponse::Ok(()));
+                        }
+                        (ah, max_price) => {
+                            let mut errors = serde_json::Map::new();
+                            if ah.is_none() {
+                                errors.insert(
+                                    "watch_archetype_block".to_string(),
+                                    json!("What is it you're watching for?"),
+                                );
+                            }
+                            if max_price.is_none() {
+                                errors.insert(
+                                    "watch_price_block".to_string(),
+                                    json!("That's not a price anyone would sell for!"),
+                                );
+                            }
+
+                            return Ok(ActionResponse::Json(Json(json!({
+                                "response_action": "errors",
+                                "errors": errors,
+                            }))));
+                        }
+                    }
+                }
+                "bulk_sell_modal" | "bulk_buy_modal" => {
+                    let selling = view.callback_id == "bulk_sell_modal";
+                    let prefix = if selling { "bulk_sell" } else { "bulk_buy" };
+                    let number = |field: &str| -> Option<u64> {
+                        values
+                            .get(&format!("{}_{}_block", prefix, field))?
+                            .get(&format!("{}_{}_input", prefix, field))?
+                            .get("value")?
+                            .as_str()?
+                            .parse()
+                            .ok()
+                            .filter(|&n| n > 0)
+                    };
+                    let ah: config::ArchetypeHandle =
+                        view.private_metadata.parse().map_err(|e| {
+                            format!("couldn't parse archetype handle from metadata: {}", e)
+                        })?;
+                    let archetype = CONFIG
+                        .possession_archetypes
+                        .get(ah)
+                        .ok_or_else(|| format!("no archetype {}", ah))?;
+                    let name = &archetype.name;
+
+                    let mut errors = serde_json::Map::new();
+                    let price = number("price");
+                    if price.is_none() {
+                        errors.insert(
+                            format!("{}_price_block", prefix),
+                            json!("That's not a price anyone would pay!"),
+                        );
+                    }
+                    let quantity = number("quantity");
+                    match quantity {
+                        None => {
+                            errors.insert(
+                                format!("{}_quantity_block", prefix),
+                                json!("It has to be at least one!"),
+                            );
+                        }
+                        Some(quantity) if selling => {
+                            let unsold = Hacksteader::from_db(&dyn_db(), user.id.clone())
+                                .await?
+                                .inventory
+                                .into_iter()
+                                .filter(|p| p.archetype_handle == ah && p.sale.is_none())
+                                .count() as u64;
+                            if quantity > unsold {
+                                errors.insert(
+                                    "bulk_sell_quantity_block".to_string(),
+                                    json!(format!("You only have {} to sell!", unsold)),
+                                );
+                            }
+                        }
+                        Some(_) => {}
+                    }
+
+                    match (price, quantity) {
+                        (Some(price), Some(quantity)) if errors.is_empty() => {
+                            if selling {
+                                banker::invoice(
+                                    &user.id,
+                                    quantity
+                                        * CONFIG.market.fees.fee(archetype.kind.category(), price),
+                                    &format!(
+                                        "hackmarket fees for selling {} {} at {}gp each :{}",
+                                        quantity, name, price, ah
+                                    ),
+                                )
+                                .await?;
+                            } else {
+                                banker::invoice(
+                                    &user.id,
+                                    quantity * price,
+                                    &format!(
+                                        "hackmarket bulk purchase of {} {} at up to {}gp each :{}",
+                                        quantity, name, price, ah
+                                    ),
+                                )
+                                .await?;
+                            }
+
+                            return Ok(ActionResponse::Ok(()));
+                        }
+                        _ => {
+                            return Ok(ActionResponse::Json(Json(json!({
+                                "response_action": "errors",
+                                "errors": errors,
+                            }))));
+                        }
+                    }
+                }
+                "hackmarket_filter_modal" => {
+                    let query: MarketQuery = serde_json::from_str(&view.private_metadata)
+                        .map_err(|e| format!("couldn't parse market query: {}", e))?;
+                    let input = |field: &str| -> Option<&Value> {
+                        values
+                            .get(&format!("hackmarket_filter_{}_block", field))?
+                            .get(&format!("hackmarket_filter_{}_input", field))
+                    };
+                    let text = |field: &str| -> Option<String> {
+                        Some(input(field)?.get("value")?.as_str()?.trim().to_string())
+                            .filter(|s| !s.is_empty())
+                    };
+                    let selected = |field: &str| -> Option<Value> {
+                        serde_json::from_str(
+                            input(field)?
+                                .get("selected_option")?
+                                .get("value")?
+                                .as_str()?,
+                        )
+                        .ok()
+                    };
+
+                    let mut errors = serde_json::Map::new();
+                    let mut price = |field: &str| match text(field).map(|t| t.parse::<u64>()) {
+                        Some(Ok(price)) => Some(price),
+                        Some(Err(_)) => {
+                            errors.insert(
+                                format!("hackmarket_filter_{}_block", field),
+                                json!("That's not a price!"),
+                            );
+                            None
+                        }
+                        None => None,
+                    };
+                    let (min_price, max_price) = (price("min_price"), price("max_price"));
+                    if let (Some(min), Some(max)) = (min_price, max_price) {
+                        if min > max {
+                            errors.insert(
+                                "hackmarket_filter_max_price_block".to_string(),
+                                json!("This can't be lower than the lowest price!"),
+                            );
+                        }
+                    }
+                    if !errors.is_empty() {
+                        return Ok(ActionResponse::Json(Json(json!({
+                            "response_action": "errors",
+                            "errors": errors,
+                        }))));
+                    }
+
+                    let query = MarketQuery {
+                        category: query.category,
+                        search: text("search"),
+                        kind: selected("kind").and_then(|v| serde_json::from_value(v).ok()),
+                        seller: input("seller")
+                            .and_then(|s| s.get("selected_user"))
+                            .and_then(|s| s.as_str())
+                            .map(|s| s.to_string()),
+                        min_price,
+                        max_price,
+                        sort: selected("sort")
+                            .and_then(|v| serde_json::from_value(v).ok())
+                            .unwrap_or(MarketSort::Archetype),
+                        page: 0,
+                    };
+
+                    // show the results on the market this was opened from
+                    ModalUpdate {
+                        trigger_id: trigger_id.to_string(),
+                        callback_id: "hackstreet_modal".to_string(),
+                        title: "Hackstreet!".to_string(),
+                        private_metadata: serde_json::to_string(&query).unwrap(),
+                        hash: None,
+                        view_id: view.root_view_id,
+                        blocks: hackmarket_blocks(&query, user.id.clone()).await,
+                        submit: None,
+                    }
+                    .launch()
+                    .await?;
+
+                    return Ok(ActionResponse::Ok(()));
+                }
+                "auction_bid_modal" => {
+                    let auction_id = uuid::Uuid::parse_str(&view.private_metadata)
+                        .map_err(|e| format!("couldn't parse auction id: {}", e))?;
+                    let auction = market::get_auction(&dyn_db(), auction_id).await?;
+                    let amount = values
+                        .get("auction_bid_amount_block")
+                        .and_then(|i| i.get("auction_bid_amount_input"))
+                        .and_then(|s| s.get("value"))
+                        .and_then(|s| s.as_str())
+                        .and_then(|s| s.parse::<u64>().ok());
+
+                    let amount = match amount {
+                        _ if auction.seller == user.id => {
+                            Err("You can't bid on your own auction!".to_string())
+                        }
+                        _ if auction.time_left().is_none() => {
+                            Err("This auction has already ended.".to_string())
+                        }
+                        Some(amount) if amount >= auction.min_bid() => Ok(amount),
+                        _ => Err(format!(
+                            "You'll have to bid at least {}gp.",
+                            auction.min_bid()
+                        )),
+                    };
+                    let amount = match amount {
+                        Ok(amount) => amount,
+                        Err(error) => {
+                            return Ok(ActionResponse::Json(Json(json!({
+                                "response_action": "errors",
+                                "errors": { "auction_bid_amount_block": error },
+                            }))))
+                        }
+                    };
+
+                    banker::invoice(
+                        &user.id,
+                        amount,
+                        &format!(
+                            "hackmarket bid of {}gp on {} at auction :{}",
+                            amount,
+                            auction.name(),
+                            auction.id
+                        ),
+                    )
+                    .await?;
+
+                    return Ok(ActionResponse::Ok(()));
+                }
+                "trade_partner_modal" => {
+                    let partner = values
+                        .get("trade_partner_block")
+                        .and_then(|i| i.get("trade_partner_input"))
+                        .and_then(|s| s.get("selected_user"))
+                        .and_then(|s| s.as_str())
+                        .unwrap_or_default()
+                        .to_string();
+
+                    let db = dyn_db();
+                    let error = if partner == user.id {
+                        Some("You can't trade with yourself!")
+                    } else if !hacksteader::exists(&db, partner.clone()).await {
+                        Some("They'll need a hackstead before you can trade with them.")
+                    } else {
+                        None
+                    };
+                    if let Some(error) = error {
+                        return Ok(ActionResponse::Json(Json(json!({
+                            "response_action": "errors",
+                            "errors": { "trade_partner_block": error },
+                        }))));
+                    }
+
+                    let (mine, theirs) = futures::try_join!(
+                        Hacksteader::from_db(&db, user.id.clone()),
+                        Hacksteader::from_db(&db, partner.clone()),
+                    )?;
+
+                    return Ok(ActionResponse::Json(Json(json!({
+                        "response_action": "update",
+                        "view": {
+                            "type": "modal",
+                            "callback_id": "trade_offer_modal",
+                            "private_metadata": partner,
+                            "title": plain_text("Propose Trade"),
+                            "submit": plain_text("Offer!"),
+                            "blocks": trade_offer_blocks(
+                                &partner,
+                                trade_options(mine),
+                                trade_options(theirs)
+                            ),
+                        }
+                    }))));
+                }
+                "trade_offer_modal" => {
+                    use core::trade::TradeOffer;
+
+                    let partner = view.private_metadata.clone();
+                    let selected = |block: &str, input: &str| -> Vec<Key> {
+                        values
+                            .get(block)
+                            .and_then(|i| i.get(input))
+                            .and_then(|s| s.get("selected_options"))
+                            .and_then(|o| o.as_array())
+                            .map(|options| {
+                                options
+                                    .iter()
+                                    .filter_map(|o| {
+                                        serde_json::from_str(o.get("value")?.as_str()?).ok()
+                                    })
+                                    .collect()
+                            })
+                            .unwrap_or_default()
+                    };
+                    let offered = selected("trade_offered_block", "trade_offered_input");
+                    let requested = selected("trade_requested_block", "trade_requested_input");
+                    let gp = match values
+                        .get("trade_gp_block")
+                        .and_then(|i| i.get("trade_gp_input"))
+                        .and_then(|s| s.get("value"))
+                        .and_then(|s| s.as_str())
+                    {
+                        None | Some("") => Some(0),
+                        Some(gp) => gp.parse::<u64>().ok(),
+                    };
+
+                    let possessions = offered.len() + requested.len();
+                    let error = match gp {
+                        None => Some("That's not an amount of GP!".to_string()),
+                        Some(_) if possessions == 0 => {
+                            Some("You'll have to trade something!".to_string())
+                        }
+                        Some(_) if possessions > TradeOffer::MAX_POSSESSIONS => Some(format!(
+                            "You can only trade {} things at once.",
+                            TradeOffer::MAX_POSSESSIONS
+                        )),
+                        Some(_) => None,
+                    };
+                    if let Some(error) = error {
+                        return Ok(ActionResponse::Json(Json(json!({
+                            "response_action": "errors",
+                            "errors": { "trade_gp_block": error },
+                        }))));
+                    }
+
+                    let db = dyn_db();
+                    let offer = TradeOffer::new(
+                        user.id.clone(),
+                        partner.clone(),
+                        offered,
+                        requested,
+                        gp.unwrap(),
+                    );
+                    trade::propose(&db, &offer).await?;
+                    if offer.sent {
+                        trade::send(&db, &offer).await?;
+                    } else {
+                        banker::invoice(
+                            &user.id,
+                            offer.gp,
+                            &format!(
+                                "trade offer of {}gp to <@{}> :{}",
+                                offer.gp, partner, offer.id
+                            ),
+                        )
+                        .await?;
+                    }
+                    update_user_home_tab(user.id.clone()).await?;
+
+                    return Ok(ActionResponse::Json(Json(json!({
+                        "response_action": "clear",
+                    }))));
+                }
+                "trade_review_modal" => {
+                    let offer_id = uuid::Uuid::parse_str(&view.private_metadata)
+                        .map_err(|e| format!("couldn't parse trade offer id: {}", e))?;
+                    let db = dyn_db();
+                    let offer = trade::get_offer(&db, offer_id).await?;
+                    if offer.recipient != user.id {
+                        return Err(format!("{} can't accept trade {}", user.id, offer_id));
+                    }
+                    let (offered, requested) = trade::possessions(&db, &offer).await?;
+
+                    if !trade::execute(&db, &offer).await? {
+                        // it's no good anymore, so nobody should be left waiting on it
+                        let called_off = trade::call_off(&db, offer.id, user.id.clone()).await?;
+                        if let Some(offer) = called_off {
+                            trade::refund(&offer).await?;
+                        }
+                        dm_blocks(
+                            offer.proposer.clone(),
+                            vec![json!({
+                                "type": "section",
+                                "text": mrkdwn(format!(
+                                    "Your trade offer to <@{}> fell through, because something in it \
+                                    has changed hands or gone up on the market since you made it.",
+                                    offer.recipient
+                                )),
+                            })],
+                        )
+                        .await?;
+
+                        return Ok(ActionResponse::Json(Json(json!({
+                            "response_action": "update",
+                            "view": {
+                                "type": "modal",
+                                "callback_id": "trade_rejected_modal",
+                                "title": plain_text("Trade Offer"),
+                                "blocks": [comment(
+                                    "This trade can't go through anymore, because something in it \
+                                    has changed hands or gone up on the market since it was offered."
+                                )],
+                            }
+                        }))));
+                    }
+
+                    if offer.gp > 0 {
+                        banker::pay(
+                            offer.recipient.clone(),
+                            offer.gp,
+                            format!("your trade with <@{}>", offer.proposer),
+                        )
+                        .await?;
+                    }
+
+                    let mut blocks = vec![json!({
+                        "type": "section",
+                        "text": mrkdwn(format!(
+                            "<@{}> has accepted your trade offer!",
+                            offer.recipient
+                        )),
+                    })];
+                    blocks.append(&mut trade::summary_blocks(&offer, &offered, &requested));
+                    futures::try_join!(
+                        dm_blocks(offer.proposer.clone(), blocks),
+                        update_user_home_tab(offer.proposer.clone()),
+                        update_user_home_tab(offer.recipient.clone()),
+                    )?;
+
+                    return Ok(ActionResponse::Json(Json(json!({
+                        "response_action": "clear",
+                    }))));
+                }
+                "workbench_confirm_modal" => {
+                    let recipe: config::Recipe<config::ArchetypeHandle> =
+                        serde_json::from_str(&view.private_metadata).unwrap();
+
+                    to_farming
+                        .send(FarmingInputEvent::BeginWorkbenchCraft {
+                            user_id: user.id.clone(),
+                            recipe,
+                        })
+                        .expect("couldn't send to farming");
+
+                    return Ok(ActionResponse::Json(Json(json!({
+                        "response_action": "clear",
+                    }))));
+                }
                 _ => {}
             };
 
@@ -1676,18 +3964,90 @@ async fn action_endpoint(
                 .and_then(|s| s.as_str())
                 .and_then(|v| serde_json::from_str(v).ok())
             {
-                info!("applying item!");
-                let db = dyn_db();
-                Hacksteader::delete(&db, Key::misc(item_id))
-                    .await
-                    .map_err(|e| {
-                        let a = format!("couldn't remove item after applying: {}", e);
-                        error!("{}", a);
-                        a
-                    })?;
+                use core::effect::Application;
+
+                let plant = hacksteader::get_tile(&dyn_db(), tile_id)
+                    .await?
+                    .plant
+                    .ok_or_else(|| "can't apply items to empty land".to_string())?;
+                let item_name = CONFIG
+                    .possession_archetypes
+                    .get(item_ah)
+                    .map(|a| a.name.as_str())
+                    .unwrap_or("unknown");
+
+                match hacksteader::Effect::application(&plant.effects, item_ah) {
+                    Some(Application::Rejected(i)) => {
+                        return Ok(ActionResponse::Json(Json(json!({
+                            "response_action": "errors",
+                            "errors": {
+                                "item_apply_input": format!(
+                                    "This plant can't take another {} until its current one wears off. ({})",
+                                    item_name,
+                                    effect_remaining(&plant.effects[i]),
+                                ),
+                            }
+                        }))));
+                    }
+                    Some(Application::Replaced(i)) => {
+                        // make sure they know what they're throwing away
+                        return Ok(ActionResponse::Json(Json(json!({
+                            "response_action": "update",
+                            "view": {
+                                "type": "modal",
+                                "callback_id": "item_apply_confirm_modal",
+                                "private_metadata": serde_json::to_string(&(
+                                    tile_id,
+                                    item_id,
+                                    item_ah
+                                )).unwrap(),
+                                "title": plain_text("Replace Effect?"),
+                                "submit": plain_text("Replace it!"),
+                                "blocks": [json!({
+                                    "type": "section",
+                                    "text": mrkdwn(format!(
+                                        concat!(
+                                            "Using {} *{}* on your *{}* will start its effect over, ",
+                                            "wasting what's left of the current one:\n\n_{}_",
+                                        ),
+                                        emojify(item_name),
+                                        item_name,
+                                        plant.name,
+                                        effect_remaining(&plant.effects[i]),
+                                    )),
+                                })],
+                            }
+                        }))));
+                    }
+                    Some(_) => {}
+                    None => return Err(format!("{} can't be applied to plants", item_name)),
+                }
+
+                apply_item(&to_farming, user.id, tile_id, item_id, item_ah).await?;
+
+                return Ok(ActionResponse::Ok(()));
+            }
+            if let Some((tile_id, partner_id, makes)) = values
+                .get("crossbreed_input")
+                .and_then(|i| i.get("crossbreed_select"))
+                .and_then(|s| s.get("selected_option"))
+                .and_then(|s| s.get("value"))
+                .and_then(|s| s.as_str())
+                .and_then(|v| serde_json::from_str(v).ok())
+            {
+                info!("crossbreeding plants!");
+
+                to_farming
+                    .send(FarmingInputEvent::Crossbreed {
+                        tile_id,
+                        partner_id,
+                        makes,
+                        user_id: user.id.clone(),
+                    })
+                    .unwrap();
 
                 to_farming
-                    .send(FarmingInputEvent::ApplyItem(tile_id, item_ah))
+                    .send(FarmingInputEvent::ActivateUser(user.id.clone()))
                     .unwrap();
 
                 return Ok(ActionResponse::Ok(()));
@@ -1724,6 +4084,11 @@ async fn action_endpoint(
             let page_json = i.view.ok_or("no view!".to_string())?.private_metadata;
             //let page: PossessionPage = serde_json::from_str(&page_json)
             // .map_err(|e| dbg!(format!("couldn't parse {}: {}", page_json, e)))?;
+            let key: Key = serde_json::from_str(&page_json).map_err(|e| {
+                let a = format!("couldn't parse {}: {}", page_json, e);
+                error!("{}", a);
+                a
+            })?;
 
             Modal {
                 method: "push".to_string(),
@@ -1731,31 +4096,14 @@ async fn action_endpoint(
                 callback_id: "possession_sell_modal".to_string(),
                 title: "Sell Item".to_string(),
                 private_metadata: page_json,
-                blocks: vec![
-                    json!({
-                        "type": "input",
-                        "block_id": "possession_sell_price_block",
-                        "label": plain_text("Price (gp)"),
-                        "element": {
-                            "type": "plain_text_input",
-                            "action_id": "possession_sell_price_input",
-                            "placeholder": plain_text("Price Item"),
-                            "initial_value": "50",
-                        }
-                    }),
-                    json!({ "type": "divider" }),
-                    comment("As a form of confirmation, you'll get an invoice to pay before your Item goes up on the market. \
-                        To fund Harvests and to encourage Hacksteaders to keep prices sensible, \
-                        this invoice is 5% of the price of your sale \
-                        rounded down to the nearest GP (meaning that sales below 20gp aren't taxed at all)."),
-                ],
+                blocks: possession_sell_blocks(key.category),
                 submit: Some("Sell!".to_string()),
                 ..Default::default()
             }
             .launch()
             .await?
         }
-        "possession_give" => {
+        "possession_auction" => {
             let key_json = i.view.ok_or("no view!".to_string())?.private_metadata;
             let key: Key = serde_json::from_str(&key_json).map_err(|e| {
                 let a = format!("couldn't parse {}: {}", key_json, e);
@@ -1763,19 +4111,92 @@ async fn action_endpoint(
                 a
             })?;
 
-            let possession = hacksteader::get_possession(&dyn_db(), key).await?;
-
             Modal {
                 method: "push".to_string(),
                 trigger_id: i.trigger_id,
-                callback_id: "possession_give_modal".to_string(),
-                title: "Give Item".to_string(),
-                blocks: vec![json!({
-                    "type": "input",
-                    "block_id": "possession_give_receiver_block",
-                    "label": plain_text("Give Item"),
-                    "element": {
-                        "type": "users_select",
+                callback_id: "possession_auction_modal".to_string(),
+                title: "Auction Item".to_string(),
+                private_metadata: key_json,
+                blocks: vec![
+                    json!({
+                        "type": "input",
+                        "block_id": "possession_auction_reserve_block",
+                        "label": plain_text("Reserve price (gp)"),
+                        "element": {
+                            "type": "plain_text_input",
+                            "action_id": "possession_auction_reserve_input",
+                            "placeholder": plain_text("Lowest bid you'll take"),
+                            "initial_value": "50",
+                        }
+                    }),
+                    json!({
+                        "type": "input",
+                        "block_id": "possession_auction_hours_block",
+                        "label": plain_text("Length (hours)"),
+                        "element": {
+                            "type": "plain_text_input",
+                            "action_id": "possession_auction_hours_input",
+                            "initial_value": "24",
+                        }
+                    }),
+                    json!({ "type": "divider" }),
+                    comment(format!(
+                        concat!(
+                            "Whoever bids the most by the time your auction ends gets your item. ",
+                            "Bids are paid up front, and Banker refunds anyone who's outbid. ",
+                            "Like any other sale, a fee of {} ",
+                            "is taken out of the winning bid to fund Harvests. ",
+                            "If nobody bids at least your reserve price, you keep your item."
+                        ),
+                        CONFIG.market.fees.describe(key.category)
+                    )),
+                ],
+                submit: Some("Auction!".to_string()),
+            }
+            .launch()
+            .await?
+        }
+        "auction_bid" => {
+            let auction_id = uuid::Uuid::parse_str(&action.value).map_err(|e| {
+                let a = format!("couldn't parse auction id {}: {}", action.value, e);
+                error!("{}", a);
+                a
+            })?;
+            let auction = market::get_auction(&dyn_db(), auction_id).await?;
+
+            Modal {
+                method: "push".to_string(),
+                trigger_id: i.trigger_id,
+                callback_id: "auction_bid_modal".to_string(),
+                title: "Place Bid".to_string(),
+                private_metadata: auction.id.to_simple().to_string(),
+                blocks: auction_bid_blocks(&auction),
+                submit: Some("Bid!".to_string()),
+            }
+            .launch()
+            .await?
+        }
+        "possession_give" => {
+            let key_json = i.view.ok_or("no view!".to_string())?.private_metadata;
+            let key: Key = serde_json::from_str(&key_json).map_err(|e| {
+                let a = format!("couldn't parse {}: {}", key_json, e);
+                error!("{}", a);
+                a
+            })?;
+
+            let possession = hacksteader::get_possession(&dyn_db(), key).await?;
+
+            Modal {
+                method: "push".to_string(),
+                trigger_id: i.trigger_id,
+                callback_id: "possession_give_modal".to_string(),
+                title: "Give Item".to_string(),
+                blocks: vec![json!({
+                    "type": "input",
+                    "block_id": "possession_give_receiver_block",
+                    "label": plain_text("Give Item"),
+                    "element": {
+                        "type": "users_select",
                         "action_id": "possession_give_receiver_input",
                         "placeholder": plain_text("Who Really Gets your Gotchi?"),
                         "initial_user": ({
@@ -1819,6 +4240,42 @@ async fn action_endpoint(
 
             json!({})
         }
+        "craft_cancel" => {
+            let tile_id: uuid::Uuid = uuid::Uuid::parse_str(&action.value).unwrap();
+
+            to_farming
+                .send(FarmingInputEvent::CancelCraft(tile_id, i.user.id.clone()))
+                .unwrap();
+            to_farming
+                .send(FarmingInputEvent::ActivateUser(i.user.id.clone()))
+                .unwrap();
+
+            json!({})
+        }
+        "blueprint_study" => {
+            let blueprint_id: uuid::Uuid = uuid::Uuid::parse_str(&action.value).unwrap();
+
+            to_farming
+                .send(FarmingInputEvent::StudyBlueprint(blueprint_id, i.user.id.clone()))
+                .unwrap();
+            to_farming
+                .send(FarmingInputEvent::ActivateUser(i.user.id.clone()))
+                .unwrap();
+
+            json!({})
+        }
+        "uproot" => {
+            let tile_id: uuid::Uuid = uuid::Uuid::parse_str(&action.value).unwrap();
+
+            to_farming
+                .send(FarmingInputEvent::Uproot(tile_id, i.user.id.clone()))
+                .unwrap();
+            to_farming
+                .send(FarmingInputEvent::ActivateUser(i.user.id.clone()))
+                .unwrap();
+
+            json!({})
+        }
         "seed_plant" => {
             let tile_id: uuid::Uuid = uuid::Uuid::parse_str(&action.value).unwrap();
             let hs = match Hacksteader::from_db(&dyn_db(), i.user.id.clone()).await {
@@ -1863,8 +4320,10 @@ async fn action_endpoint(
                                         json!({
                                             "text": plain_text(format!("{} {}", emojify(&s.name), s.name)),
                                             "description": plain_text(format!(
-                                                "{} generations old", 
-                                                s.inner.pedigree.iter().map(|sg| sg.generations).sum::<u64>()
+                                                "{} generations old, x{:.2} speed, x{:.2} size",
+                                                s.inner.pedigree.iter().map(|sg| sg.generations).sum::<u64>(),
+                                                s.inner.traits.yield_speed,
+                                                s.inner.traits.yield_size,
                                             )),
                                             // this is fucky-wucky because value can only be 75 chars
                                             "value": serde_json::to_string(&(
@@ -1958,7 +4417,7 @@ async fn action_endpoint(
                     plant.archetype_handle
                 );
 
-            let sum = plant.advancements.sum(plant.xp, neighbor_bonuses.iter());
+            let sum = plant.advancements_sum(neighbor_bonuses.iter());
 
             let recipe = raw_recipe
                 .lookup_handles()
@@ -1992,6 +4451,13 @@ async fn action_endpoint(
                                     what.name
                                 )
                             })
+                            .chain(recipe.catalysts.iter().map(|what| {
+                                format!(
+                                    "{} _{}_ _(not used up)_",
+                                    emojify(&what.name),
+                                    what.name
+                                )
+                            }))
                             .collect::<Vec<_>>()
                             .join("\n"),
                         if recipe.destroys_plant {
@@ -2020,7 +4486,7 @@ async fn action_endpoint(
 
             let (tile_id, recipes): (
                 uuid::Uuid,
-                Vec<(bool, config::Recipe<config::ArchetypeHandle>)>,
+                Vec<(Vec<config::MissingRequirement>, config::Recipe<config::ArchetypeHandle>)>,
             ) = serde_json::from_str(&action.value).unwrap();
             let tile = hacksteader::get_tile(&dyn_db(), tile_id)
                 .await
@@ -2032,7 +4498,7 @@ async fn action_endpoint(
 
             let blocks = recipes
                 .into_iter()
-                .flat_map(|(possible, raw_recipe)| {
+                .flat_map(|(missing, raw_recipe)| {
                     let recipe = raw_recipe
                         .clone()
                         .lookup_handles()
@@ -2049,7 +4515,7 @@ async fn action_endpoint(
                             possible_output.description
                         )),
                     });
-                    if possible && tile.steader == i.user.id {
+                    if missing.is_empty() && tile.steader == i.user.id {
                         head.as_object_mut().unwrap().insert(
                             "accessory".to_string(),
                             json!({
@@ -2063,17 +4529,24 @@ async fn action_endpoint(
                                 "action_id": "crafting_confirm",
                             }),
                         );
+                    } else if let Some(index) = CONFIG
+                        .recipes()
+                        .iter()
+                        .position(|(_, r)| *r == raw_recipe)
+                        .filter(|_| !missing.is_empty())
+                    {
+                        head.as_object_mut().unwrap().insert(
+                            "accessory".to_string(),
+                            json!({
+                                "type": "button",
+                                "text": plain_text("Plan"),
+                                "value": serde_json::to_string(&PlanFor::Recipe(index)).unwrap(),
+                                "action_id": "crafting_planner",
+                            }),
+                        );
                     }
                     b.push(head);
-
-                    for (count, resource) in recipe.needs {
-                        b.push(comment(format!(
-                            "*{}* {} _{}_",
-                            count,
-                            emojify(&resource.name),
-                            resource.name
-                        )));
-                    }
+                    b.append(&mut recipe_requirement_blocks(&recipe, &missing));
                     b.push(json!({ "type": "divider" }));
 
                     b
@@ -2092,6 +4565,125 @@ async fn action_endpoint(
             .launch()
             .await?
         }
+        "workbench_confirm" => {
+            let recipe_json = &action.value;
+            let raw_recipe: config::Recipe<config::ArchetypeHandle> =
+                serde_json::from_str(&recipe_json).unwrap();
+
+            let recipe = raw_recipe
+                .lookup_handles()
+                .ok_or_else(|| "invalid recipe".to_string())?;
+            let possible_output = recipe.makes.any();
+
+            Modal {
+                method: "push".to_string(),
+                trigger_id: i.trigger_id,
+                callback_id: "workbench_confirm_modal".to_string(),
+                title: "Workbench Confirmation".to_string(),
+                private_metadata: recipe_json.to_string(),
+                blocks: vec![json!({
+                    "type": "section",
+                    "text": mrkdwn(format!(
+                        concat!(
+                            "Are you sure you want to spend the next ",
+                            "{:.2} minutes at your workbench crafting {} ",
+                            "using\n{}",
+                        ),
+                        recipe.time / FARM_CYCLES_PER_MIN as f32,
+                        recipe.makes,
+                        recipe
+                            .needs
+                            .iter()
+                            .map(|(n, what)| {
+                                format!(
+                                    "*{}* {} _{}_",
+                                    n,
+                                    emojify(&what.name),
+                                    what.name
+                                )
+                            })
+                            .chain(recipe.catalysts.iter().map(|what| {
+                                format!(
+                                    "{} _{}_ _(not used up)_",
+                                    emojify(&what.name),
+                                    what.name
+                                )
+                            }))
+                            .collect::<Vec<_>>()
+                            .join("\n"),
+                    )),
+                    "accessory": {
+                        "type": "image",
+                        "image_url": format!("http://{}/gotchi/img/{}/{}.png",
+                            *URL,
+                            possible_output.kind.category(),
+                            filify(&possible_output.name)
+                        ),
+                        "alt_text": "The thing you'd like to craft",
+                    }
+                })],
+                submit: Some("Craft!".to_string()),
+            }
+            .launch()
+            .await?
+        }
+        "workbench" => {
+            info!("workbench window");
+
+            let (steader, recipes): (String, Vec<(Vec<config::MissingRequirement>, config::Recipe<config::ArchetypeHandle>)>) =
+                serde_json::from_str(&action.value).unwrap();
+
+            let blocks = recipes
+                .into_iter()
+                .flat_map(|(missing, raw_recipe)| {
+                    let recipe = raw_recipe
+                        .clone()
+                        .lookup_handles()
+                        .expect("invalid archetype handle");
+                    let mut b = Vec::with_capacity(recipe.needs.len() + 2);
+                    let possible_output = recipe.makes.any();
+
+                    let mut head = json!({
+                        "type": "section",
+                        "text": mrkdwn(format!(
+                            "{} *{}*\n_{}_",
+                            emojify(&possible_output.name),
+                            possible_output.name,
+                            possible_output.description
+                        )),
+                    });
+                    if missing.is_empty() && steader == i.user.id {
+                        head.as_object_mut().unwrap().insert(
+                            "accessory".to_string(),
+                            json!({
+                                "type": "button",
+                                "style": "primary",
+                                "text": plain_text(format!("Craft {}", possible_output.name)),
+                                "value": serde_json::to_string(&raw_recipe).unwrap(),
+                                "action_id": "workbench_confirm",
+                            }),
+                        );
+                    }
+                    b.push(head);
+                    b.append(&mut recipe_requirement_blocks(&recipe, &missing));
+                    b.push(json!({ "type": "divider" }));
+
+                    b
+                })
+                .collect();
+
+            Modal {
+                method: "open".to_string(),
+                trigger_id: i.trigger_id,
+                callback_id: "workbench_modal".to_string(),
+                title: "Workbench".to_string(),
+                private_metadata: String::new(),
+                blocks,
+                submit: None,
+            }
+            .launch()
+            .await?
+        }
         "levels" => {
             let (ah, xp): (config::ArchetypeHandle, u64) =
                 serde_json::from_str(&action.value).unwrap();
@@ -2161,9 +4753,7 @@ async fn action_endpoint(
                 })
                 .chain(neighbor_bonuses.iter())
                 .collect::<Vec<_>>();
-            let sum = plant
-                .advancements
-                .sum(plant.xp, neighbor_bonuses.iter());
+            let sum = plant.advancements_sum(neighbor_bonuses.iter());
             let yield_farm_cycles = plant.base_yield_duration / sum.yield_speed_multiplier;
 
             let mut blocks = vec![];
@@ -2223,6 +4813,20 @@ async fn action_endpoint(
                 }
             }
 
+            blocks.push(json!({
+                "type": "section",
+                "text": mrkdwn(format!(
+                    concat!(
+                        "*Traits*\n",
+                        "Inherited from its seed: ",
+                        "*x{:.3}* speed, *x{:.3}* size, *x{:.3}* xp",
+                    ),
+                    plant.traits.yield_speed,
+                    plant.traits.yield_size,
+                    plant.traits.xp_rate,
+                ))
+            }));
+
             blocks.push(json!({
                 "type": "section",
                 "text": mrkdwn("*Yield Items*".to_string())
@@ -2250,158 +4854,1016 @@ async fn action_endpoint(
                     name,
                 )));
             }
-
+
+            Modal {
+                method: "open".to_string(),
+                trigger_id: i.trigger_id,
+                callback_id: "yield_stats_modal".to_string(),
+                title: "Yield Stats".to_string(),
+                private_metadata: String::new(),
+                blocks,
+                submit: None,
+            }
+            .launch()
+            .await?
+        }
+        "item_apply" => {
+            use config::ApplicationEffect::*;
+            use futures::stream::{self, StreamExt, TryStreamExt};
+
+            let (tile_id, applicable_ids): (uuid::Uuid, Vec<uuid::Uuid>) =
+                serde_json::from_str(&action.value).unwrap();
+            let db = dyn_db();
+
+            let applicables = stream::iter(applicable_ids)
+                .map(|id| hacksteader::get_possession(&db, Key::misc(id)))
+                .buffer_unordered(50)
+                .try_collect::<Vec<Possession>>()
+                .await
+                .map_err(|e| {
+                    let a = format!("couldn't get applicable: {}", e);
+                    error!("{}", a);
+                    a
+                })?
+                .into_iter()
+                .map(|x| x.try_into())
+                .collect::<Result<Vec<Possessed<possess::Keepsake>>, _>>()
+                .map_err(|e| {
+                    let a = format!("couldn't transform fetched applicable: {}", e);
+                    error!("{}", a);
+                    a
+                })?;
+
+            Modal {
+                method: "open".to_string(),
+                trigger_id: i.trigger_id,
+                callback_id: "item_apply_modal".to_string(),
+                title: "Item + Plant = :D".to_string(),
+                private_metadata: String::new(),
+                blocks: vec![json!({
+                    "type": "input",
+                    "label": plain_text("Item Select"),
+                    "block_id": "item_apply_input",
+                    "element": {
+                        "type": "static_select",
+                        "placeholder": plain_text("Which item do ya wanna use on this plant?"),
+                        "action_id": "item_apply_select",
+                        // show them each applicable item they have sorted by category
+                        "options": applicables
+                            .into_iter()
+                            .filter_map(|i| {
+                                Some(json!({
+                                    "text": plain_text(format!("{} {}", emojify(&i.name), i.name)),
+                                    "description": plain_text(match i.inner.item_application_effect.as_ref()? {
+                                        TimeIncrease { extra_cycles, duration_cycles } => format!(
+                                            "~{} hours pass in {} minutes",
+                                            extra_cycles / FARM_CYCLES_PER_MIN / 60,
+                                            duration_cycles / FARM_CYCLES_PER_MIN,
+                                        ),
+                                        YieldSizeBoost { multiplier, duration_cycles } => format!(
+                                            "{}x yields for {} minutes",
+                                            multiplier,
+                                            duration_cycles / FARM_CYCLES_PER_MIN,
+                                        ),
+                                        XpBoost { extra_xp, duration_cycles } => format!(
+                                            "{}x xp for {} minutes",
+                                            extra_xp + 1,
+                                            duration_cycles / FARM_CYCLES_PER_MIN,
+                                        ),
+                                        FinishCraft => "finishes a craft instantly".to_string(),
+                                        GuaranteedSeed => "next yield comes with a seed".to_string(),
+                                    }),
+                                    // this is fucky-wucky because value can only be 75 chars
+                                    "value": serde_json::to_string(&(
+                                        &tile_id.to_simple().to_string(),
+                                        i.id.to_simple().to_string(),
+                                        i.archetype_handle
+                                    )).unwrap(),
+                                }))
+                            })
+                            .collect::<Vec<Value>>(),
+                    }
+                })],
+                submit: Some("Apply!".to_string()),
+            }
+            .launch()
+            .await?
+        }
+        "crossbreed" => {
+            let tile_id: uuid::Uuid = uuid::Uuid::parse_str(&action.value).unwrap();
+            let hs = Hacksteader::from_db(&dyn_db(), i.user.id.clone()).await?;
+            let plant = hs
+                .land
+                .iter()
+                .find_map(|tile| tile.plant.as_ref().filter(|_| tile.id == tile_id))
+                .ok_or_else(|| format!("no plant to crossbreed on tile {}", tile_id))?;
+
+            Modal {
+                method: "open".to_string(),
+                trigger_id: i.trigger_id,
+                callback_id: "crossbreed_modal".to_string(),
+                title: "Crossbreed Plants".to_string(),
+                private_metadata: String::new(),
+                blocks: vec![
+                    json!({
+                        "type": "input",
+                        "label": plain_text("Hybrid Select"),
+                        "block_id": "crossbreed_input",
+                        "element": {
+                            "type": "static_select",
+                            "placeholder": plain_text("Which plant should this one be crossed with?"),
+                            "action_id": "crossbreed_select",
+                            // show them each hybrid they could make with each of their other plants
+                            "options": hs
+                                .land
+                                .iter()
+                                .filter(|t| t.id != tile_id)
+                                .filter_map(|t| Some((t.id, t.plant.as_ref()?)))
+                                .filter(|(_, partner)| partner.ready_to_crossbreed())
+                                .flat_map(|(partner_id, partner)| {
+                                    CONFIG
+                                        .find_hybrids(&plant.name, &partner.name)
+                                        .map(move |ah| {
+                                            let arch = &CONFIG.possession_archetypes[ah];
+                                            json!({
+                                                "text": plain_text(format!("{} {}", emojify(&arch.name), arch.name)),
+                                                "description": plain_text(format!(
+                                                    "with your {}lvl {}",
+                                                    partner.advancements.current_position(partner.xp),
+                                                    partner.name
+                                                )),
+                                                // this is fucky-wucky because value can only be 75 chars
+                                                "value": serde_json::to_string(&(
+                                                    &tile_id.to_simple().to_string(),
+                                                    partner_id.to_simple().to_string(),
+                                                    ah
+                                                )).unwrap(),
+                                            })
+                                        })
+                                })
+                                .collect::<Vec<Value>>(),
+                        }
+                    }),
+                    comment(concat!(
+                        "Crossbreeding uses up the current yield of both plants, ",
+                        "so both need to be at least halfway to their next yield."
+                    )),
+                ],
+                submit: Some("Crossbreed!".to_string()),
+            }
+            .launch()
+            .await?
+        }
+        "possession_history" => {
+            let key: Key = serde_json::from_str(&action.value).map_err(|e| {
+                let a = format!("couldn't parse {}: {}", action.value, e);
+                error!("{}", a);
+                a
+            })?;
+            let possession = hacksteader::get_possession(&dyn_db(), key).await?;
+
+            let mut blocks = ownership_log_blocks(&possession.ownership_log);
+            if let Some(s) = possession.kind.seed() {
+                blocks.push(json!({ "type": "divider" }));
+                blocks.append(&mut pedigree_blocks(&s.pedigree));
+            }
+
+            Modal {
+                method: "push".to_string(),
+                trigger_id: i.trigger_id,
+                callback_id: "possession_history_modal".to_string(),
+                title: "History".to_string(),
+                private_metadata: String::new(),
+                blocks,
+                submit: None,
+            }
+            .launch()
+            .await?
+        }
+        "plant_history" => {
+            let tile_id: uuid::Uuid = uuid::Uuid::parse_str(&action.value).unwrap();
+            let tile = hacksteader::get_tile(&dyn_db(), tile_id).await?;
+            let plant = tile
+                .plant
+                .ok_or_else(|| format!("no plant on tile {} to show history of", tile_id))?;
+
+            let mut blocks = vec![comment(format!(
+                "This *{}* is growing on <@{}>'s land.",
+                plant.name, tile.steader
+            ))];
+            blocks.append(&mut pedigree_blocks(&plant.pedigree));
+
+            Modal {
+                method: "open".to_string(),
+                trigger_id: i.trigger_id,
+                callback_id: "plant_history_modal".to_string(),
+                title: "History".to_string(),
+                private_metadata: String::new(),
+                blocks,
+                submit: None,
+            }
+            .launch()
+            .await?
+        }
+        "gotchi_nickname" => {
+            Modal {
+                method: "push".to_string(),
+                trigger_id: i.trigger_id,
+                callback_id: "gotchi_nickname_modal".to_string(),
+                title: "Nickname Gotchi".to_string(),
+                private_metadata: i.view.ok_or("no view!".to_string())?.private_metadata,
+                blocks: vec![json!({
+                    "type": "input",
+                    "block_id": "gotchi_nickname_block",
+                    "label": plain_text("Nickname Gotchi"),
+                    "element": {
+                        "type": "plain_text_input",
+                        "action_id": "gotchi_nickname_input",
+                        "placeholder": plain_text("Nickname Gotchi"),
+                        "initial_value": action.value,
+                        "min_length": 1,
+                        "max_length": 25,
+                    }
+                })],
+                submit: Some("Change it!".to_string()),
+                ..Default::default()
+            }
+            .launch()
+            .await?
+        }
+        "possession_market_overview_page" => {
+            let page_json = &action.value;
+            let (item_name, cat): (String, Category) = serde_json::from_str(page_json).unwrap();
+
+            let page = PossessionOverviewPage {
+                credentials: if hacksteader::exists(&dyn_db(), i.user.id.clone()).await {
+                    Credentials::Hacksteader
+                } else {
+                    Credentials::None
+                },
+                page: 0,
+                interactivity: Interactivity::Buy,
+                source: PossessionOverviewSource::Market(cat),
+                item_name,
+            };
+
+            // watchers are sent this from outside of any modal
+            let method = if i.view.is_some() { "push" } else { "open" };
+            page.modal(i.trigger_id, method).await?.launch().await?
+        }
+        "push_possession_page" => {
+            let page_json = action.value;
+            let mut page: PossessionPage = serde_json::from_str(&page_json).unwrap();
+
+            if page.possession.steader == i.user.id {
+                page.credentials = Credentials::Owner;
+            }
+
+            page.modal(i.trigger_id, "push").launch().await?
+        }
+        "possession_page" => {
+            let page_json = action.value;
+            let mut page: PossessionPage = serde_json::from_str(&page_json).unwrap();
+
+            if page.possession.steader == i.user.id {
+                page.credentials = Credentials::Owner;
+            }
+
+            page.modal(i.trigger_id, "open").launch().await?
+        }
+        "possession_overview_page" => {
+            let page_json = action.value;
+            let page: PossessionOverviewPage = serde_json::from_str(&page_json).unwrap();
+
+            page.modal(i.trigger_id, "open").await?.launch().await?
+        }
+        "crafting_planner" => {
+            let plan_for: PlanFor = serde_json::from_str(&action.value).map_err(|e| {
+                let a = format!("couldn't parse planner target {}: {}", action.value, e);
+                error!("{}", a);
+                a
+            })?;
+            let blocks = crafting_planner_blocks(plan_for, i.user.id.clone()).await?;
+
+            match i.view.filter(|v| v.callback_id == "encyclopedia_modal") {
+                // keep room on the modal stack for opening the listings
+                Some(view) => ModalUpdate {
+                    trigger_id: i.trigger_id,
+                    callback_id: "crafting_planner_modal".to_string(),
+                    title: "Crafting Planner".to_string(),
+                    private_metadata: String::new(),
+                    hash: None,
+                    view_id: view.id,
+                    blocks,
+                    submit: None,
+                }
+                .launch()
+                .await?,
+                None => Modal {
+                    method: "push".to_string(),
+                    trigger_id: i.trigger_id,
+                    callback_id: "crafting_planner_modal".to_string(),
+                    title: "Crafting Planner".to_string(),
+                    private_metadata: String::new(),
+                    blocks,
+                    submit: None,
+                }
+                .launch()
+                .await?,
+            }
+        }
+        "hackmarket_page" => {
+            let query: MarketQuery = serde_json::from_str(&action.value).map_err(|e| {
+                let a = format!("couldn't parse market query {}: {}", action.value, e);
+                error!("{}", a);
+                a
+            })?;
+            let view = i.view.ok_or_else(|| "no view!".to_string())?;
+
+            ModalUpdate {
+                trigger_id: i.trigger_id,
+                callback_id: view.callback_id,
+                title: "Hackstreet!".to_string(),
+                private_metadata: action.value,
+                hash: None,
+                view_id: view.id,
+                blocks: hackmarket_blocks(&query, i.user.id.clone()).await,
+                submit: None,
+            }
+            .launch()
+            .await?
+        }
+        "hackmarket_filter" => {
+            let query: MarketQuery = serde_json::from_str(&action.value).map_err(|e| {
+                let a = format!("couldn't parse market query {}: {}", action.value, e);
+                error!("{}", a);
+                a
+            })?;
+
+            Modal {
+                method: "push".to_string(),
+                trigger_id: i.trigger_id,
+                callback_id: "hackmarket_filter_modal".to_string(),
+                title: "Search & Filter".to_string(),
+                private_metadata: action.value,
+                blocks: hackmarket_filter_blocks(&query),
+                submit: Some("Search".to_string()),
+            }
+            .launch()
+            .await?
+        }
+        "watchlist" => {
+            let profile = hacksteader::get_profile(&dyn_db(), i.user.id.clone()).await?;
+
+            Modal {
+                method: "push".to_string(),
+                trigger_id: i.trigger_id,
+                callback_id: "watchlist_modal".to_string(),
+                title: "Watchlist".to_string(),
+                private_metadata: String::new(),
+                blocks: watchlist_blocks(&profile.watches),
+                submit: if profile.watches.len() < Watch::MAX_PER_PROFILE {
+                    Some("Watch".to_string())
+                } else {
+                    None
+                },
+            }
+            .launch()
+            .await?
+        }
+        "watch_remove" => {
+            let ah: config::ArchetypeHandle = action.value.parse().map_err(|e| {
+                let a = format!("couldn't parse watched archetype {}: {}", action.value, e);
+                error!("{}", a);
+                a
+            })?;
+            let view = i.view.ok_or_else(|| "no view!".to_string())?;
+            let mut profile = hacksteader::get_profile(&dyn_db(), i.user.id.clone()).await?;
+
+            to_farming
+                .send(FarmingInputEvent::UnwatchMarket(ah, i.user.id.clone()))
+                .unwrap();
+            to_farming
+                .send(FarmingInputEvent::ActivateUser(i.user.id.clone()))
+                .unwrap();
+
+            // the farming loop won't have gotten to it yet
+            profile.watches.retain(|w| w.archetype_handle != ah);
+            ModalUpdate {
+                trigger_id: i.trigger_id,
+                callback_id: view.callback_id,
+                title: "Watchlist".to_string(),
+                private_metadata: view.private_metadata,
+                hash: None,
+                view_id: view.id,
+                blocks: watchlist_blocks(&profile.watches),
+                submit: Some("Watch".to_string()),
+            }
+            .launch()
+            .await?
+        }
+        "shop" => {
+            let db = dyn_db();
+            let can_buy = hacksteader::exists(&db, i.user.id.clone()).await;
+            let sold = shop::sold_today(&db).await?;
+
+            Modal {
+                method: "push".to_string(),
+                trigger_id: i.trigger_id,
+                callback_id: "shop_modal".to_string(),
+                title: format!("{}'s Shop", CONFIG.shop.keeper),
+                private_metadata: String::new(),
+                blocks: shop::blocks(&sold, can_buy),
+                submit: None,
+            }
+            .launch()
+            .await?
+        }
+        "shop_buy" => {
+            let ah: config::ArchetypeHandle = action.value.parse().map_err(|e| {
+                let a = format!("couldn't parse shop item {}: {}", action.value, e);
+                error!("{}", a);
+                a
+            })?;
+            let item = CONFIG
+                .shop
+                .item(ah)
+                .ok_or_else(|| format!("the shop doesn't sell archetype {}", ah))?;
+            let sold = shop::sold_today(&dyn_db())
+                .await?
+                .get(&ah)
+                .copied()
+                .unwrap_or(0);
+
+            Modal {
+                method: "push".to_string(),
+                trigger_id: i.trigger_id,
+                callback_id: "shop_buy_modal".to_string(),
+                title: "Buy from the Shop".to_string(),
+                private_metadata: action.value,
+                blocks: vec![
+                    json!({
+                        "type": "section",
+                        "text": mrkdwn(format!(
+                            "{} *{}* for *{}gp* each, _{} left today_",
+                            emojify(&item.name),
+                            item.name,
+                            CONFIG.shop.price(item, sold),
+                            item.daily_stock.saturating_sub(sold),
+                        )),
+                    }),
+                    json!({
+                        "type": "input",
+                        "block_id": "shop_buy_quantity_block",
+                        "label": plain_text("How many"),
+                        "element": {
+                            "type": "plain_text_input",
+                            "action_id": "shop_buy_quantity_input",
+                            "initial_value": "1",
+                        }
+                    }),
+                    comment(format!(
+                        "You'll get an invoice for the lot. If {} runs out before you pay it, \
+                        you'll get your GP back.",
+                        CONFIG.shop.keeper
+                    )),
+                ],
+                submit: Some("Buy".to_string()),
+            }
+            .launch()
+            .await?
+        }
+        "buy_order_post" => {
+            Modal {
+                method: "push".to_string(),
+                trigger_id: i.trigger_id,
+                callback_id: "buy_order_modal".to_string(),
+                title: "Post Buy Order".to_string(),
+                private_metadata: String::new(),
+                blocks: vec![
+                    json!({
+                        "type": "input",
+                        "block_id": "buy_order_archetype_block",
+                        "label": plain_text("Item"),
+                        "element": {
+                            "type": "static_select",
+                            "action_id": "buy_order_archetype_input",
+                            "placeholder": plain_text("What are you looking to buy?"),
+                            "options": CONFIG
+                                .possession_archetypes
+                                .iter()
+                                .enumerate()
+                                .take(100)
+                                .map(|(ah, a)| json!({
+                                    "text": plain_text(format!("{} {}", emojify(&a.name), a.name)),
+                                    "value": ah.to_string(),
+                                }))
+                                .collect::<Vec<_>>(),
+                        }
+                    }),
+                    json!({
+                        "type": "input",
+                        "block_id": "buy_order_price_block",
+                        "label": plain_text("Most you'll pay for each (gp)"),
+                        "element": {
+                            "type": "plain_text_input",
+                            "action_id": "buy_order_price_input",
+                            "initial_value": "50",
+                        }
+                    }),
+                    json!({
+                        "type": "input",
+                        "block_id": "buy_order_quantity_block",
+                        "label": plain_text("How many"),
+                        "element": {
+                            "type": "plain_text_input",
+                            "action_id": "buy_order_quantity_input",
+                            "initial_value": "1",
+                        }
+                    }),
+                    json!({ "type": "divider" }),
+                    comment(concat!(
+                        "You'll get an invoice for your price times how many you want. ",
+                        "Banker holds onto that GP until your order is filled, ",
+                        "or until you cancel it for a refund. ",
+                        "If your order buys something listed for less than your price, ",
+                        "you'll get the difference back."
+                    )),
+                ],
+                submit: Some("Post!".to_string()),
+            }
+            .launch()
+            .await?
+        }
+        "buy_order_cancel" => {
+            let order_id = uuid::Uuid::parse_str(&action.value).map_err(|e| {
+                let a = format!("couldn't parse buy order id {}: {}", action.value, e);
+                error!("{}", a);
+                a
+            })?;
+            let order = market::cancel_buy_order(&dyn_db(), order_id, i.user.id.clone()).await?;
+            banker::pay(
+                order.buyer.clone(),
+                order.escrow(),
+                format!("refund for your cancelled buy order for {}", order.name()),
+            )
+            .await?;
+
+            match i.view {
+                Some(view) => {
+                    let query = serde_json::from_str(&view.private_metadata)
+                        .unwrap_or_else(|_| MarketQuery::new(Category::Misc));
+                    ModalUpdate {
+                        trigger_id: i.trigger_id,
+                        callback_id: view.callback_id,
+                        title: "Hackstreet!".to_string(),
+                        private_metadata: view.private_metadata,
+                        hash: None,
+                        view_id: view.id,
+                        blocks: hackmarket_blocks(&query, i.user.id.clone()).await,
+                        submit: None,
+                    }
+                    .launch()
+                    .await?
+                }
+                None => json!({}),
+            }
+        }
+        "buy_order_fill_list" => {
+            let key: Key = serde_json::from_str(&action.value).map_err(|e| {
+                let a = format!("couldn't parse possession key {}: {}", action.value, e);
+                error!("{}", a);
+                a
+            })?;
+            let possession = hacksteader::get_possession(&dyn_db(), key).await?;
+            let orders = market::buy_orders_for(&dyn_db(), possession.archetype_handle).await?;
+
+            Modal {
+                method: "push".to_string(),
+                trigger_id: i.trigger_id,
+                callback_id: "buy_orders_modal".to_string(),
+                title: "Buy Orders".to_string(),
+                private_metadata: String::new(),
+                blocks: buy_order_fill_blocks(&possession, &orders),
+                submit: None,
+            }
+            .launch()
+            .await?
+        }
+        "buy_order_fill" => {
+            let (order_id, key): (uuid::Uuid, Key) =
+                serde_json::from_str(&action.value).map_err(|e| {
+                    let a = format!("couldn't parse buy order fill {}: {}", action.value, e);
+                    error!("{}", a);
+                    a
+                })?;
+            let possession = hacksteader::get_possession(&dyn_db(), key).await?;
+            if possession.steader != i.user.id {
+                return Err("you can only sell what's yours".to_string());
+            }
+            let name = possession.name.clone();
+
+            let filled = market::fill_buy_order(&dyn_db(), order_id, possession, None).await?;
+            update_user_home_tab(i.user.id.clone()).await?;
+
+            let view = i.view.ok_or_else(|| "no view!".to_string())?;
+            ModalUpdate {
+                trigger_id: i.trigger_id,
+                callback_id: view.callback_id,
+                title: "Buy Orders".to_string(),
+                private_metadata: String::new(),
+                hash: None,
+                view_id: view.id,
+                blocks: vec![json!({
+                    "type": "section",
+                    "text": mrkdwn(if filled {
+                        format!(
+                            "You've sold your {} *{}*! Check your DMs for the details.",
+                            emojify(&name),
+                            name
+                        )
+                    } else {
+                        concat!(
+                            "Someone beat you to it, ",
+                            "that buy order's already been filled or cancelled ",
+                            "(or what you tried to sell is up for auction)."
+                        )
+                        .to_string()
+                    }),
+                })],
+                submit: None,
+            }
+            .launch()
+            .await?
+        }
+        "market_stats" => {
+            let ah: config::ArchetypeHandle = action.value.parse().map_err(|e| {
+                let a = format!("couldn't parse archetype handle {}: {}", action.value, e);
+                error!("{}", a);
+                a
+            })?;
+            let week = std::time::Duration::from_secs(60 * 60 * 24 * 7);
+            let records =
+                market::sale_records(&dyn_db(), Some(ah), std::time::SystemTime::now() - week)
+                    .await?;
+
+            Modal {
+                method: "push".to_string(),
+                trigger_id: i.trigger_id,
+                callback_id: "market_stats_modal".to_string(),
+                title: "Price History".to_string(),
+                private_metadata: String::new(),
+                blocks: market_stats_blocks(ah, &records),
+                submit: None,
+            }
+            .launch()
+            .await?
+        }
+        "bulk_sell" => {
+            let ah: config::ArchetypeHandle = action.value.parse().map_err(|e| {
+                let a = format!("couldn't parse archetype handle {}: {}", action.value, e);
+                error!("{}", a);
+                a
+            })?;
+            let archetype = CONFIG
+                .possession_archetypes
+                .get(ah)
+                .ok_or_else(|| format!("no archetype {}", ah))?;
+            let unsold = Hacksteader::from_db(&dyn_db(), i.user.id.clone())
+                .await?
+                .inventory
+                .into_iter()
+                .filter(|p| p.archetype_handle == ah && p.sale.is_none())
+                .count();
+
+            Modal {
+                method: "push".to_string(),
+                trigger_id: i.trigger_id,
+                callback_id: "bulk_sell_modal".to_string(),
+                title: "Sell Many".to_string(),
+                private_metadata: ah.to_string(),
+                blocks: vec![
+                    json!({
+                        "type": "input",
+                        "block_id": "bulk_sell_quantity_block",
+                        "label": plain_text(format!("How many (you have {})", unsold)),
+                        "element": {
+                            "type": "plain_text_input",
+                            "action_id": "bulk_sell_quantity_input",
+                            "initial_value": unsold.to_string(),
+                        }
+                    }),
+                    json!({
+                        "type": "input",
+                        "block_id": "bulk_sell_price_block",
+                        "label": plain_text("Price for each (gp)"),
+                        "element": {
+                            "type": "plain_text_input",
+                            "action_id": "bulk_sell_price_input",
+                            "initial_value": "50",
+                        }
+                    }),
+                    json!({ "type": "divider" }),
+                    comment(format!(
+                        concat!(
+                            "You'll get one invoice for the market fees on all of them. ",
+                            "The fee for each one is {}. ",
+                            "Each one goes up on the market on its own, ",
+                            "so buyers can take as many or as few as they like."
+                        ),
+                        CONFIG.market.fees.describe(archetype.kind.category())
+                    )),
+                ],
+                submit: Some("Sell!".to_string()),
+            }
+            .launch()
+            .await?
+        }
+        "listing_relist" => {
+            let (key, price): (Key, u64) = serde_json::from_str(&action.value).map_err(|e| {
+                let a = format!("couldn't parse relisting {}: {}", action.value, e);
+                error!("{}", a);
+                a
+            })?;
+            let possession = hacksteader::get_possession(&dyn_db(), key).await?;
+
+            if possession.steader != i.user.id || possession.sale.is_some() {
+                mrkdwn("That's either changed hands or is already back up for sale.")
+            } else if CONFIG.market.fees.fee(possession.kind.category(), price) == 0 {
+                // there's no sense in sending an invoice for nothing
+                let name = possession.name.clone();
+                market::list(&dyn_db(), possession, price, name).await?;
+
+                mrkdwn("It's back up for sale!")
+            } else {
+                banker::invoice(
+                    &i.user.id,
+                    CONFIG.market.fees.fee(possession.kind.category(), price),
+                    &format!(
+                        "hackmarket fees for selling {} at {}gp :{}:{}",
+                        possession.name,
+                        price,
+                        possession.id,
+                        possession.kind.category() as u8
+                    ),
+                )
+                .await?;
+
+                mrkdwn("Check your DMs from Banker for the invoice to relist it!")
+            }
+        }
+        "listing_relist_new_price" => {
+            let key: Key = serde_json::from_str(&action.value).map_err(|e| {
+                let a = format!("couldn't parse relisting {}: {}", action.value, e);
+                error!("{}", a);
+                a
+            })?;
+            let possession = hacksteader::get_possession(&dyn_db(), key).await?;
+
+            if possession.steader != i.user.id || possession.sale.is_some() {
+                mrkdwn("That's either changed hands or is already back up for sale.")
+            } else {
+                Modal {
+                    method: "open".to_string(),
+                    trigger_id: i.trigger_id,
+                    callback_id: "possession_sell_modal".to_string(),
+                    title: "Relist Item".to_string(),
+                    private_metadata: action.value,
+                    blocks: possession_sell_blocks(key.category),
+                    submit: Some("Sell!".to_string()),
+                }
+                .launch()
+                .await?
+            }
+        }
+        "bulk_buy" => {
+            let ah: config::ArchetypeHandle = action.value.parse().map_err(|e| {
+                let a = format!("couldn't parse archetype handle {}: {}", action.value, e);
+                error!("{}", a);
+                a
+            })?;
+            let archetype = CONFIG
+                .possession_archetypes
+                .get(ah)
+                .ok_or_else(|| format!("no archetype {}", ah))?;
+            let mut prices: Vec<u64> = market::market_search(&dyn_db(), archetype.kind.category())
+                .await?
+                .into_iter()
+                .filter(|(_, p)| p.archetype_handle == ah && p.steader != i.user.id)
+                .map(|(sale, _)| sale.price)
+                .collect();
+            prices.sort_unstable();
+
+            Modal {
+                method: "push".to_string(),
+                trigger_id: i.trigger_id,
+                callback_id: "bulk_buy_modal".to_string(),
+                title: "Buy Many".to_string(),
+                private_metadata: ah.to_string(),
+                blocks: vec![
+                    json!({
+                        "type": "section",
+                        "text": mrkdwn(match (prices.first(), prices.last()) {
+                            (Some(cheapest), Some(priciest)) => format!(
+                                "*{}* {} *{}* for sale, from *{}gp* to *{}gp*.",
+                                prices.len(),
+                                emojify(&archetype.name),
+                                archetype.name,
+                                cheapest,
+                                priciest
+                            ),
+                            _ => format!("There aren't any *{}* for sale.", archetype.name),
+                        }),
+                    }),
+                    json!({
+                        "type": "input",
+                        "block_id": "bulk_buy_quantity_block",
+                        "label": plain_text("How many"),
+                        "element": {
+                            "type": "plain_text_input",
+                            "action_id": "bulk_buy_quantity_input",
+                            "initial_value": "1",
+                        }
+                    }),
+                    json!({
+                        "type": "input",
+                        "block_id": "bulk_buy_price_block",
+                        "label": plain_text("Most you'll pay for each (gp)"),
+                        "element": {
+                            "type": "plain_text_input",
+                            "action_id": "bulk_buy_price_input",
+                            "initial_value": prices.first().unwrap_or(&50).to_string(),
+                        }
+                    }),
+                    json!({ "type": "divider" }),
+                    comment(concat!(
+                        "You'll get an invoice for your price times how many you want. ",
+                        "Once it's paid, you'll buy up the cheapest ones ",
+                        "listed for your price or less, ",
+                        "and get back whatever GP wasn't spent."
+                    )),
+                ],
+                submit: Some("Buy!".to_string()),
+            }
+            .launch()
+            .await?
+        }
+        "trade_propose" => {
             Modal {
                 method: "open".to_string(),
                 trigger_id: i.trigger_id,
-                callback_id: "yield_stats_modal".to_string(),
-                title: "Yield Stats".to_string(),
+                callback_id: "trade_partner_modal".to_string(),
+                title: "Propose Trade".to_string(),
                 private_metadata: String::new(),
-                blocks,
-                submit: None,
+                blocks: vec![
+                    json!({
+                        "type": "input",
+                        "block_id": "trade_partner_block",
+                        "label": plain_text("Trade with"),
+                        "element": {
+                            "type": "users_select",
+                            "action_id": "trade_partner_input",
+                            "placeholder": plain_text("Who do you want to trade with?"),
+                        }
+                    }),
+                    comment("Next, you'll get to pick what you'd like to swap."),
+                ],
+                submit: Some("Next".to_string()),
             }
             .launch()
             .await?
         }
-        "item_apply" => {
-            use config::ApplicationEffect::*;
-            use futures::stream::{self, StreamExt, TryStreamExt};
-
-            let (tile_id, applicable_ids): (uuid::Uuid, Vec<uuid::Uuid>) =
-                serde_json::from_str(&action.value).unwrap();
+        "trade_review" => {
+            let offer_id = uuid::Uuid::parse_str(&action.value).map_err(|e| {
+                let a = format!("couldn't parse trade offer id {}: {}", action.value, e);
+                error!("{}", a);
+                a
+            })?;
             let db = dyn_db();
-
-            let applicables = stream::iter(applicable_ids)
-                .map(|id| hacksteader::get_possession(&db, Key::misc(id)))
-                .buffer_unordered(50)
-                .try_collect::<Vec<Possession>>()
+            let offer = trade::get_offer(&db, offer_id)
                 .await
-                .map_err(|e| {
-                    let a = format!("couldn't get applicable: {}", e);
-                    error!("{}", a);
-                    a
-                })?
-                .into_iter()
-                .map(|x| x.try_into())
-                .collect::<Result<Vec<Possessed<possess::Keepsake>>, _>>()
-                .map_err(|e| {
-                    let a = format!("couldn't transform fetched applicable: {}", e);
-                    error!("{}", a);
-                    a
-                })?;
+                .ok()
+                .filter(|o| o.sent && o.recipient == i.user.id);
+
+            let (blocks, submit) = match offer {
+                Some(offer) => {
+                    let (offered, requested) = trade::possessions(&db, &offer).await?;
+                    let mut blocks = trade::summary_blocks(&offer, &offered, &requested);
+                    blocks.push(json!({
+                        "type": "actions",
+                        "elements": [{
+                            "type": "button",
+                            "style": "danger",
+                            "text": plain_text("Reject"),
+                            "value": offer.id.to_simple().to_string(),
+                            "action_id": "trade_reject",
+                            "confirm": {
+                                "style": "danger",
+                                "title": plain_text("Reject this offer?"),
+                                "text": mrkdwn(format!(
+                                    "<@{}> will be told that you've turned it down.",
+                                    offer.proposer
+                                )),
+                                "deny": plain_text("Think it over"),
+                                "confirm": plain_text("Reject it"),
+                            }
+                        }]
+                    }));
+                    (blocks, Some("Accept".to_string()))
+                }
+                None => (
+                    vec![comment("This trade offer isn't around anymore.")],
+                    None,
+                ),
+            };
 
             Modal {
                 method: "open".to_string(),
                 trigger_id: i.trigger_id,
-                callback_id: "item_apply_modal".to_string(),
-                title: "Item + Plant = :D".to_string(),
-                private_metadata: String::new(),
-                blocks: vec![json!({
-                    "type": "input",
-                    "label": plain_text("Item Select"),
-                    "block_id": "item_apply_input",
-                    "element": {
-                        "type": "static_select",
-                        "placeholder": plain_text("Which item do ya wanna use on this plant?"),
-                        "action_id": "item_apply_select",
-                        // show them each applicable item they have sorted by category
-                        "options": applicables
-                            .into_iter()
-                            .filter_map(|i| {
-                                Some(json!({
-                                    "text": plain_text(format!("{} {}", emojify(&i.name), i.name)),
-                                    "description": plain_text(match i.inner.item_application_effect.as_ref()? {
-                                        TimeIncrease { extra_cycles, duration_cycles } => format!(
-                                            "~{} hours pass in {} minutes",
-                                            extra_cycles / FARM_CYCLES_PER_MIN / 60,
-                                            duration_cycles / FARM_CYCLES_PER_MIN,
-                                        )
-                                    }),
-                                    // this is fucky-wucky because value can only be 75 chars
-                                    "value": serde_json::to_string(&(
-                                        &tile_id.to_simple().to_string(),
-                                        i.id.to_simple().to_string(),
-                                        i.archetype_handle
-                                    )).unwrap(),
-                                }))
-                            })
-                            .collect::<Vec<Value>>(),
-                    }
-                })],
-                submit: Some("Apply!".to_string()),
-            }
-            .launch()
-            .await?
-        }
-        "gotchi_nickname" => {
-            Modal {
-                method: "push".to_string(),
-                trigger_id: i.trigger_id,
-                callback_id: "gotchi_nickname_modal".to_string(),
-                title: "Nickname Gotchi".to_string(),
-                private_metadata: i.view.ok_or("no view!".to_string())?.private_metadata,
-                blocks: vec![json!({
-                    "type": "input",
-                    "block_id": "gotchi_nickname_block",
-                    "label": plain_text("Nickname Gotchi"),
-                    "element": {
-                        "type": "plain_text_input",
-                        "action_id": "gotchi_nickname_input",
-                        "placeholder": plain_text("Nickname Gotchi"),
-                        "initial_value": action.value,
-                        "min_length": 1,
-                        "max_length": 25,
-                    }
-                })],
-                submit: Some("Change it!".to_string()),
-                ..Default::default()
+                callback_id: "trade_review_modal".to_string(),
+                title: "Trade Offer".to_string(),
+                private_metadata: offer_id.to_simple().to_string(),
+                blocks,
+                submit,
             }
             .launch()
             .await?
         }
-        "possession_market_overview_page" => {
-            let page_json = &action.value;
-            let (item_name, cat): (String, Category) = serde_json::from_str(page_json).unwrap();
-
-            let page = PossessionOverviewPage {
-                credentials: if hacksteader::exists(&dyn_db(), i.user.id.clone()).await {
-                    Credentials::Hacksteader
-                } else {
-                    Credentials::None
-                },
-                page: 0,
-                interactivity: Interactivity::Buy,
-                source: PossessionOverviewSource::Market(cat),
-                item_name,
-            };
+        "trade_call_off" => {
+            let offer_id = uuid::Uuid::parse_str(&action.value).map_err(|e| {
+                let a = format!("couldn't parse trade offer id {}: {}", action.value, e);
+                error!("{}", a);
+                a
+            })?;
+            call_off_trade(offer_id, i.user.id.clone()).await?;
 
-            page.modal(i.trigger_id, "push").await?.launch().await?
+            json!({})
         }
-        "push_possession_page" => {
-            let page_json = action.value;
-            let mut page: PossessionPage = serde_json::from_str(&page_json).unwrap();
-
-            if page.possession.steader == i.user.id {
-                page.credentials = Credentials::Owner;
+        "trade_reject" => {
+            let offer_id = uuid::Uuid::parse_str(&action.value).map_err(|e| {
+                let a = format!("couldn't parse trade offer id {}: {}", action.value, e);
+                error!("{}", a);
+                a
+            })?;
+            call_off_trade(offer_id, i.user.id.clone()).await?;
+
+            match i.view {
+                Some(view) => {
+                    ModalUpdate {
+                        trigger_id: i.trigger_id,
+                        callback_id: "trade_rejected_modal".to_string(),
+                        title: "Trade Offer".to_string(),
+                        private_metadata: String::new(),
+                        hash: None,
+                        view_id: view.id,
+                        blocks: vec![comment("You've turned down this trade offer.")],
+                        submit: None,
+                    }
+                    .launch()
+                    .await?
+                }
+                None => json!({}),
             }
-
-            page.modal(i.trigger_id, "push").launch().await?
         }
-        "possession_page" => {
-            let page_json = action.value;
-            let mut page: PossessionPage = serde_json::from_str(&page_json).unwrap();
-
-            if page.possession.steader == i.user.id {
-                page.credentials = Credentials::Owner;
+        encyclopedia if encyclopedia.starts_with("encyclopedia") => {
+            let trail: Vec<config::ArchetypeHandle> = serde_json::from_str(&action.value)
+                .map_err(|e| {
+                    let a = format!("couldn't parse encyclopedia trail {}: {}", action.value, e);
+                    error!("{}", a);
+                    a
+                })?;
+            let discovered = Hacksteader::from_db(&dyn_db(), i.user.id.clone())
+                .await
+                .map(|hs| hs.profile.discovered_recipes)
+                .unwrap_or_default();
+
+            match i.view.filter(|v| v.callback_id == "encyclopedia_modal") {
+                // already in the encyclopedia, so flip the page instead of
+                // pushing another modal onto slack's (quite short) stack
+                Some(view) => ModalUpdate {
+                    trigger_id: i.trigger_id,
+                    callback_id: "encyclopedia_modal".to_string(),
+                    title: "Encyclopedia".to_string(),
+                    private_metadata: String::new(),
+                    hash: None,
+                    view_id: view.id,
+                    blocks: encyclopedia_blocks(&trail, &discovered),
+                    submit: None,
+                }
+                .launch()
+                .await?,
+                None => Modal {
+                    method: "push".to_string(),
+                    trigger_id: i.trigger_id,
+                    callback_id: "encyclopedia_modal".to_string(),
+                    title: "Encyclopedia".to_string(),
+                    private_metadata: String::new(),
+                    blocks: encyclopedia_blocks(&trail, &discovered),
+                    submit: None,
+                }
+                .launch()
+                .await?,
             }
-
-            page.modal(i.trigger_id, "open").launch().await?
-        }
-        "possession_overview_page" => {
-            let page_json = action.value;
-            let page: PossessionOverviewPage = serde_json::from_str(&page_json).unwrap();
-
-            page.modal(i.trigger_id, "open").await?.launch().await?
         }
         _ => mrkdwn("huh?"),
     };
@@ -2421,10 +5883,29 @@ pub enum FarmingInputEvent {
     RedeemLandCert(uuid::Uuid, String),
     ApplyItem(uuid::Uuid, config::ArchetypeHandle),
     PlantSeed(uuid::Uuid, hacksteader::Plant),
+    Uproot(uuid::Uuid, String),
     BeginCraft {
         tile_id: uuid::Uuid,
         recipe: config::Recipe<config::ArchetypeHandle>,
     },
+    CancelCraft(uuid::Uuid, String),
+    StudyBlueprint(uuid::Uuid, String),
+    BeginWorkbenchCraft {
+        user_id: String,
+        recipe: config::Recipe<config::ArchetypeHandle>,
+    },
+    Crossbreed {
+        tile_id: uuid::Uuid,
+        partner_id: uuid::Uuid,
+        makes: config::ArchetypeHandle,
+        user_id: String,
+    },
+    WatchMarket {
+        user_id: String,
+        archetype_handle: config::ArchetypeHandle,
+        max_price: u64,
+    },
+    UnwatchMarket(config::ArchetypeHandle, String),
 }
 
 #[tokio::main]
@@ -2443,24 +5924,24 @@ async fn main() -> Result<(), Box<dyn std::error::Error + 'static>> {
         use std::time::{Duration, SystemTime};
         use tokio::time::interval;
 
-        struct ItemEffect {
-            // the number of farming cycles this effect lasts for
-            duration_cycles_total: u64,
-
-            // the number of extra cycles this effect applies to its plant
-            // (right now all items can do is give their plants extra cycles)
-            extra_cycles_total: u64,
-            // the number of cycles still to be awarded to the plant
-            extra_cycles_remaining: u64,
-        }
-
         let mut interval = interval(Duration::from_millis(FARM_CYCLE_MILLIS));
 
         let mut active_users: HashMap<String, bool> = HashMap::new();
-        let mut item_effects: HashMap<uuid::Uuid, ItemEffect> = HashMap::new();
+        let mut item_queue: HashMap<uuid::Uuid, Vec<config::ArchetypeHandle>> = HashMap::new();
         let mut plant_queue: HashMap<uuid::Uuid, hacksteader::Plant> = HashMap::new();
+        let mut uproot_queue: HashMap<uuid::Uuid, String> = HashMap::new();
         let mut craft_queue: HashMap<uuid::Uuid, config::Recipe<_>> = HashMap::new();
+        let mut craft_cancel_queue: HashMap<uuid::Uuid, String> = HashMap::new();
+        let mut workbench_queue: HashMap<String, config::Recipe<_>> = HashMap::new();
+        let mut blueprint_queue: HashMap<uuid::Uuid, String> = HashMap::new();
         let mut land_cert_queue: HashMap<String, uuid::Uuid> = HashMap::new();
+        let mut crossbreed_queue: HashMap<
+            uuid::Uuid,
+            (uuid::Uuid, config::ArchetypeHandle, String),
+        > = HashMap::new();
+        // None stops watching
+        let mut watch_queue: HashMap<String, Vec<(config::ArchetypeHandle, Option<u64>)>> =
+            HashMap::new();
 
         async move {
             use core::Profile;
@@ -2481,38 +5962,53 @@ async fn main() -> Result<(), Box<dyn std::error::Error + 'static>> {
                             active_users.insert(name, true);
                         }
                         ApplyItem(tile_id, item_ah) => {
-                            let config::ApplicationEffect::TimeIncrease {
-                                extra_cycles,
-                                duration_cycles,
-                            } = CONFIG
-                                .possession_archetypes
-                                .get(item_ah)
-                                .expect("invalid archetype handle")
-                                .kind
-                                .keepsake()
-                                .expect("can only apply keepsakes")
-                                .item_application_effect
-                                .clone()
-                                .expect("keepsake has no application effect");
-
-                            item_effects.insert(
-                                tile_id,
-                                ItemEffect {
-                                    duration_cycles_total: duration_cycles,
-                                    extra_cycles_total: extra_cycles,
-                                    extra_cycles_remaining: extra_cycles,
-                                },
-                            );
+                            item_queue.entry(tile_id).or_default().push(item_ah);
                         }
                         PlantSeed(tile_id, plant) => {
                             plant_queue.insert(tile_id, plant);
                         }
+                        Uproot(tile_id, user_id) => {
+                            uproot_queue.insert(tile_id, user_id);
+                        }
                         RedeemLandCert(cert_id, user_id) => {
                             land_cert_queue.insert(user_id, cert_id);
                         }
                         BeginCraft { tile_id, recipe } => {
                             craft_queue.insert(tile_id, recipe);
                         }
+                        CancelCraft(tile_id, user_id) => {
+                            craft_cancel_queue.insert(tile_id, user_id);
+                        }
+                        StudyBlueprint(blueprint_id, user_id) => {
+                            blueprint_queue.insert(blueprint_id, user_id);
+                        }
+                        BeginWorkbenchCraft { user_id, recipe } => {
+                            workbench_queue.insert(user_id, recipe);
+                        }
+                        Crossbreed {
+                            tile_id,
+                            partner_id,
+                            makes,
+                            user_id,
+                        } => {
+                            crossbreed_queue.insert(tile_id, (partner_id, makes, user_id));
+                        }
+                        WatchMarket {
+                            user_id,
+                            archetype_handle,
+                            max_price,
+                        } => {
+                            watch_queue
+                                .entry(user_id)
+                                .or_default()
+                                .push((archetype_handle, Some(max_price)));
+                        }
+                        UnwatchMarket(archetype_handle, user_id) => {
+                            watch_queue
+                                .entry(user_id)
+                                .or_default()
+                                .push((archetype_handle, None));
+                        }
                     }
                 }
 
@@ -2522,42 +6018,192 @@ async fn main() -> Result<(), Box<dyn std::error::Error + 'static>> {
                     continue;
                 }
 
-                let db = dyn_db();
-
-                let mut deletions = vec![];
-                let mut clear_plants = vec![];
-                let mut possessions = vec![];
-                let mut new_tiles = vec![];
-                let mut dms: Vec<(String, [Value; 2])> = Vec::new();
+                let db = dyn_db();
+
+                let mut deletions = vec![];
+                let mut clear_plants = vec![];
+                let mut uprooted = vec![];
+                let mut possessions = vec![];
+                let mut new_tiles = vec![];
+                let mut dms: Vec<(String, [Value; 2])> = Vec::new();
+                let mut watch_updates = vec![];
+
+                let mut hacksteaders: Vec<Hacksteader> = match stream::iter(active_users.clone())
+                    .map(|(id, _)| Hacksteader::from_db(&db, id))
+                    .buffer_unordered(50)
+                    .try_collect::<Vec<_>>()
+                    .await
+                {
+                    Ok(i) => i,
+                    Err(e) => {
+                        error!("error reading hacksteader from db: {}", e);
+                        continue;
+                    }
+                };
+
+                // Give away requested land
+                for hs in hacksteaders.iter_mut() {
+                    if let Some(cert_id) = land_cert_queue.remove(&hs.user_id) {
+                        deletions.push(Key::misc(cert_id));
+                        let new_tile = hacksteader::Tile::new(hs.user_id.clone());
+                        hs.land.push(new_tile.clone());
+                        new_tiles.push(new_tile.clone());
+                    }
+                }
+
+                // Uproot requested plants, turning them back into seeds
+                for hs in hacksteaders.iter_mut() {
+                    for tile in hs.land.iter_mut() {
+                        match uproot_queue.remove(&tile.id) {
+                            Some(requester) if requester == tile.steader => {}
+                            _ => continue,
+                        }
+                        let mut plant = match tile.plant.take() {
+                            Some(pl) => pl,
+                            None => continue,
+                        };
+
+                        match plant.clone().into_seed(tile.steader.clone()) {
+                            Some(seed) => {
+                                possessions.append(&mut plant.refund_queued_crafts(&tile.steader));
+                                dms.push((
+                                    tile.steader.clone(),
+                                    [
+                                        json!({
+                                            "type": "section",
+                                            "text": mrkdwn(format!(
+                                                "You've uprooted your plant, and gotten a {} *{}* back!",
+                                                emojify(&seed.name),
+                                                seed.name
+                                            )),
+                                            "accessory": {
+                                                "type": "image",
+                                                "image_url": format!(
+                                                    "http://{}/gotchi/img/misc/{}.png",
+                                                    *URL,
+                                                    filify(&seed.name)
+                                                ),
+                                                "alt_text": "A seed, ready to be planted again",
+                                            }
+                                        }),
+                                        comment("DIRT UNDER UR FINGERNAILS NOW"),
+                                    ],
+                                ));
+                                possessions.push(seed);
+                            }
+                            None => {
+                                error!("no seed archetype to uproot tile[{}] into", tile.id);
+                                tile.plant = Some(plant);
+                                continue;
+                            }
+                        }
+                        uprooted.push(tile.id);
+                    }
+                }
+
+                // Apply requested items, giving back any that can't take effect
+                for hs in hacksteaders.iter_mut() {
+                    for tile in hs.land.iter_mut() {
+                        for item_ah in item_queue.remove(&tile.id).unwrap_or_default() {
+                            let application = tile
+                                .plant
+                                .as_mut()
+                                .and_then(|p| hacksteader::Effect::apply(&mut p.effects, item_ah));
+
+                            match application {
+                                Some(core::effect::Application::Rejected(_)) | None => {}
+                                Some(_) => continue,
+                            }
+                            info!(
+                                "giving back an item that couldn't be applied to {}",
+                                tile.id
+                            );
+                            let item = Possession::new(
+                                item_ah,
+                                possess::Owner::farmer(tile.steader.clone()),
+                            );
+                            dms.push((
+                                tile.steader.clone(),
+                                [
+                                    json!({
+                                        "type": "section",
+                                        "text": mrkdwn(format!(
+                                            "Your {} *{}* couldn't be used on that plant, so you've gotten it back.",
+                                            emojify(&item.name),
+                                            item.name
+                                        )),
+                                    }),
+                                    comment("NO REFUNDS. EXCEPT THIS ONE."),
+                                ],
+                            ));
+                            possessions.push(item);
+                        }
+                    }
+                }
+
+                // Cancel requested crafts, refunding some of what they used
+                for hs in hacksteaders.iter_mut() {
+                    for tile in hs.land.iter_mut() {
+                        match craft_cancel_queue.remove(&tile.id) {
+                            Some(requester) if requester == tile.steader => {}
+                            _ => continue,
+                        }
+                        let craft = match tile.plant.as_mut().and_then(|p| {
+                            let craft = p.craft.take();
+                            p.start_next_craft();
+                            craft
+                        }) {
+                            Some(craft) => craft,
+                            None => continue,
+                        };
 
-                let mut hacksteaders: Vec<Hacksteader> = match stream::iter(active_users.clone())
-                    .map(|(id, _)| Hacksteader::from_db(&db, id))
-                    .buffer_unordered(50)
-                    .try_collect::<Vec<_>>()
-                    .await
-                {
-                    Ok(i) => i,
-                    Err(e) => {
-                        error!("error reading hacksteader from db: {}", e);
-                        continue;
+                        let refund = CONFIG.crafting.cancel_refund(&craft.needs);
+                        for &(count, ah) in refund.iter() {
+                            for _ in 0..count {
+                                possessions.push(Possession::new(
+                                    ah,
+                                    possess::Owner::crafter(tile.steader.clone()),
+                                ));
+                            }
+                        }
+                        dms.push((
+                            tile.steader.clone(),
+                            [
+                                json!({
+                                    "type": "section",
+                                    "text": mrkdwn(if refund.is_empty() {
+                                        "You've cancelled a craft, \
+                                        but none of what it used could be refunded.".to_string()
+                                    } else {
+                                        format!(
+                                            "You've cancelled a craft and gotten back:\n{}",
+                                            refund
+                                                .iter()
+                                                .filter_map(|&(n, ah)| {
+                                                    let arch = CONFIG.possession_archetypes.get(ah)?;
+                                                    Some(format!("*{}* {} _{}_", n, emojify(&arch.name), arch.name))
+                                                })
+                                                .collect::<Vec<_>>()
+                                                .join("\n")
+                                        )
+                                    }),
+                                }),
+                                comment("SOME THINGS JUST WEREN'T MEANT TO BE"),
+                            ],
+                        ));
                     }
-                };
+                }
 
-                // Give away requested land
+                // Launch requested crafts, or queue them up behind the current one
                 for hs in hacksteaders.iter_mut() {
-                    if let Some(cert_id) = land_cert_queue.remove(&hs.user_id) {
-                        deletions.push(Key::misc(cert_id));
-                        let new_tile = hacksteader::Tile::new(hs.user_id.clone());
-                        hs.land.push(new_tile.clone());
-                        new_tiles.push(new_tile.clone());
-                    }
-                }
+                    let all_nb = hs.neighbor_bonuses();
+                    let Hacksteader {
+                        land,
+                        inventory,
+                        profile,
+                        ..
+                    } = hs;
 
-                // Launch requested crafts
-                for Hacksteader {
-                    land, inventory, ..
-                } in hacksteaders.iter_mut()
-                {
                     for tile in land.iter_mut() {
                         let plant = match &mut tile.plant {
                             Some(pl) => pl,
@@ -2565,10 +6211,55 @@ async fn main() -> Result<(), Box<dyn std::error::Error + 'static>> {
                         };
 
                         if let Some(recipe) = craft_queue.remove(&tile.id) {
+                            if !recipe.discovered_by(&profile.discovered_recipes) {
+                                error!("{} tried to craft an undiscovered recipe", tile.steader);
+                                continue;
+                            }
+
+                            let queue_slots = plant
+                                .advancements_sum(
+                                    all_nb
+                                        .clone()
+                                        .bonuses_for_plant(tile.id, plant.archetype_handle)
+                                        .iter(),
+                                )
+                                .craft_queue_slots;
+                            if plant.craft.is_some() && plant.craft_queue.len() >= queue_slots {
+                                dms.push((
+                                    tile.steader.clone(),
+                                    [
+                                        comment("that plant's crafting queue is already full"),
+                                        comment("patience is a virtue"),
+                                    ],
+                                ));
+                                continue;
+                            }
+
+                            let missing = recipe
+                                .missing(&inventory, plant.advancements.current_position(plant.xp));
+                            if !missing.is_empty() {
+                                dms.push((
+                                    tile.steader.clone(),
+                                    [
+                                        comment(format!(
+                                            "you can't craft that yet, you still need {}",
+                                            missing
+                                                .iter()
+                                                .map(|m| m.to_string())
+                                                .collect::<Vec<_>>()
+                                                .join(", ")
+                                        )),
+                                        comment("nice try tho"),
+                                    ],
+                                ));
+                                continue;
+                            }
+
                             let should_take: usize =
                                 recipe.needs.iter().map(|(n, _)| n).sum::<usize>();
                             let mut used_resources = recipe
                                 .needs
+                                .clone()
                                 .into_iter()
                                 .flat_map(|(count, ah)| {
                                     inventory
@@ -2580,14 +6271,21 @@ async fn main() -> Result<(), Box<dyn std::error::Error + 'static>> {
                                 .collect::<Vec<_>>();
 
                             if should_take == used_resources.len() {
+                                // these are reserved for this craft now, so other
+                                // crafts launched this tick can't use them too.
+                                inventory.retain(|p| {
+                                    !used_resources.iter().any(|k| k.id == p.id)
+                                });
                                 deletions.append(&mut used_resources);
 
-                                plant.craft = Some(hacksteader::Craft {
+                                plant.craft_queue.push(hacksteader::Craft {
                                     until_finish: recipe.time,
                                     total_cycles: recipe.time,
                                     makes: recipe.makes.any(),
                                     destroys_plant: recipe.destroys_plant,
+                                    needs: recipe.needs,
                                 });
+                                plant.start_next_craft();
                             } else {
                                 dms.push((
                                     tile.steader.clone(),
@@ -2601,6 +6299,277 @@ async fn main() -> Result<(), Box<dyn std::error::Error + 'static>> {
                     }
                 }
 
+                // Study requested blueprints, revealing the hidden recipes they describe
+                for hs in hacksteaders.iter_mut() {
+                    let requested =
+                        |p: &&Possession| blueprint_queue.get(&p.id) == Some(&hs.user_id);
+                    let listed: Vec<uuid::Uuid> = hs
+                        .inventory
+                        .iter()
+                        .filter(requested)
+                        .filter(|p| p.sale.is_some())
+                        .map(|p| p.id)
+                        .collect();
+                    let studied: Vec<(uuid::Uuid, config::ArchetypeHandle)> = hs
+                        .inventory
+                        .iter()
+                        .filter(requested)
+                        .filter(|p| p.sale.is_none())
+                        .filter_map(|p| {
+                            let bp = CONFIG
+                                .possession_archetypes
+                                .get(p.archetype_handle)?
+                                .kind
+                                .keepsake()?
+                                .blueprint
+                                .as_ref()?;
+                            Some((p.id, CONFIG.find_possession_handle(bp).ok()?))
+                        })
+                        .collect();
+
+                    for blueprint_id in listed {
+                        blueprint_queue.remove(&blueprint_id);
+                        dms.push((
+                            hs.user_id.clone(),
+                            [
+                                comment("you can't study a blueprint you've put up for sale"),
+                                comment("take it off the market first"),
+                            ],
+                        ));
+                    }
+                    for (blueprint_id, reveals) in studied {
+                        blueprint_queue.remove(&blueprint_id);
+                        if hs.profile.discover_recipes_for(reveals) {
+                            hs.inventory.retain(|p| p.id != blueprint_id);
+                            deletions.push(Key::misc(blueprint_id));
+                            dms.push((hs.user_id.clone(), recipe_discovery_blocks(reveals)));
+                        } else {
+                            dms.push((
+                                hs.user_id.clone(),
+                                [
+                                    comment("that blueprint doesn't have anything you don't already know"),
+                                    comment("maybe sell it to someone who doesn't?"),
+                                ],
+                            ));
+                        }
+                    }
+                }
+
+                // Launch requested workbench crafts
+                for hs in hacksteaders.iter_mut() {
+                    let recipe = match workbench_queue.remove(&hs.user_id) {
+                        Some(recipe) => recipe,
+                        None => continue,
+                    };
+                    if !recipe.discovered_by(&hs.profile.discovered_recipes) {
+                        error!("{} tried to craft an undiscovered recipe", hs.user_id);
+                        continue;
+                    }
+                    if hs.profile.workbench.is_some() {
+                        dms.push((
+                            hs.user_id.clone(),
+                            [
+                                comment("your workbench is already busy crafting something"),
+                                comment("one thing at a time"),
+                            ],
+                        ));
+                        continue;
+                    }
+
+                    let missing = recipe.missing(
+                        &hs.inventory,
+                        hs.profile.advancements.current_position(hs.profile.xp),
+                    );
+                    if !missing.is_empty() {
+                        dms.push((
+                            hs.user_id.clone(),
+                            [
+                                comment(format!(
+                                    "you can't craft that yet, you still need {}",
+                                    missing
+                                        .iter()
+                                        .map(|m| m.to_string())
+                                        .collect::<Vec<_>>()
+                                        .join(", ")
+                                )),
+                                comment("nice try tho"),
+                            ],
+                        ));
+                        continue;
+                    }
+
+                    let should_take: usize = recipe.needs.iter().map(|(n, _)| n).sum::<usize>();
+                    let inventory = &hs.inventory;
+                    let mut used_resources = recipe
+                        .needs
+                        .clone()
+                        .into_iter()
+                        .flat_map(|(count, ah)| {
+                            inventory
+                                .iter()
+                                .filter(move |p| p.archetype_handle == ah)
+                                .map(|p| p.key())
+                                .take(count)
+                        })
+                        .collect::<Vec<_>>();
+
+                    if should_take == used_resources.len() {
+                        hs.inventory
+                            .retain(|p| !used_resources.iter().any(|k| k.id == p.id));
+                        deletions.append(&mut used_resources);
+
+                        // like when planting, without any plants nothing has kept
+                        // last_farm up to date, so the craft would finish instantly.
+                        if hs.land.iter().all(|t| t.plant.is_none()) {
+                            hs.profile.last_farm = SystemTime::now();
+                        }
+                        hs.profile.workbench = Some(hacksteader::Craft {
+                            until_finish: recipe.time,
+                            total_cycles: recipe.time,
+                            makes: recipe.makes.any(),
+                            destroys_plant: false,
+                            needs: recipe.needs,
+                        });
+                    } else {
+                        dms.push((
+                            hs.user_id.clone(),
+                            [
+                                comment("you don't have enough resources to craft that"),
+                                comment("nice try tho"),
+                            ],
+                        ));
+                    }
+                }
+
+                // Update requested market watches
+                for hs in hacksteaders.iter_mut() {
+                    for (ah, max_price) in watch_queue.remove(&hs.user_id).unwrap_or_default() {
+                        match max_price {
+                            Some(max_price) => {
+                                if hs.profile.watch(ah, max_price) {
+                                    watch_updates.push((ah, hs.user_id.clone(), Some(max_price)));
+                                } else {
+                                    dms.push((
+                                        hs.user_id.clone(),
+                                        [
+                                            comment("your watchlist is already full"),
+                                            comment("stop watching for something else first"),
+                                        ],
+                                    ));
+                                }
+                            }
+                            None => {
+                                hs.profile.watches.retain(|w| w.archetype_handle != ah);
+                                watch_updates.push((ah, hs.user_id.clone(), None));
+                            }
+                        }
+                    }
+                }
+
+                // Crossbreed requested plants
+                for Hacksteader { land, user_id, .. } in hacksteaders.iter_mut() {
+                    let requests: Vec<_> = land
+                        .iter()
+                        .filter_map(|t| Some((t.id, crossbreed_queue.remove(&t.id)?)))
+                        .collect();
+
+                    for (tile_id, (partner_id, makes, requester)) in requests {
+                        if requester != *user_id {
+                            continue;
+                        }
+                        let parents: Vec<&Plant> = land
+                            .iter()
+                            .filter(|t| t.id == tile_id || t.id == partner_id)
+                            .filter_map(|t| t.plant.as_ref())
+                            .collect();
+                        let seed_archetype = CONFIG
+                            .possession_archetypes
+                            .get(makes)
+                            .and_then(|a| match &a.kind {
+                                config::ArchetypeKind::Seed(sa) => Some(sa),
+                                _ => None,
+                            });
+                        if parents.iter().any(|p| !p.ready_to_crossbreed()) {
+                            dms.push((
+                                user_id.clone(),
+                                [
+                                    comment(
+                                        "those plants need to grow more of their yields \
+                                        before they can be crossbred",
+                                    ),
+                                    comment("patience is a virtue"),
+                                ],
+                            ));
+                            continue;
+                        }
+                        let hybrid = match (parents.as_slice(), seed_archetype) {
+                            ([a, b], Some(sa)) if sa.is_hybrid_of(&a.name, &b.name) => {
+                                let mut p = Possession::new(
+                                    makes,
+                                    possess::Owner::farmer(user_id.clone()),
+                                );
+                                if let Some(s) = p.kind.seed_mut() {
+                                    s.pedigree = possess::seed::SeedGrower::crossbreed(
+                                        &a.pedigree,
+                                        &b.pedigree,
+                                        user_id,
+                                    );
+                                    s.traits = a
+                                        .traits
+                                        .blend(b.traits)
+                                        .mutate(&mut rand::thread_rng());
+                                }
+                                p
+                            }
+                            _ => {
+                                dms.push((
+                                    user_id.clone(),
+                                    [
+                                        comment("those plants can't be crossbred into that"),
+                                        comment("nice try tho"),
+                                    ],
+                                ));
+                                continue;
+                            }
+                        };
+
+                        // crossbreeding uses up the yield both parents were working on
+                        for tile in land
+                            .iter_mut()
+                            .filter(|t| t.id == tile_id || t.id == partner_id)
+                        {
+                            if let Some(plant) = tile.plant.as_mut() {
+                                plant.until_yield = plant.base_yield_duration;
+                            }
+                        }
+
+                        dms.push((
+                            user_id.clone(),
+                            [
+                                json!({
+                                    "type": "section",
+                                    "text": mrkdwn(format!(
+                                        "Your crossbreeding efforts have produced a {} *{}*!",
+                                        emojify(&hybrid.name),
+                                        hybrid.name
+                                    )),
+                                    "accessory": {
+                                        "type": "image",
+                                        "image_url": format!(
+                                            "http://{}/gotchi/img/misc/{}.png",
+                                            *URL,
+                                            filify(&hybrid.name)
+                                        ),
+                                        "alt_text": "A brand new kind of seed!",
+                                    }
+                                }),
+                                comment("BIRDS AND BEES AND BRACTI"),
+                            ],
+                        ));
+                        possessions.push(hybrid);
+                    }
+                }
+
                 // we'll be frequently looking up profiles by who owns them to award xp.
                 let mut profiles: HashMap<String, Profile> = hacksteaders
                     .iter()
@@ -2633,18 +6602,21 @@ async fn main() -> Result<(), Box<dyn std::error::Error + 'static>> {
                     .into_iter()
                     .flat_map(|hs| hs.land.into_iter())
                     .filter_map(|mut t| {
-                        Some((
-                            t.plant.take().or_else(|| {
-                                plant_queue.remove(&t.id).map(|plant| {
-                                    profiles
-                                        .get_mut(&t.steader)
-                                        .expect("tile has no owner")
-                                        .last_farm = SystemTime::now();
-                                    plant
-                                })
-                            })?,
-                            t,
-                        ))
+                        let plant = t.plant.take().or_else(|| {
+                            plant_queue.remove(&t.id).map(|plant| {
+                                profiles
+                                    .get_mut(&t.steader)
+                                    .expect("tile has no owner")
+                                    .last_farm = SystemTime::now();
+                                plant
+                            })
+                        });
+                        // tiles without plants aren't written back by the farming loop,
+                        // so those that were just uprooted need writing back on their own
+                        if plant.is_none() && uprooted.contains(&t.id) {
+                            new_tiles.push(t.clone());
+                        }
+                        Some((plant?, t))
                     })
                     .collect();
 
@@ -2668,6 +6640,70 @@ async fn main() -> Result<(), Box<dyn std::error::Error + 'static>> {
                     }
                 }
 
+                // workbenches don't need plants, so they're ticked per hackstead
+                for profile in profiles.values_mut() {
+                    let elapsed = SystemTime::now()
+                        .duration_since(profile.last_farm)
+                        .unwrap_or_default()
+                        .as_millis()
+                        / (FARM_CYCLE_MILLIS as u128);
+                    if elapsed == 0 {
+                        continue;
+                    }
+
+                    // without any plants, nothing else is going to move last_farm forward
+                    if plant_tokens.get(&profile.id).copied().unwrap_or(0) == 0 {
+                        profile.last_farm += Duration::from_millis(
+                            (FARM_CYCLE_MILLIS as u128 * elapsed)
+                                .try_into()
+                                .unwrap_or(0),
+                        );
+                    }
+
+                    let mut craft = match profile.workbench.take() {
+                        Some(craft) => craft,
+                        None => continue,
+                    };
+                    craft.until_finish -= elapsed as f32;
+                    if craft.until_finish > 0.0 {
+                        profile.workbench = Some(craft);
+                        continue;
+                    }
+
+                    let p = Possession::new(
+                        craft.makes,
+                        possess::Owner::crafter(profile.id.clone()),
+                    );
+                    dms.push((
+                        profile.id.clone(),
+                        [
+                            json!({
+                                "type": "section",
+                                "text": mrkdwn(format!(
+                                    "Your workbench has finished crafting a {} *{}* for you!",
+                                    emojify(&p.name),
+                                    p.name
+                                )),
+                                "accessory": {
+                                    "type": "image",
+                                    "image_url": format!(
+                                        "http://{}/gotchi/img/{}/{}.png",
+                                        *URL,
+                                        p.kind.category(),
+                                        filify(&p.name)
+                                    ),
+                                    "alt_text": "Something freshly made at the workbench",
+                                }
+                            }),
+                            comment("SAWDUST EVERYWHERE"),
+                        ],
+                    ));
+                    if profile.discover_recipes_for(p.archetype_handle) {
+                        dms.push((profile.id.clone(), recipe_discovery_blocks(p.archetype_handle)));
+                    }
+                    possessions.push(p);
+                }
+
                 // game tick loop:
                 // this is where we go through and we increment each xp/craft/yield
                 for (plant, tile) in tiles.iter_mut() {
@@ -2737,40 +6773,66 @@ async fn main() -> Result<(), Box<dyn std::error::Error + 'static>> {
                             }
                         }
 
-                        if let Some(effect) = item_effects.get(&tile.id).clone() {
-                            // decrement counter, remove if 0
-                            if effect.extra_cycles_remaining == 0 {
-                                info!("removing +{} cyles effect", effect.extra_cycles_total);
-                                item_effects.remove(&tile.id);
-                                elapsed
-                            } else {
+                        let mut extra_cycles = 0;
+                        for effect in plant.effects.iter_mut() {
+                            if let Some(config::ApplicationEffect::TimeIncrease {
+                                extra_cycles: extra_cycles_total,
+                                duration_cycles,
+                            }) = effect.application_effect()
+                            {
                                 // calculate award, handle edge case that there's a bit left
-                                let base_award =
-                                    effect.extra_cycles_total / effect.duration_cycles_total;
-                                let to_award = if effect.extra_cycles_remaining < base_award {
-                                    effect.extra_cycles_remaining
-                                } else {
-                                    base_award
-                                };
-
-                                let i = item_effects.get_mut(&tile.id).unwrap();
-                                i.extra_cycles_remaining -= to_award;
-
-                                elapsed + to_award as u128
+                                let base_award = extra_cycles_total / duration_cycles;
+                                let to_award = base_award.min(effect.remaining);
+                                effect.remaining -= to_award;
+                                extra_cycles += to_award;
                             }
-                        } else {
-                            elapsed
                         }
+
+                        elapsed + extra_cycles as u128
                     };
 
+                    if let Some(craft) = plant.craft.as_mut() {
+                        if let Some(effect) = plant.effects.iter_mut().find(|e| {
+                            e.remaining > 0
+                                && match e.application_effect() {
+                                    Some(config::ApplicationEffect::FinishCraft) => true,
+                                    _ => false,
+                                }
+                        }) {
+                            info!("finishing craft early for {}", tile.id);
+                            effect.remaining -= 1;
+                            craft.until_finish = 0.0;
+                        }
+                    }
+
                     info!(
                         "triggering {} farming cycles for {}",
                         boosted_elapsed, profile.id
                     );
                     for _ in 0..boosted_elapsed {
+                        let mut yield_size = 1.0;
+                        let mut extra_xp = 0;
+                        for effect in plant.effects.iter_mut().filter(|e| e.remaining > 0) {
+                            match effect.application_effect() {
+                                Some(config::ApplicationEffect::YieldSizeBoost {
+                                    multiplier,
+                                    ..
+                                }) => {
+                                    yield_size *= multiplier;
+                                    effect.remaining -= 1;
+                                }
+                                Some(config::ApplicationEffect::XpBoost {
+                                    extra_xp: xp, ..
+                                }) => {
+                                    extra_xp += xp;
+                                    effect.remaining -= 1;
+                                }
+                                _ => {}
+                            }
+                        }
                         let plant_sum = plant
-                            .advancements
-                            .sum(plant.xp, neighbor_bonuses.iter());
+                            .advancements_sum(neighbor_bonuses.iter())
+                            .with_yield_size(yield_size);
 
                         plant.craft = match plant.craft.take() {
                             Some(mut craft) => {
@@ -2784,6 +6846,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error + 'static>> {
                                     );
                                     if craft.destroys_plant {
                                         clear_plants.push(tile.id.clone());
+                                        possessions
+                                            .append(&mut plant.refund_queued_crafts(&tile.steader));
                                     }
                                     possessions.push(p.clone());
                                     dms.push((
@@ -2814,53 +6878,71 @@ async fn main() -> Result<(), Box<dyn std::error::Error + 'static>> {
                                             comment("YAY FREE STUFFZ 'CEPT LIKE IT'S NOT FREE")
                                         ]
                                     ));
+                                    if profile.discover_recipes_for(p.archetype_handle) {
+                                        dms.push((
+                                            tile.steader.clone(),
+                                            recipe_discovery_blocks(p.archetype_handle),
+                                        ));
+                                    }
                                     None
                                 }
                             }
                             None => None,
                         };
+                        plant.start_next_craft();
 
                         plant.until_yield = match plant.until_yield
                             - plant_sum.yield_speed_multiplier
                         {
                             n if n > 0.0 => n,
                             _ => {
+                                let make_yield = {
+                                    let owner = &tile.steader;
+                                    let pedigree = &plant.pedigree;
+                                    let traits = plant.traits;
+                                    move |ah: config::ArchetypeHandle| {
+                                        let mut p = Possession::new(
+                                            ah,
+                                            possess::Owner::farmer(owner.clone()),
+                                        );
+                                        if let Some(s) = p.kind.seed_mut() {
+                                            s.pedigree = pedigree.clone();
+                                            s.traits = traits.mutate(&mut rand::thread_rng());
+
+                                            if let Some(sg) =
+                                                s.pedigree.last_mut().filter(|sg| sg.id == *owner)
+                                            {
+                                                sg.generations += 1;
+                                            } else {
+                                                s.pedigree.push(possess::seed::SeedGrower::new(
+                                                    owner.clone(),
+                                                    1,
+                                                ))
+                                            }
+                                        }
+                                        p
+                                    }
+                                };
                                 let mut produced: Vec<Possession> = plant_sum
                                     .yields
                                     .iter()
-                                    .flat_map({
-                                        let owner = &tile.steader;
-                                        let pedigree = &plant.pedigree;
-                                        move |(spawn_rate, ah)| {
-                                            (0..spawn_rate.gen_count(&mut rng)).map(move |_| {
-                                                let mut p = Possession::new(
-                                                    *ah,
-                                                    possess::Owner::farmer(owner.clone()),
-                                                );
-                                                if let Some(s) = p.kind.seed_mut() {
-                                                    s.pedigree = pedigree.clone();
-
-                                                    if let Some(sg) = s
-                                                        .pedigree
-                                                        .last_mut()
-                                                        .filter(|sg| sg.id == *owner)
-                                                    {
-                                                        sg.generations += 1;
-                                                    } else {
-                                                        s.pedigree.push(
-                                                            possess::seed::SeedGrower::new(
-                                                                owner.clone(),
-                                                                1,
-                                                            ),
-                                                        )
-                                                    }
-                                                }
-                                                p
-                                            })
-                                        }
+                                    .flat_map(|(spawn_rate, ah)| {
+                                        (0..spawn_rate.gen_count(&mut rng))
+                                            .map(move |_| make_yield(*ah))
                                     })
                                     .collect();
 
+                                if let Some(effect) = plant.effects.iter_mut().find(|e| {
+                                    e.remaining > 0
+                                        && match e.application_effect() {
+                                            Some(config::ApplicationEffect::GuaranteedSeed) => true,
+                                            _ => false,
+                                        }
+                                }) {
+                                    effect.remaining -= 1;
+                                    produced.extend(plant.seed_archetype_handle().map(make_yield));
+                                }
+
                                 dms.push((tile.steader.clone(), [
                                     json!({
                                         "type": "section",
@@ -2896,30 +6978,33 @@ async fn main() -> Result<(), Box<dyn std::error::Error + 'static>> {
                             }
                         };
 
-                        if let Some(advancement) = plant.increment_xp() {
-                            dms.push((tile.steader.clone(), [
-                                json!({
-                                    "type": "section",
-                                    "text": mrkdwn(format!(
-                                        concat!(
-                                            ":tada: Your _{}_ is now a *{}*!\n\n",
-                                            "*{}* Achieved:\n _{}_\n\n",
-                                            ":stonks: Total XP: *{}xp*",
-                                        ),
-                                        plant.name,
-                                        advancement.achiever_title,
-                                        advancement.title,
-                                        advancement.description,
-                                        advancement.xp
-                                    )),
-                                    "accessory": {
-                                        "type": "image",
-                                        "image_url": format!("http://{}/gotchi/img/plant/{}.gif", *URL, filify(&advancement.art)),
-                                        "alt_text": "happy shiny better plant",
-                                    }
-                                }),
-                                comment("EXCITING LEVELING UP NOISES"),
-                            ]))
+                        // some plants have inherited a knack for learning quickly
+                        for _ in 0..plant.traits.xp_earned(extra_xp + 1, &mut rand::thread_rng()) {
+                            if let Some(advancement) = plant.increment_xp() {
+                                dms.push((tile.steader.clone(), [
+                                    json!({
+                                        "type": "section",
+                                        "text": mrkdwn(format!(
+                                            concat!(
+                                                ":tada: Your _{}_ is now a *{}*!\n\n",
+                                                "*{}* Achieved:\n _{}_\n\n",
+                                                ":stonks: Total XP: *{}xp*",
+                                            ),
+                                            plant.name,
+                                            advancement.achiever_title,
+                                            advancement.title,
+                                            advancement.description,
+                                            advancement.xp
+                                        )),
+                                        "accessory": {
+                                            "type": "image",
+                                            "image_url": format!("http://{}/gotchi/img/plant/{}.gif", *URL, filify(&advancement.art)),
+                                            "alt_text": "happy shiny better plant",
+                                        }
+                                    }),
+                                    comment("EXCITING LEVELING UP NOISES"),
+                                ]))
+                            }
                         }
                         let profile_sum = profile.advancements.sum(profile.xp, std::iter::empty());
                         if let Some(advancement) = profile.increment_xp() {
@@ -2931,15 +7016,23 @@ async fn main() -> Result<(), Box<dyn std::error::Error + 'static>> {
                                             ":tada: Your _Hackstead_ is now a *{}*!\n\n",
                                             "*{}* Achieved:\n_{}_\n\n",
                                             ":stonks: Total XP: *{}xp*\n",
-                                            ":mountain: Land Available: *{} pieces* _(+{} pieces)_"
+                                            "{}"
                                         ),
                                         advancement.achiever_title,
                                         advancement.title,
                                         advancement.description,
                                         advancement.xp,
-                                        profile_sum.land,
-                                        match advancement.kind {
-                                            config::HacksteadAdvancementKind::Land { pieces } => pieces,
+                                        match &advancement.kind {
+                                            config::HacksteadAdvancementKind::Land { pieces } => format!(
+                                                ":mountain: Land Available: *{} pieces* _(+{} pieces)_",
+                                                profile_sum.land,
+                                                pieces
+                                            ),
+                                            config::HacksteadAdvancementKind::Workbench(recipes) => format!(
+                                                ":hammer_and_wrench: Workbench Recipes: *{}* _(+{} recipes)_",
+                                                profile_sum.recipes.len() + recipes.len(),
+                                                recipes.len()
+                                            ),
                                         }
                                     )),
                                     "accessory": {
@@ -2952,6 +7045,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error + 'static>> {
                             ]));
                         }
                     }
+
+                    plant.effects.retain(|e| e.remaining > 0);
                 }
 
                 let _ = stream::iter(
@@ -3021,6 +7116,12 @@ async fn main() -> Result<(), Box<dyn std::error::Error + 'static>> {
                 .await
                 .map_err(|e| error!("farm cycle async err: {}", e));
 
+                for (ah, watcher, max_price) in watch_updates {
+                    if let Err(e) = market::index_watch(&db, ah, watcher, max_price).await {
+                        error!("{}", e);
+                    }
+                }
+
                 let _ = futures::try_join!(
                     stream::iter(profiles.clone())
                         .map(|x| Ok(x))
@@ -3036,6 +7137,36 @@ async fn main() -> Result<(), Box<dyn std::error::Error + 'static>> {
         }
     });
 
+    // auctions end whether or not anyone's around to farm, so they're settled on their own
+    tokio::task::spawn(async move {
+        use std::time::Duration;
+        use tokio::time::interval;
+
+        let mut interval = interval(Duration::from_secs(AUCTION_SETTLE_SECS));
+        loop {
+            interval.tick().await;
+
+            if let Err(e) = market::settle_ended_auctions(&dyn_db()).await {
+                error!("couldn't settle auctions: {}", e);
+            }
+        }
+    });
+
+    // listings expire on their own too
+    tokio::task::spawn(async move {
+        use std::time::Duration;
+        use tokio::time::interval;
+
+        let mut interval = interval(Duration::from_secs(LISTING_EXPIRY_SECS));
+        loop {
+            interval.tick().await;
+
+            if let Err(e) = market::expire_listings(&dyn_db()).await {
+                error!("couldn't expire listings: {}", e);
+            }
+        }
+    });
+
     rocket::ignite()
         .manage(tx)
         .mount(
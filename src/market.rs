@@ -1,5 +1,17 @@
-use core::{market::Sale, Category, Key, Possession};
-use rusoto_dynamodb::{AttributeValue, DynamoDb, DynamoDbClient};
+use super::hacksteader::{self, Hacksteader};
+use super::{banker, comment, dm_blocks, filify, mrkdwn, plain_text, URL};
+use core::config::{ArchetypeHandle, CONFIG};
+use core::frontend::emojify;
+use core::market::{Auction, Bid, BuyOrder, Sale, SaleRecord};
+use core::{possess, Category, Key, Possession};
+use futures::TryFutureExt;
+use humantime::format_rfc3339;
+use rusoto_core::RusotoError;
+use rusoto_dynamodb::{
+    AttributeValue, DeleteItemError, DynamoDb, DynamoDbClient, PutItemError, UpdateItemError,
+};
+use serde_json::json;
+use std::time::SystemTime;
 
 use std::env::var;
 lazy_static::lazy_static! {
@@ -29,10 +41,19 @@ pub async fn log_blocks(blocks: Vec<serde_json::Value>) -> Result<(), String> {
     Ok(())
 }
 
+/// Everything of a category that's up for sale, and hasn't expired.
 pub async fn market_search(
     db: &DynamoDbClient,
     cat: Category,
 ) -> Result<Vec<(Sale, Possession)>, String> {
+    Ok(listings(db, cat)
+        .await?
+        .into_iter()
+        .filter(|(sale, _)| !sale.expired())
+        .collect())
+}
+
+async fn listings(db: &DynamoDbClient, cat: Category) -> Result<Vec<(Sale, Possession)>, String> {
     let query = db
         .query(rusoto_dynamodb::QueryInput {
             table_name: core::TABLE_NAME.to_string(),
@@ -70,11 +91,19 @@ pub async fn place_on_market(
     name: String,
 ) -> Result<(), String> {
     println!("putting {} on the market", key.id);
+    let expires = SystemTime::now() + CONFIG.market.listing_lifetime();
 
     db.update_item(rusoto_dynamodb::UpdateItemInput {
         key: key.clone().into_item(),
         expression_attribute_values: Some(
             [
+                (
+                    ":expires".to_string(),
+                    AttributeValue {
+                        s: Some(format_rfc3339(expires).to_string()),
+                        ..Default::default()
+                    },
+                ),
                 (
                     ":sale_price".to_string(),
                     AttributeValue {
@@ -94,7 +123,9 @@ pub async fn place_on_market(
             .cloned()
             .collect(),
         ),
-        update_expression: Some("SET price = :sale_price, market_name = :new_name".to_string()),
+        update_expression: Some(
+            "SET price = :sale_price, market_name = :new_name, expires = :expires".to_string(),
+        ),
         table_name: core::TABLE_NAME.to_string(),
         ..Default::default()
     })
@@ -104,12 +135,55 @@ pub async fn place_on_market(
     Ok(())
 }
 
+/// Puts something its seller has paid the market fee for (if there was one) up for sale
+/// and announces it, then offers it to anyone already waiting to buy it,
+/// or failing that, lets anyone watching for it know.
+pub async fn list(
+    db: &DynamoDbClient,
+    possession: Possession,
+    price: u64,
+    name: String,
+) -> Result<(), String> {
+    let (seller, ah) = (possession.steader.clone(), possession.archetype_handle);
+
+    futures::try_join!(
+        place_on_market(db, possession.key(), price, name),
+        log_blocks(vec![
+            json!({
+                "type": "section",
+                "text": mrkdwn(format!(
+                    "A *{}* has gone up for sale! \
+                    <@{}> is selling it on the hackmarket for *{} GP*!",
+                    possession.name, seller, price
+                )),
+                "accessory": {
+                    "type": "image",
+                    "image_url": format!(
+                        "http://{}/gotchi/img/{}/{}.png",
+                        *URL,
+                        possession.kind.category(),
+                        filify(&possession.name)
+                    ),
+                    "alt_text": "Hackpheus sitting on bags of money!",
+                }
+            }),
+            comment("QWIK U BETTR BYE ET B4 SUM1 EYLS"),
+        ]),
+    )?;
+
+    if !match_listing(db, possession, price).await? {
+        notify_watchers(db, &seller, ah, price, 1).await?;
+    }
+
+    Ok(())
+}
+
 pub async fn take_off_market(db: &DynamoDbClient, key: Key) -> Result<(), String> {
     println!("taking {} off the market", key.id);
 
     db.update_item(rusoto_dynamodb::UpdateItemInput {
         key: key.clone().into_item(),
-        update_expression: Some("REMOVE price, market_name".to_string()),
+        update_expression: Some("REMOVE price, market_name, expires".to_string()),
         table_name: core::TABLE_NAME.to_string(),
         ..Default::default()
     })
@@ -118,3 +192,1520 @@ pub async fn take_off_market(db: &DynamoDbClient, key: Key) -> Result<(), String
 
     Ok(())
 }
+
+/// What the growers of a seed's line are owed when it's sold for `price`.
+pub fn royalties(possession: &Possession, price: u64) -> Vec<(String, u64)> {
+    match possession.kind.seed() {
+        Some(seed) => seed
+            .royalties(CONFIG.market.seed_royalty(price))
+            .into_iter()
+            .filter(|(grower, _)| *grower != possession.steader)
+            .collect(),
+        None => vec![],
+    }
+}
+
+/// How `price`, less `fee`, is split between the seller of a possession and
+/// the growers of its line. Royalties are trimmed to fit what the fee leaves,
+/// so the seller's share bottoms out at zero rather than going negative.
+pub fn sale_shares(possession: &Possession, price: u64, fee: u64) -> (u64, Vec<(String, u64)>) {
+    let mut left = price.saturating_sub(fee);
+    let royalties = royalties(possession, price)
+        .into_iter()
+        .map(|(grower, share)| {
+            let share = share.min(left);
+            left -= share;
+            (grower, share)
+        })
+        .filter(|(_, share)| *share > 0)
+        .collect();
+    (left, royalties)
+}
+
+/// Hands a sold possession over to its buyer, taking it off the market.
+/// Returns false, handing nothing over, if the possession has changed hands
+/// or been relisted since `possession` was read.
+async fn transfer_sale(
+    db: &DynamoDbClient,
+    possession: &Possession,
+    seller: String,
+    buyer: String,
+    price: u64,
+) -> Result<bool, String> {
+    // so that two buyers racing for the same possession can't both get it
+    let mut values: std::collections::HashMap<String, AttributeValue> = [
+        (
+            ":new_owner".to_string(),
+            AttributeValue {
+                s: Some(buyer.clone()),
+                ..Default::default()
+            },
+        ),
+        (
+            ":ownership_entry".to_string(),
+            AttributeValue {
+                l: Some(vec![possess::Owner {
+                    id: buyer.clone(),
+                    acquisition: possess::Acquisition::Purchase { price },
+                }
+                .into()]),
+                ..Default::default()
+            },
+        ),
+        (
+            ":seller".to_string(),
+            AttributeValue {
+                s: Some(seller.clone()),
+                ..Default::default()
+            },
+        ),
+    ]
+    .iter()
+    .cloned()
+    .collect();
+    let condition = match &possession.sale {
+        Some(sale) => {
+            values.insert(
+                ":price".to_string(),
+                AttributeValue {
+                    n: Some(sale.price.to_string()),
+                    ..Default::default()
+                },
+            );
+            "steader = :seller AND price = :price"
+        }
+        None => "steader = :seller AND attribute_not_exists(price)",
+    };
+
+    match db
+        .update_item(rusoto_dynamodb::UpdateItemInput {
+            key: possession.key().into_item(),
+            expression_attribute_values: Some(values),
+            condition_expression: Some(condition.to_string()),
+            update_expression: Some(
+                concat!(
+                    "REMOVE price, market_name, expires ",
+                    "SET steader = :new_owner, ",
+                    "ownership_log = list_append(ownership_log, :ownership_entry)"
+                )
+                .to_string(),
+            ),
+            table_name: core::TABLE_NAME.to_string(),
+            ..Default::default()
+        })
+        .await
+    {
+        Ok(_) => Ok(true),
+        Err(RusotoError::Service(UpdateItemError::ConditionalCheckFailed(_))) => Ok(false),
+        Err(e) => Err(format!("database err: {}", e)),
+    }
+}
+
+/// Pays the seller of a possession that's been handed over to its buyer,
+/// and the growers of its line, their shares of `price`, less `fee`.
+/// Returns what the seller earned, and the royalties paid out.
+async fn pay_out_sale(
+    db: &DynamoDbClient,
+    possession: &Possession,
+    seller: String,
+    buyer: String,
+    price: u64,
+    fee: u64,
+) -> Result<(u64, Vec<(String, u64)>), String> {
+    let (seller_earnings, royalties) = sale_shares(possession, price, fee);
+    let record = SaleRecord::new(
+        possession.archetype_handle,
+        price,
+        buyer.clone(),
+        seller.clone(),
+    );
+
+    futures::try_join!(
+        banker::pay(
+            seller,
+            seller_earnings,
+            format!("sale of your {}", possession.name),
+        ),
+        futures::future::try_join_all(royalties.iter().map(|(grower, share)| {
+            banker::pay(
+                grower.clone(),
+                *share,
+                format!(
+                    "royalties from the sale of a {} from your line",
+                    possession.name
+                ),
+            )
+        })),
+        record_sale(db, &record),
+        collect_fee(db, fee),
+    )?;
+
+    Ok((seller_earnings, royalties))
+}
+
+/// Hands a sold possession over to its buyer, taking it off the market,
+/// and pays its seller and the growers of its line their shares of `price`, less `fee`.
+/// Returns what the seller earned, and the royalties paid out, or None, having paid nobody,
+/// if the possession has changed hands or been relisted since `possession` was read.
+pub async fn complete_sale(
+    db: &DynamoDbClient,
+    possession: &Possession,
+    seller: String,
+    buyer: String,
+    price: u64,
+    fee: u64,
+) -> Result<Option<(u64, Vec<(String, u64)>)>, String> {
+    if !transfer_sale(db, possession, seller.clone(), buyer.clone(), price).await? {
+        return Ok(None);
+    }
+
+    pay_out_sale(db, possession, seller, buyer, price, fee)
+        .await
+        .map(Some)
+}
+
+fn fee_pool_key() -> Key {
+    Key {
+        category: Category::MarketFees,
+        id: uuid::Uuid::nil(),
+    }
+}
+
+/// Keeps track of a market fee the banker's held onto, so that Harvests
+/// can report how much of what they pay out came from the market.
+pub async fn collect_fee(db: &DynamoDbClient, fee: u64) -> Result<(), String> {
+    if fee == 0 {
+        return Ok(());
+    }
+
+    db.update_item(rusoto_dynamodb::UpdateItemInput {
+        key: fee_pool_key().into_item(),
+        table_name: core::TABLE_NAME.to_string(),
+        update_expression: Some("ADD collected :fee".to_string()),
+        expression_attribute_values: Some(
+            [(
+                ":fee".to_string(),
+                AttributeValue {
+                    n: Some(fee.to_string()),
+                    ..Default::default()
+                },
+            )]
+            .iter()
+            .cloned()
+            .collect(),
+        ),
+        ..Default::default()
+    })
+    .await
+    .map_err(|e| format!("Couldn't collect market fee of {}gp: {}", fee, e))?;
+
+    Ok(())
+}
+
+/// Empties out the market fees collected since this was last called, returning how much there was.
+pub async fn take_collected_fees(db: &DynamoDbClient) -> Result<u64, String> {
+    let old = db
+        .update_item(rusoto_dynamodb::UpdateItemInput {
+            key: fee_pool_key().into_item(),
+            table_name: core::TABLE_NAME.to_string(),
+            update_expression: Some("SET collected = :zero".to_string()),
+            expression_attribute_values: Some(
+                [(
+                    ":zero".to_string(),
+                    AttributeValue {
+                        n: Some("0".to_string()),
+                        ..Default::default()
+                    },
+                )]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            return_values: Some("ALL_OLD".to_string()),
+            ..Default::default()
+        })
+        .await
+        .map_err(|e| format!("Couldn't take collected market fees: {}", e))?
+        .attributes;
+
+    Ok(old
+        .as_ref()
+        .and_then(|item| item.get("collected"))
+        .and_then(|av| av.n.as_ref())
+        .and_then(|n| n.parse().ok())
+        .unwrap_or(0))
+}
+
+/// Keeps track of a sale, for the market's price history.
+pub async fn record_sale(db: &DynamoDbClient, record: &SaleRecord) -> Result<(), String> {
+    db.put_item(rusoto_dynamodb::PutItemInput {
+        item: record.item(),
+        table_name: core::TABLE_NAME.to_string(),
+        ..Default::default()
+    })
+    .await
+    .map_err(|e| format!("Couldn't record sale {}: {}", record.id, e))?;
+
+    Ok(())
+}
+
+/// Every sale made since `since`, of a given archetype if one is provided, newest first.
+/// Sale records are indexed by when they were made, so older ones are never read.
+pub async fn sale_records(
+    db: &DynamoDbClient,
+    archetype_handle: Option<ArchetypeHandle>,
+    since: SystemTime,
+) -> Result<Vec<SaleRecord>, String> {
+    let mut values = vec![
+        (":record_cat".to_string(), Category::SaleRecord.into_av()),
+        (
+            ":since".to_string(),
+            AttributeValue {
+                s: Some(format_rfc3339(since).to_string()),
+                ..Default::default()
+            },
+        ),
+    ];
+    let mut filter = None;
+    if let Some(ah) = archetype_handle {
+        values.push((
+            ":ah".to_string(),
+            AttributeValue {
+                n: Some(ah.to_string()),
+                ..Default::default()
+            },
+        ));
+        filter = Some("archetype_handle = :ah".to_string());
+    }
+    let values: std::collections::HashMap<String, AttributeValue> = values.into_iter().collect();
+
+    let mut records = vec![];
+    let mut exclusive_start_key = None;
+    loop {
+        let page = db
+            .query(rusoto_dynamodb::QueryInput {
+                table_name: core::TABLE_NAME.to_string(),
+                index_name: Some("cat_sold_at_index".to_string()),
+                key_condition_expression: Some(
+                    "cat = :record_cat AND sold_at >= :since".to_string(),
+                ),
+                filter_expression: filter.clone(),
+                expression_attribute_values: Some(values.clone()),
+                scan_index_forward: Some(false),
+                exclusive_start_key,
+                ..Default::default()
+            })
+            .await
+            .map_err(|e| format!("Couldn't search sale records: {}", e))?;
+
+        records.extend(
+            page.items
+                .ok_or_else(|| format!("sale record query returned no items"))?
+                .iter()
+                .filter_map(|i| match SaleRecord::from_item(i) {
+                    Ok(record) => Some(record),
+                    Err(e) => {
+                        println!("error parsing sale record: {}", e);
+                        None
+                    }
+                }),
+        );
+
+        // each page holds at most a megabyte of records
+        exclusive_start_key = page.last_evaluated_key;
+        if exclusive_start_key.is_none() {
+            break;
+        }
+    }
+
+    Ok(records)
+}
+
+pub async fn place_buy_order(db: &DynamoDbClient, order: &BuyOrder) -> Result<(), String> {
+    println!("placing buy order {}", order.id);
+
+    db.put_item(rusoto_dynamodb::PutItemInput {
+        item: order.item(),
+        table_name: core::TABLE_NAME.to_string(),
+        ..Default::default()
+    })
+    .await
+    .map_err(|e| format!("Couldn't place buy order {}: {}", order.id, e))?;
+
+    Ok(())
+}
+
+/// Every buy order standing on the market, the best paying first.
+pub async fn buy_orders(db: &DynamoDbClient) -> Result<Vec<BuyOrder>, String> {
+    let query = db
+        .query(rusoto_dynamodb::QueryInput {
+            table_name: core::TABLE_NAME.to_string(),
+            index_name: Some("cat_price_index".to_string()),
+            key_condition_expression: Some("cat = :order_cat".to_string()),
+            expression_attribute_values: Some(
+                [(":order_cat".to_string(), Category::BuyOrder.into_av())]
+                    .iter()
+                    .cloned()
+                    .collect(),
+            ),
+            scan_index_forward: Some(false),
+            ..Default::default()
+        })
+        .await;
+
+    Ok(query
+        .map_err(|e| format!("Couldn't search buy orders: {}", e))?
+        .items
+        .ok_or_else(|| format!("buy order query returned no items"))?
+        .iter()
+        .filter_map(|i| match BuyOrder::from_item(i) {
+            Ok(order) => Some(order),
+            Err(e) => {
+                println!("error parsing buy order: {}", e);
+                None
+            }
+        })
+        .collect())
+}
+
+pub async fn buy_orders_for(
+    db: &DynamoDbClient,
+    ah: ArchetypeHandle,
+) -> Result<Vec<BuyOrder>, String> {
+    Ok(buy_orders(db)
+        .await?
+        .into_iter()
+        .filter(|o| o.archetype_handle == ah)
+        .collect())
+}
+
+/// Takes one off of what's left to buy on this order, returning what's left of it,
+/// or None if it's already been filled or cancelled, or isn't buying `ah`.
+async fn take_from_buy_order(
+    db: &DynamoDbClient,
+    id: uuid::Uuid,
+    ah: ArchetypeHandle,
+) -> Result<Option<BuyOrder>, String> {
+    let key = Key {
+        category: Category::BuyOrder,
+        id,
+    };
+    let av_n = |n: u64| AttributeValue {
+        n: Some(n.to_string()),
+        ..Default::default()
+    };
+
+    // conditional so that two sellers can't both be paid for the last one
+    let updated = match db
+        .update_item(rusoto_dynamodb::UpdateItemInput {
+            key: key.into_item(),
+            table_name: core::TABLE_NAME.to_string(),
+            update_expression: Some("SET quantity = quantity - :one".to_string()),
+            condition_expression: Some("quantity >= :one AND archetype_handle = :ah".to_string()),
+            expression_attribute_values: Some(
+                [
+                    (":one".to_string(), av_n(1)),
+                    (":ah".to_string(), av_n(ah as u64)),
+                ]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            return_values: Some("ALL_NEW".to_string()),
+            ..Default::default()
+        })
+        .await
+    {
+        Ok(rusoto_dynamodb::UpdateItemOutput {
+            attributes: Some(item),
+            ..
+        }) => BuyOrder::from_item(&item)
+            .map_err(|e| format!("couldn't parse updated buy order: {}", e))?,
+        Err(RusotoError::Service(UpdateItemError::ConditionalCheckFailed(_))) => return Ok(None),
+        Err(e) => return Err(format!("Couldn't take from buy order {}: {}", id, e)),
+        _ => return Err(format!("no attributes returned!")),
+    };
+
+    if updated.quantity == 0 {
+        Hacksteader::delete(db, key).await?;
+    }
+
+    Ok(Some(updated))
+}
+
+/// Puts back one that was taken off of a buy order by `take_from_buy_order`
+/// for a sale that didn't go through, refunding the buyer if it's since been cancelled.
+async fn return_to_buy_order(db: &DynamoDbClient, order: &BuyOrder) -> Result<(), String> {
+    // the last one was taken, so the order was deleted
+    if order.quantity == 0 {
+        return place_buy_order(
+            db,
+            &BuyOrder {
+                quantity: 1,
+                ..order.clone()
+            },
+        )
+        .await;
+    }
+
+    match db
+        .update_item(rusoto_dynamodb::UpdateItemInput {
+            key: Key {
+                category: Category::BuyOrder,
+                id: order.id,
+            }
+            .into_item(),
+            table_name: core::TABLE_NAME.to_string(),
+            update_expression: Some("SET quantity = quantity + :one".to_string()),
+            condition_expression: Some("attribute_exists(quantity)".to_string()),
+            expression_attribute_values: Some(
+                [(
+                    ":one".to_string(),
+                    AttributeValue {
+                        n: Some("1".to_string()),
+                        ..Default::default()
+                    },
+                )]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            ..Default::default()
+        })
+        .await
+    {
+        Ok(_) => Ok(()),
+        Err(RusotoError::Service(UpdateItemError::ConditionalCheckFailed(_))) => {
+            banker::pay(
+                order.buyer.clone(),
+                order.price,
+                "refund from your cancelled buy order".to_string(),
+            )
+            .await
+        }
+        Err(e) => Err(format!("Couldn't return to buy order {}: {}", order.id, e)),
+    }
+}
+
+/// Takes a buy order off of the market, returning what was left of it
+/// so that its escrow can be refunded. Only the buyer can cancel their order.
+pub async fn cancel_buy_order(
+    db: &DynamoDbClient,
+    id: uuid::Uuid,
+    buyer: String,
+) -> Result<BuyOrder, String> {
+    match db
+        .delete_item(rusoto_dynamodb::DeleteItemInput {
+            key: Key {
+                category: Category::BuyOrder,
+                id,
+            }
+            .into_item(),
+            table_name: core::TABLE_NAME.to_string(),
+            condition_expression: Some("steader = :buyer".to_string()),
+            expression_attribute_values: Some(
+                [(
+                    ":buyer".to_string(),
+                    AttributeValue {
+                        s: Some(buyer),
+                        ..Default::default()
+                    },
+                )]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            return_values: Some("ALL_OLD".to_string()),
+            ..Default::default()
+        })
+        .await
+    {
+        Ok(rusoto_dynamodb::DeleteItemOutput {
+            attributes: Some(item),
+            ..
+        }) => BuyOrder::from_item(&item)
+            .map_err(|e| format!("couldn't parse cancelled buy order: {}", e)),
+        Err(e) => Err(format!("Couldn't cancel buy order {}: {}", id, e)),
+        _ => Err(format!("no buy order {} to cancel", id)),
+    }
+}
+
+/// Hands `possession` over to whoever placed a buy order, paying for it out of that order's escrow.
+///
+/// If the possession is listed on the market, its seller has already paid the market fee,
+/// so they get `listed_price` for it and the buyer is refunded whatever they offered on top.
+/// Otherwise, the seller is filling the order themselves, and is paid its price less the fee.
+///
+/// Returns false if the order had already been filled or cancelled, if it isn't buying
+/// the possession's archetype, or if the possession is up for auction
+/// or had already been sold to someone else.
+pub async fn fill_buy_order(
+    db: &DynamoDbClient,
+    order_id: uuid::Uuid,
+    possession: Possession,
+    listed_price: Option<u64>,
+) -> Result<bool, String> {
+    if up_for_auction(db, possession.id).await? {
+        return Ok(false);
+    }
+    let order = match take_from_buy_order(db, order_id, possession.archetype_handle).await? {
+        Some(order) => order,
+        None => return Ok(false),
+    };
+    let name = possession.name.clone();
+    let seller = possession.steader.clone();
+    let (price, fee) = match listed_price {
+        Some(price) => (price, 0),
+        None => (
+            order.price,
+            CONFIG
+                .market
+                .fees
+                .fee(possession.kind.category(), order.price),
+        ),
+    };
+    let seller_earnings = match complete_sale(
+        db,
+        &possession,
+        seller.clone(),
+        order.buyer.clone(),
+        price,
+        fee,
+    )
+    .await?
+    {
+        Some((seller_earnings, _)) => seller_earnings,
+        None => {
+            return_to_buy_order(db, &order).await?;
+            return Ok(false);
+        }
+    };
+
+    if order.price > price {
+        banker::pay(
+            order.buyer.clone(),
+            order.price - price,
+            format!("change from your buy order for {}", name),
+        )
+        .await?;
+    }
+
+    let image_url = format!(
+        "http://{}/gotchi/img/{}/{}.png",
+        *URL,
+        possession.kind.category(),
+        filify(&name)
+    );
+    futures::try_join!(
+        dm_blocks(
+            order.buyer.clone(),
+            vec![
+                json!({
+                    "type": "section",
+                    "text": mrkdwn(format!(
+                        "Your buy order has gotten you a {} *{}* from <@{}> for *{} GP*! \
+                        It's looking for *{}* more.",
+                        emojify(&name),
+                        name,
+                        seller,
+                        price,
+                        order.quantity
+                    )),
+                    "accessory": {
+                        "type": "image",
+                        "image_url": image_url,
+                        "alt_text": "Hackpheus holding a Gift!",
+                    }
+                }),
+                comment("STANDING ORDERS, STANDING OVATIONS"),
+            ]
+        ),
+        dm_blocks(
+            seller.clone(),
+            vec![json!({
+                "type": "section",
+                "text": mrkdwn(format!(
+                    "Your {} *{}* has been bought by <@{}>'s buy order, earning you *{} GP*!",
+                    emojify(&name),
+                    name,
+                    order.buyer,
+                    seller_earnings
+                )),
+            })]
+        ),
+        log_blocks(vec![
+            json!({
+                "type": "section",
+                "text": mrkdwn(format!(
+                    "A buy order has been filled! \
+                    <@{}> sold a *{}* to <@{}> for *{} GP*!",
+                    seller, name, order.buyer, price
+                )),
+                "accessory": {
+                    "type": "image",
+                    "image_url": image_url,
+                    "alt_text": "Hackpheus sitting on bags of money!",
+                }
+            }),
+            comment("ORDER UP"),
+        ]),
+    )
+    .map_err(|e| format!("Couldn't fill buy order {}: {}", order_id, e))?;
+
+    Ok(true)
+}
+
+/// Fills the best paying buy order willing to pay for something that was just listed.
+/// Returns whether any buy order took it.
+pub async fn match_listing(
+    db: &DynamoDbClient,
+    mut possession: Possession,
+    price: u64,
+) -> Result<bool, String> {
+    // it was read before it was listed, but the sale should only go through if it's still up
+    possession.sale = Some(Sale {
+        price,
+        market_name: possession.name.clone(),
+        expires: None,
+    });
+
+    let orders = buy_orders_for(db, possession.archetype_handle).await?;
+    for order in orders
+        .iter()
+        .filter(|o| o.price >= price && o.buyer != possession.steader)
+    {
+        if fill_buy_order(db, order.id, possession.clone(), Some(price)).await? {
+            return Ok(true);
+        }
+    }
+
+    Ok(false)
+}
+
+/// Where the market keeps track of who's watching for an archetype, and for how much,
+/// so that listings don't have to go through every profile to find them.
+fn watchers_key(archetype_handle: ArchetypeHandle) -> Key {
+    Key {
+        category: Category::Watchers,
+        id: uuid::Uuid::from_u128(archetype_handle as u128),
+    }
+}
+
+/// Starts watching for an archetype on someone's behalf,
+/// or if `max_price` is None, stops.
+pub async fn index_watch(
+    db: &DynamoDbClient,
+    archetype_handle: ArchetypeHandle,
+    watcher: String,
+    max_price: Option<u64>,
+) -> Result<(), String> {
+    let max_price = match max_price {
+        Some(max_price) => max_price,
+        None => return unindex_watch(db, archetype_handle, watcher).await,
+    };
+
+    if set_watch(db, archetype_handle, &watcher, max_price).await? {
+        return Ok(());
+    }
+    if start_watchers(db, archetype_handle, &watcher, max_price).await? {
+        return Ok(());
+    }
+    // someone else might've been the first to watch for these
+    if set_watch(db, archetype_handle, &watcher, max_price).await? {
+        Ok(())
+    } else {
+        Err(format!(
+            "Couldn't index {}'s watch for archetype {}",
+            watcher, archetype_handle
+        ))
+    }
+}
+
+/// Updates someone's watch in an archetype's existing list of watchers,
+/// returning false if nobody's watched for that archetype before.
+async fn set_watch(
+    db: &DynamoDbClient,
+    archetype_handle: ArchetypeHandle,
+    watcher: &str,
+    max_price: u64,
+) -> Result<bool, String> {
+    match db
+        .update_item(rusoto_dynamodb::UpdateItemInput {
+            key: watchers_key(archetype_handle).into_item(),
+            table_name: core::TABLE_NAME.to_string(),
+            update_expression: Some("SET watchers.#watcher = :max_price".to_string()),
+            condition_expression: Some("attribute_exists(watchers)".to_string()),
+            expression_attribute_names: Some(
+                [("#watcher".to_string(), watcher.to_string())]
+                    .iter()
+                    .cloned()
+                    .collect(),
+            ),
+            expression_attribute_values: Some(
+                [(
+                    ":max_price".to_string(),
+                    AttributeValue {
+                        n: Some(max_price.to_string()),
+                        ..Default::default()
+                    },
+                )]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            ..Default::default()
+        })
+        .await
+    {
+        Ok(_) => Ok(true),
+        Err(RusotoError::Service(UpdateItemError::ConditionalCheckFailed(_))) => Ok(false),
+        Err(e) => Err(format!(
+            "Couldn't index {}'s watch for archetype {}: {}",
+            watcher, archetype_handle, e
+        )),
+    }
+}
+
+/// Starts an archetype's list of watchers off with just this one,
+/// returning false if someone else has already started it.
+async fn start_watchers(
+    db: &DynamoDbClient,
+    archetype_handle: ArchetypeHandle,
+    watcher: &str,
+    max_price: u64,
+) -> Result<bool, String> {
+    match db
+        .update_item(rusoto_dynamodb::UpdateItemInput {
+            key: watchers_key(archetype_handle).into_item(),
+            table_name: core::TABLE_NAME.to_string(),
+            update_expression: Some("SET watchers = :watchers".to_string()),
+            condition_expression: Some("attribute_not_exists(watchers)".to_string()),
+            expression_attribute_values: Some(
+                [(
+                    ":watchers".to_string(),
+                    AttributeValue {
+                        m: Some(
+                            [(
+                                watcher.to_string(),
+                                AttributeValue {
+                                    n: Some(max_price.to_string()),
+                                    ..Default::default()
+                                },
+                            )]
+                            .iter()
+                            .cloned()
+                            .collect(),
+                        ),
+                        ..Default::default()
+                    },
+                )]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            ..Default::default()
+        })
+        .await
+    {
+        Ok(_) => Ok(true),
+        Err(RusotoError::Service(UpdateItemError::ConditionalCheckFailed(_))) => Ok(false),
+        Err(e) => Err(format!(
+            "Couldn't index {}'s watch for archetype {}: {}",
+            watcher, archetype_handle, e
+        )),
+    }
+}
+
+/// Takes someone off of an archetype's list of watchers.
+async fn unindex_watch(
+    db: &DynamoDbClient,
+    archetype_handle: ArchetypeHandle,
+    watcher: String,
+) -> Result<(), String> {
+    match db
+        .update_item(rusoto_dynamodb::UpdateItemInput {
+            key: watchers_key(archetype_handle).into_item(),
+            table_name: core::TABLE_NAME.to_string(),
+            update_expression: Some("REMOVE watchers.#watcher".to_string()),
+            condition_expression: Some("attribute_exists(watchers)".to_string()),
+            expression_attribute_names: Some(
+                [("#watcher".to_string(), watcher.clone())]
+                    .iter()
+                    .cloned()
+                    .collect(),
+            ),
+            ..Default::default()
+        })
+        .await
+    {
+        // nobody was watching for these anyway
+        Ok(_) | Err(RusotoError::Service(UpdateItemError::ConditionalCheckFailed(_))) => Ok(()),
+        Err(e) => Err(format!(
+            "Couldn't unindex {}'s watch for archetype {}: {}",
+            watcher, archetype_handle, e
+        )),
+    }
+}
+
+/// Everyone watching for an archetype, and the most they'd pay for one.
+async fn watchers(
+    db: &DynamoDbClient,
+    archetype_handle: ArchetypeHandle,
+) -> Result<Vec<(String, u64)>, String> {
+    let item = db
+        .get_item(rusoto_dynamodb::GetItemInput {
+            key: watchers_key(archetype_handle).into_item(),
+            table_name: core::TABLE_NAME.to_string(),
+            ..Default::default()
+        })
+        .await
+        .map_err(|e| {
+            format!(
+                "Couldn't read watchers of archetype {}: {}",
+                archetype_handle, e
+            )
+        })?
+        .item;
+
+    Ok(item
+        .as_ref()
+        .and_then(|item| item.get("watchers"))
+        .and_then(|av| av.m.as_ref())
+        .map(|watchers| {
+            watchers
+                .iter()
+                .filter_map(|(watcher, av)| Some((watcher.clone(), av.n.as_ref()?.parse().ok()?)))
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+/// Lets everyone watching the market for an archetype know that `count` of them
+/// have just gone up for sale by `seller` at a price they'd pay.
+pub async fn notify_watchers(
+    db: &DynamoDbClient,
+    seller: &str,
+    archetype_handle: ArchetypeHandle,
+    price: u64,
+    count: usize,
+) -> Result<(), String> {
+    let archetype = CONFIG
+        .possession_archetypes
+        .get(archetype_handle)
+        .ok_or_else(|| format!("watched listing of unknown archetype {}", archetype_handle))?;
+    let watchers: Vec<String> = watchers(db, archetype_handle)
+        .await?
+        .into_iter()
+        .filter(|(watcher, max_price)| watcher != seller && price <= *max_price)
+        .map(|(watcher, _)| watcher)
+        .collect();
+
+    let what = match count {
+        1 => format!("A {} *{}*", emojify(&archetype.name), archetype.name),
+        n => format!("*{}* {} *{}*", n, emojify(&archetype.name), archetype.name),
+    };
+    let page_json = serde_json::to_string(&(&archetype.name, archetype.kind.category())).unwrap();
+    futures::future::try_join_all(watchers.into_iter().map(|watcher| {
+        dm_blocks(
+            watcher,
+            vec![
+                json!({
+                    "type": "section",
+                    "text": mrkdwn(format!(
+                        "{} just went up on the hackmarket for *{} GP*{}, \
+                        and you've been watching for those!",
+                        what,
+                        price,
+                        if count == 1 { "" } else { " each" }
+                    )),
+                    "accessory": {
+                        "type": "button",
+                        "style": "primary",
+                        "text": plain_text("View Listings"),
+                        "value": page_json.clone(),
+                        "action_id": "possession_market_overview_page",
+                    }
+                }),
+                comment("You can stop watching for them from your watchlist on the hackmarket."),
+            ],
+        )
+    }))
+    .await?;
+
+    Ok(())
+}
+
+/// Fills a buy order from the cheapest listings it's willing to pay for.
+pub async fn match_buy_order(db: &DynamoDbClient, order: &BuyOrder) -> Result<(), String> {
+    let category = match CONFIG.possession_archetypes.get(order.archetype_handle) {
+        Some(a) => a.kind.category(),
+        None => return Err(format!("buy order {} for unknown archetype", order.id)),
+    };
+    let mut listings: Vec<(Sale, Possession)> = market_search(db, category)
+        .await?
+        .into_iter()
+        .filter(|(sale, p)| {
+            p.archetype_handle == order.archetype_handle
+                && sale.price <= order.price
+                && p.steader != order.buyer
+        })
+        .collect();
+    listings.sort_by_key(|(sale, _)| sale.price);
+
+    for (sale, possession) in listings.into_iter().take(order.quantity as usize) {
+        fill_buy_order(db, order.id, possession, Some(sale.price)).await?;
+    }
+
+    Ok(())
+}
+
+/// Buys up to `quantity` of an archetype at once, from the cheapest listings going for
+/// at most `max_price` each, for a buyer who's already paid `quantity * max_price`.
+/// Whatever isn't spent is refunded, even if some of the sales fail,
+/// and each seller is sent one DM for all they've sold.
+/// Returns what was bought, with what was paid for each.
+pub async fn bulk_purchase(
+    db: &DynamoDbClient,
+    buyer: String,
+    archetype_handle: ArchetypeHandle,
+    quantity: u64,
+    max_price: u64,
+) -> Result<Vec<(Possession, u64)>, String> {
+    let archetype = CONFIG
+        .possession_archetypes
+        .get(archetype_handle)
+        .ok_or_else(|| format!("bulk purchase of unknown archetype {}", archetype_handle))?;
+    let mut listings: Vec<(Sale, Possession)> = market_search(db, archetype.kind.category())
+        .await?
+        .into_iter()
+        .filter(|(sale, p)| {
+            p.archetype_handle == archetype_handle && sale.price <= max_price && p.steader != buyer
+        })
+        .collect();
+    listings.sort_by_key(|(sale, _)| sale.price);
+
+    // the buyer still needs their change if some of the sales fail
+    let mut errors = vec![];
+    let mut bought = vec![];
+    let mut earnings: Vec<(String, u64, u64)> = vec![];
+    for (sale, possession) in listings {
+        if bought.len() as u64 == quantity {
+            break;
+        }
+        let seller = possession.steader.clone();
+        match transfer_sale(db, &possession, seller.clone(), buyer.clone(), sale.price).await {
+            Ok(true) => {}
+            // it could've been bought by someone else since the search
+            Ok(false) => continue,
+            Err(e) => {
+                errors.push(e);
+                continue;
+            }
+        }
+
+        // the buyer has it now, so it's been bought even if paying for it goes wrong
+        match pay_out_sale(
+            db,
+            &possession,
+            seller.clone(),
+            buyer.clone(),
+            sale.price,
+            0,
+        )
+        .await
+        {
+            Ok((seller_earnings, _)) => match earnings.iter_mut().find(|(s, _, _)| *s == seller) {
+                Some((_, count, gp)) => {
+                    *count += 1;
+                    *gp += seller_earnings;
+                }
+                None => earnings.push((seller, 1, seller_earnings)),
+            },
+            Err(e) => println!(
+                "couldn't pay {} for the {} {} bought in bulk: {}",
+                seller, possession.name, possession.id, e
+            ),
+        }
+        bought.push((possession, sale.price));
+    }
+
+    let spent: u64 = bought.iter().map(|(_, price)| price).sum();
+    if quantity * max_price > spent {
+        banker::pay(
+            buyer.clone(),
+            quantity * max_price - spent,
+            format!("change from your bulk purchase of {}", archetype.name),
+        )
+        .await?;
+    }
+
+    futures::future::try_join_all(earnings.into_iter().map(|(seller, count, gp)| {
+        dm_blocks(
+            seller,
+            vec![json!({
+                "type": "section",
+                "text": mrkdwn(format!(
+                    "<@{}> bought *{}* of your {} *{}* on the hackmarket, earning you *{} GP*!",
+                    buyer,
+                    count,
+                    emojify(&archetype.name),
+                    archetype.name,
+                    gp
+                )),
+            })],
+        )
+    }))
+    .await?;
+
+    if !errors.is_empty() {
+        return Err(errors.join(", "));
+    }
+
+    Ok(bought)
+}
+
+/// Puts an auction up on the market.
+/// Returns false if the possession is already up for auction.
+pub async fn start_auction(db: &DynamoDbClient, auction: &Auction) -> Result<bool, String> {
+    println!("starting auction {}", auction.id);
+
+    match db
+        .put_item(rusoto_dynamodb::PutItemInput {
+            item: auction.item(),
+            table_name: core::TABLE_NAME.to_string(),
+            condition_expression: Some("attribute_not_exists(id)".to_string()),
+            ..Default::default()
+        })
+        .await
+    {
+        Ok(_) => Ok(true),
+        Err(RusotoError::Service(PutItemError::ConditionalCheckFailed(_))) => Ok(false),
+        Err(e) => Err(format!("Couldn't start auction {}: {}", auction.id, e)),
+    }
+}
+
+/// Every auction on the market, including those which have ended but haven't been settled.
+pub async fn auctions(db: &DynamoDbClient) -> Result<Vec<Auction>, String> {
+    let query = db
+        .query(rusoto_dynamodb::QueryInput {
+            table_name: core::TABLE_NAME.to_string(),
+            index_name: Some("cat_price_index".to_string()),
+            key_condition_expression: Some("cat = :auction_cat".to_string()),
+            expression_attribute_values: Some(
+                [(":auction_cat".to_string(), Category::Auction.into_av())]
+                    .iter()
+                    .cloned()
+                    .collect(),
+            ),
+            ..Default::default()
+        })
+        .await;
+
+    Ok(query
+        .map_err(|e| format!("Couldn't search auctions: {}", e))?
+        .items
+        .ok_or_else(|| format!("auction query returned no items"))?
+        .iter()
+        .filter_map(|i| match Auction::from_item(i) {
+            Ok(auction) => Some(auction),
+            Err(e) => {
+                println!("error parsing auction: {}", e);
+                None
+            }
+        })
+        .collect())
+}
+
+pub async fn get_auction(db: &DynamoDbClient, id: uuid::Uuid) -> Result<Auction, String> {
+    let item = db
+        .get_item(rusoto_dynamodb::GetItemInput {
+            key: Key {
+                category: Category::Auction,
+                id,
+            }
+            .into_item(),
+            table_name: core::TABLE_NAME.to_string(),
+            ..Default::default()
+        })
+        .await
+        .map_err(|e| format!("Couldn't read auction {}: {}", id, e))?
+        .item
+        .ok_or_else(|| format!("no auction {}", id))?;
+
+    Auction::from_item(&item).map_err(|e| format!("couldn't parse auction: {}", e))
+}
+
+/// Whether a possession is up for auction, or has been won at one that hasn't been settled.
+pub async fn up_for_auction(db: &DynamoDbClient, id: uuid::Uuid) -> Result<bool, String> {
+    Ok(db
+        .get_item(rusoto_dynamodb::GetItemInput {
+            key: Key {
+                category: Category::Auction,
+                id,
+            }
+            .into_item(),
+            table_name: core::TABLE_NAME.to_string(),
+            ..Default::default()
+        })
+        .await
+        .map_err(|e| format!("Couldn't read auction {}: {}", id, e))?
+        .item
+        .is_some())
+}
+
+/// Makes this the highest bid on an auction, returning the auction as it was before
+/// so that whoever was outbid can be refunded. Returns None if the bid is too low,
+/// the auction has ended, or the bidder is the seller.
+pub async fn bid_on_auction(
+    db: &DynamoDbClient,
+    id: uuid::Uuid,
+    bid: Bid,
+) -> Result<Option<Auction>, String> {
+    let av_n = |n: u64| AttributeValue {
+        n: Some(n.to_string()),
+        ..Default::default()
+    };
+    let av_s = |s: String| AttributeValue {
+        s: Some(s),
+        ..Default::default()
+    };
+
+    // conditional so that two bids placed at once can't both win
+    match db
+        .update_item(rusoto_dynamodb::UpdateItemInput {
+            key: Key {
+                category: Category::Auction,
+                id,
+            }
+            .into_item(),
+            table_name: core::TABLE_NAME.to_string(),
+            update_expression: Some(
+                "SET bid = :amount, bidder = :bidder, price = :next_min".to_string(),
+            ),
+            condition_expression: Some(
+                concat!(
+                    "ends > :now AND reserve <= :amount AND steader <> :bidder ",
+                    "AND (attribute_not_exists(bid) OR bid < :amount)"
+                )
+                .to_string(),
+            ),
+            expression_attribute_values: Some(
+                [
+                    (":amount".to_string(), av_n(bid.amount)),
+                    (":next_min".to_string(), av_n(bid.amount + 1)),
+                    (":bidder".to_string(), av_s(bid.bidder)),
+                    (
+                        ":now".to_string(),
+                        av_s(format_rfc3339(SystemTime::now()).to_string()),
+                    ),
+                ]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            return_values: Some("ALL_OLD".to_string()),
+            ..Default::default()
+        })
+        .await
+    {
+        Ok(rusoto_dynamodb::UpdateItemOutput {
+            attributes: Some(item),
+            ..
+        }) => Auction::from_item(&item)
+            .map(Some)
+            .map_err(|e| format!("couldn't parse auction before bid: {}", e)),
+        Err(RusotoError::Service(UpdateItemError::ConditionalCheckFailed(_))) => Ok(None),
+        Err(e) => Err(format!("Couldn't bid on auction {}: {}", id, e)),
+        _ => Err(format!("no attributes returned!")),
+    }
+}
+
+/// Takes an auction which has ended off of the market, returning how it ended,
+/// or None if it's already been settled.
+async fn close_auction(db: &DynamoDbClient, id: uuid::Uuid) -> Result<Option<Auction>, String> {
+    // conditional so that an auction is only ever settled once
+    match db
+        .delete_item(rusoto_dynamodb::DeleteItemInput {
+            key: Key {
+                category: Category::Auction,
+                id,
+            }
+            .into_item(),
+            table_name: core::TABLE_NAME.to_string(),
+            condition_expression: Some("ends <= :now".to_string()),
+            expression_attribute_values: Some(
+                [(
+                    ":now".to_string(),
+                    AttributeValue {
+                        s: Some(format_rfc3339(SystemTime::now()).to_string()),
+                        ..Default::default()
+                    },
+                )]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            return_values: Some("ALL_OLD".to_string()),
+            ..Default::default()
+        })
+        .await
+    {
+        Ok(rusoto_dynamodb::DeleteItemOutput {
+            attributes: Some(item),
+            ..
+        }) => Auction::from_item(&item)
+            .map(Some)
+            .map_err(|e| format!("couldn't parse closed auction: {}", e)),
+        Ok(_) => Ok(None),
+        Err(RusotoError::Service(DeleteItemError::ConditionalCheckFailed(_))) => Ok(None),
+        Err(e) => Err(format!("Couldn't close auction {}: {}", id, e)),
+    }
+}
+
+/// Hands an auction's possession over to whoever bid the most for it,
+/// or lets the seller know that nobody did.
+async fn settle_auction(db: &DynamoDbClient, id: uuid::Uuid) -> Result<(), String> {
+    let auction = match close_auction(db, id).await? {
+        Some(auction) => auction,
+        None => return Ok(()),
+    };
+    let name = auction.name().to_string();
+    let image_url = format!(
+        "http://{}/gotchi/img/{}/{}.png",
+        *URL,
+        auction.possession_category,
+        filify(&name)
+    );
+
+    let Bid { bidder, amount } = match auction.high_bid {
+        Some(bid) => bid,
+        None => {
+            return dm_blocks(
+                auction.seller.clone(),
+                vec![json!({
+                    "type": "section",
+                    "text": mrkdwn(format!(
+                        "Your auction for a {} *{}* has ended without any bids, \
+                        so it's still yours.",
+                        emojify(&name),
+                        name
+                    )),
+                })],
+            )
+            .await;
+        }
+    };
+
+    // it could've been given away or sold since the auction started
+    let sale = match hacksteader::get_possession(db, auction.possession_key()).await {
+        Ok(p) if p.steader == auction.seller => {
+            complete_sale(
+                db,
+                &p,
+                auction.seller.clone(),
+                bidder.clone(),
+                amount,
+                CONFIG.market.fees.fee(p.kind.category(), amount),
+            )
+            .await?
+        }
+        _ => None,
+    };
+    let seller_earnings = match sale {
+        Some((seller_earnings, _)) => seller_earnings,
+        None => {
+            return futures::try_join!(
+                banker::pay(
+                    bidder.clone(),
+                    amount,
+                    format!("the {} you won at auction is no longer available", name),
+                ),
+                dm_blocks(
+                    bidder.clone(),
+                    vec![json!({
+                        "type": "section",
+                        "text": mrkdwn(format!(
+                            "The {} *{}* you won at auction is no longer the seller's to sell, \
+                            so your *{} GP* has been refunded.",
+                            emojify(&name),
+                            name,
+                            amount
+                        )),
+                    })]
+                ),
+            )
+            .map(|_| ());
+        }
+    };
+
+    futures::try_join!(
+        dm_blocks(
+            bidder.clone(),
+            vec![
+                json!({
+                    "type": "section",
+                    "text": mrkdwn(format!(
+                        "You've won the auction for <@{}>'s {} *{}* with a bid of *{} GP*!",
+                        auction.seller,
+                        emojify(&name),
+                        name,
+                        amount
+                    )),
+                    "accessory": {
+                        "type": "image",
+                        "image_url": image_url,
+                        "alt_text": "Hackpheus holding a Gift!",
+                    }
+                }),
+                comment("GOING ONCE, GOING TWICE, SOLD"),
+            ]
+        ),
+        dm_blocks(
+            auction.seller.clone(),
+            vec![json!({
+                "type": "section",
+                "text": mrkdwn(format!(
+                    "Your auction for a {} *{}* has been won by <@{}>, earning you *{} GP*!",
+                    emojify(&name),
+                    name,
+                    bidder,
+                    seller_earnings
+                )),
+            })]
+        ),
+        log_blocks(vec![
+            json!({
+                "type": "section",
+                "text": mrkdwn(format!(
+                    "An auction has ended! \
+                    <@{}> won <@{}>'s *{}* for *{} GP*!",
+                    bidder, auction.seller, name, amount
+                )),
+                "accessory": {
+                    "type": "image",
+                    "image_url": image_url,
+                    "alt_text": "Hackpheus sitting on bags of money!",
+                }
+            }),
+            comment("SOLD TO THE HACKSTEADER IN THE BACK"),
+        ]),
+    )
+    .map_err(|e| format!("Couldn't settle auction {}: {}", id, e))?;
+
+    Ok(())
+}
+
+/// Settles every auction which has ended.
+pub async fn settle_ended_auctions(db: &DynamoDbClient) -> Result<(), String> {
+    for auction in auctions(db).await? {
+        if auction.time_left().is_none() {
+            println!("settling auction {}", auction.id);
+            settle_auction(db, auction.id).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Takes listings that have been up for too long off of the market,
+/// letting their sellers know so that they can put them back up if they like.
+/// Sellers get one DM for each kind of thing of theirs that's expired.
+pub async fn expire_listings(db: &DynamoDbClient) -> Result<(), String> {
+    let (gotchis, misc) =
+        futures::try_join!(listings(db, Category::Gotchi), listings(db, Category::Misc))?;
+
+    let mut expired: std::collections::HashMap<(String, ArchetypeHandle), Vec<(Sale, Possession)>> =
+        Default::default();
+    for (sale, possession) in gotchis
+        .into_iter()
+        .chain(misc)
+        .filter(|(sale, _)| sale.expired())
+    {
+        println!("expiring listing of {}", possession.id);
+        if let Err(e) = take_off_market(db, possession.key()).await {
+            println!("couldn't expire listing of {}: {}", possession.id, e);
+            continue;
+        }
+
+        expired
+            .entry((possession.steader.clone(), possession.archetype_handle))
+            .or_default()
+            .push((sale, possession));
+    }
+
+    for ((seller, _), mut listings) in expired {
+        let blocks = match listings.len() {
+            1 => {
+                let (sale, possession) = listings.pop().unwrap();
+                expired_listing_blocks(&sale, &possession)
+            }
+            _ => expired_listings_blocks(&listings),
+        };
+        if let Err(e) = dm_blocks(seller.clone(), blocks).await {
+            println!("couldn't tell {} their listings expired: {}", seller, e);
+        }
+    }
+
+    Ok(())
+}
+
+/// Lets a seller know that nobody bought their possession, offering to put it back up.
+fn expired_listing_blocks(sale: &Sale, possession: &Possession) -> Vec<serde_json::Value> {
+    let key_json = serde_json::to_string(&possession.key()).unwrap();
+    vec![
+        json!({
+            "type": "section",
+            "text": mrkdwn(format!(
+                "Nobody bought your {} *{}* for *{} GP*, \
+                so it's been taken off of the hackmarket.",
+                emojify(&possession.name),
+                possession.nickname(),
+                sale.price
+            )),
+        }),
+        json!({
+            "type": "actions",
+            "elements": [
+                {
+                    "type": "button",
+                    "style": "primary",
+                    "text": plain_text(format!("Relist for {}gp", sale.price)),
+                    "value": serde_json::to_string(&(possession.key(), sale.price)).unwrap(),
+                    "action_id": "listing_relist",
+                },
+                {
+                    "type": "button",
+                    "text": plain_text("Relist at New Price"),
+                    "value": key_json,
+                    "action_id": "listing_relist_new_price",
+                },
+            ]
+        }),
+        comment("Relisting it means paying the market fee again."),
+    ]
+}
+
+/// Lets a seller know that nobody bought several of their possessions of the same kind.
+fn expired_listings_blocks(listings: &[(Sale, Possession)]) -> Vec<serde_json::Value> {
+    let name = &listings[0].1.name;
+    let low = listings.iter().map(|(s, _)| s.price).min().unwrap_or(0);
+    let high = listings.iter().map(|(s, _)| s.price).max().unwrap_or(0);
+    vec![
+        json!({
+            "type": "section",
+            "text": mrkdwn(format!(
+                "Nobody bought your *{}* {} *{}* for *{} GP*, \
+                so they've been taken off of the hackmarket.",
+                listings.len(),
+                emojify(name),
+                name,
+                if low == high {
+                    low.to_string()
+                } else {
+                    format!("{}-{}", low, high)
+                }
+            )),
+        }),
+        comment("You can put them back up from your hackstead, for the market fee again."),
+    ]
+}
@@ -0,0 +1,254 @@
+use super::{comment, mrkdwn, plain_text};
+use core::config::{ArchetypeHandle, CONFIG};
+use core::frontend::emojify;
+use core::{Category, Key};
+use rusoto_core::RusotoError;
+use rusoto_dynamodb::{AttributeValue, DynamoDb, DynamoDbClient, UpdateItemError};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::time::SystemTime;
+
+/// Where the shop keeps track of how much of each item it's sold today.
+fn stock_key() -> Key {
+    Key {
+        category: Category::ShopStock,
+        id: uuid::Uuid::nil(),
+    }
+}
+
+/// Days since the UNIX epoch, so the shop restocks at midnight UTC.
+pub fn today() -> u64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_secs() / (60 * 60 * 24))
+        .unwrap_or(0)
+}
+
+fn number(n: u64) -> AttributeValue {
+    AttributeValue {
+        n: Some(n.to_string()),
+        ..Default::default()
+    }
+}
+
+/// How many of each item the shop has sold so far today.
+pub async fn sold_today(db: &DynamoDbClient) -> Result<HashMap<ArchetypeHandle, u64>, String> {
+    let item = db
+        .get_item(rusoto_dynamodb::GetItemInput {
+            key: stock_key().into_item(),
+            table_name: core::TABLE_NAME.to_string(),
+            ..Default::default()
+        })
+        .await
+        .map_err(|e| format!("Couldn't read shop stock: {}", e))?
+        .item;
+
+    let item = match item {
+        Some(item) => item,
+        None => return Ok(HashMap::new()),
+    };
+    let day: Option<u64> = item
+        .get("day")
+        .and_then(|av| av.n.as_ref())
+        .and_then(|n| n.parse().ok());
+    if day != Some(today()) {
+        return Ok(HashMap::new());
+    }
+
+    Ok(item
+        .get("sold")
+        .and_then(|av| av.m.as_ref())
+        .map(|sold| {
+            sold.iter()
+                .filter_map(|(ah, av)| Some((ah.parse().ok()?, av.n.as_ref()?.parse().ok()?)))
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+/// Sets aside `quantity` of an item from the stock of `day`, as long as it's still that day
+/// and exactly `sold` of it have been sold so far, returning false, and setting aside none
+/// of them, otherwise. That's how many had been sold when the buyer was quoted a price,
+/// so nobody gets them for less than they'd cost now, or at yesterday's prices.
+pub async fn reserve(
+    db: &DynamoDbClient,
+    ah: ArchetypeHandle,
+    quantity: u64,
+    daily_stock: u64,
+    day: u64,
+    sold: u64,
+) -> Result<bool, String> {
+    if day != today() || sold + quantity > daily_stock {
+        return Ok(false);
+    }
+
+    if reserve_on(db, ah, quantity, day, sold).await? {
+        return Ok(true);
+    }
+    if sold > 0 {
+        return Ok(false);
+    }
+    if restock(db, ah, quantity, day).await? {
+        return Ok(true);
+    }
+    // someone else might've been the first to restock today
+    reserve_on(db, ah, quantity, day, sold).await
+}
+
+/// Adds to what's been sold of an item on `day`, as long as exactly `sold` had been sold already.
+async fn reserve_on(
+    db: &DynamoDbClient,
+    ah: ArchetypeHandle,
+    quantity: u64,
+    day: u64,
+    sold: u64,
+) -> Result<bool, String> {
+    let mut values: HashMap<String, AttributeValue> = [
+        (":day".to_string(), number(day)),
+        (":now_sold".to_string(), number(sold + quantity)),
+    ]
+    .iter()
+    .cloned()
+    .collect();
+    let condition = if sold == 0 {
+        "#day = :day AND attribute_not_exists(sold.#ah)"
+    } else {
+        values.insert(":sold".to_string(), number(sold));
+        "#day = :day AND sold.#ah = :sold"
+    };
+
+    match db
+        .update_item(rusoto_dynamodb::UpdateItemInput {
+            key: stock_key().into_item(),
+            table_name: core::TABLE_NAME.to_string(),
+            update_expression: Some("SET sold.#ah = :now_sold".to_string()),
+            condition_expression: Some(condition.to_string()),
+            expression_attribute_names: Some(
+                [
+                    ("#ah".to_string(), ah.to_string()),
+                    ("#day".to_string(), "day".to_string()),
+                ]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            expression_attribute_values: Some(values),
+            ..Default::default()
+        })
+        .await
+    {
+        Ok(_) => Ok(true),
+        Err(RusotoError::Service(UpdateItemError::ConditionalCheckFailed(_))) => Ok(false),
+        Err(e) => Err(format!(
+            "Couldn't reserve {} of {} from the shop: {}",
+            quantity, ah, e
+        )),
+    }
+}
+
+/// Starts `day`'s stock with `quantity` of an item already sold,
+/// returning false if the shop's already been restocked that day.
+async fn restock(
+    db: &DynamoDbClient,
+    ah: ArchetypeHandle,
+    quantity: u64,
+    day: u64,
+) -> Result<bool, String> {
+    match db
+        .update_item(rusoto_dynamodb::UpdateItemInput {
+            key: stock_key().into_item(),
+            table_name: core::TABLE_NAME.to_string(),
+            update_expression: Some("SET #day = :day, sold = :sold".to_string()),
+            condition_expression: Some("attribute_not_exists(#day) OR #day < :day".to_string()),
+            expression_attribute_names: Some(
+                [("#day".to_string(), "day".to_string())]
+                    .iter()
+                    .cloned()
+                    .collect(),
+            ),
+            expression_attribute_values: Some(
+                [
+                    (":day".to_string(), number(day)),
+                    (
+                        ":sold".to_string(),
+                        AttributeValue {
+                            m: Some(
+                                [(ah.to_string(), number(quantity))]
+                                    .iter()
+                                    .cloned()
+                                    .collect(),
+                            ),
+                            ..Default::default()
+                        },
+                    ),
+                ]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            ..Default::default()
+        })
+        .await
+    {
+        Ok(_) => Ok(true),
+        Err(RusotoError::Service(UpdateItemError::ConditionalCheckFailed(_))) => Ok(false),
+        Err(e) => Err(format!("Couldn't restock the shop: {}", e)),
+    }
+}
+
+/// Everything the shop sells, with what's left of it today.
+pub fn blocks(sold: &HashMap<ArchetypeHandle, u64>, can_buy: bool) -> Vec<Value> {
+    let mut blocks = vec![comment(format!(
+        "{} sells the basics at set prices, and restocks every day at midnight UTC.",
+        CONFIG.shop.keeper
+    ))];
+
+    for item in CONFIG.shop.stock.iter() {
+        let ah = match item.archetype_handle() {
+            Ok(ah) => ah,
+            Err(_) => continue,
+        };
+        let sold = sold.get(&ah).copied().unwrap_or(0);
+        let left = item.daily_stock.saturating_sub(sold);
+
+        let mut section = json!({
+            "type": "section",
+            "text": mrkdwn(format!(
+                "{} *{}*\n{}",
+                emojify(&item.name),
+                item.name,
+                if left > 0 {
+                    format!(
+                        "*{}gp* each, _{} left today_",
+                        CONFIG.shop.price(item, sold),
+                        left
+                    )
+                } else {
+                    "_sold out for today_".to_string()
+                }
+            )),
+        });
+        if can_buy && left > 0 {
+            section.as_object_mut().unwrap().insert(
+                "accessory".to_string(),
+                json!({
+                    "type": "button",
+                    "style": "primary",
+                    "text": plain_text("Buy"),
+                    "value": ah.to_string(),
+                    "action_id": "shop_buy",
+                }),
+            );
+        }
+        blocks.push(section);
+    }
+
+    if CONFIG.shop.markup_percent > 0 {
+        blocks.push(comment(format!(
+            "Prices go up by {}% of what they started at for each one sold that day.",
+            CONFIG.shop.markup_percent
+        )));
+    }
+
+    blocks
+}
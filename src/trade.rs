@@ -0,0 +1,347 @@
+use super::{banker, comment, dm_blocks, hacksteader, mrkdwn, plain_text};
+use core::frontend::emojify;
+use core::trade::TradeOffer;
+use core::{possess, Category, Key, Possession};
+use rusoto_core::RusotoError;
+use rusoto_dynamodb::{
+    AttributeValue, DeleteItemError, DynamoDb, DynamoDbClient, TransactWriteItemsError,
+    UpdateItemError,
+};
+use serde_json::{json, Value};
+
+pub async fn propose(db: &DynamoDbClient, offer: &TradeOffer) -> Result<(), String> {
+    println!("proposing trade {}", offer.id);
+
+    db.put_item(rusoto_dynamodb::PutItemInput {
+        item: offer.item(),
+        table_name: core::TABLE_NAME.to_string(),
+        ..Default::default()
+    })
+    .await
+    .map_err(|e| format!("Couldn't propose trade {}: {}", offer.id, e))?;
+
+    Ok(())
+}
+
+pub async fn get_offer(db: &DynamoDbClient, id: uuid::Uuid) -> Result<TradeOffer, String> {
+    let item = db
+        .get_item(rusoto_dynamodb::GetItemInput {
+            key: Key {
+                category: Category::TradeOffer,
+                id,
+            }
+            .into_item(),
+            table_name: core::TABLE_NAME.to_string(),
+            ..Default::default()
+        })
+        .await
+        .map_err(|e| format!("Couldn't read trade offer {}: {}", id, e))?
+        .item
+        .ok_or_else(|| format!("no trade offer {}", id))?;
+
+    TradeOffer::from_item(&item).map_err(|e| format!("couldn't parse trade offer: {}", e))
+}
+
+/// Notes that the banker has the GP for an offer, returning the offer,
+/// or None if it was called off before the GP came in.
+pub async fn mark_sent(db: &DynamoDbClient, id: uuid::Uuid) -> Result<Option<TradeOffer>, String> {
+    match db
+        .update_item(rusoto_dynamodb::UpdateItemInput {
+            key: Key {
+                category: Category::TradeOffer,
+                id,
+            }
+            .into_item(),
+            table_name: core::TABLE_NAME.to_string(),
+            update_expression: Some("SET sent = :sent".to_string()),
+            condition_expression: Some("attribute_exists(id)".to_string()),
+            expression_attribute_values: Some(
+                [(
+                    ":sent".to_string(),
+                    AttributeValue {
+                        bool: Some(true),
+                        ..Default::default()
+                    },
+                )]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            return_values: Some("ALL_NEW".to_string()),
+            ..Default::default()
+        })
+        .await
+    {
+        Ok(rusoto_dynamodb::UpdateItemOutput {
+            attributes: Some(item),
+            ..
+        }) => TradeOffer::from_item(&item)
+            .map(Some)
+            .map_err(|e| format!("couldn't parse sent trade offer: {}", e)),
+        Err(RusotoError::Service(UpdateItemError::ConditionalCheckFailed(_))) => Ok(None),
+        Err(e) => Err(format!("Couldn't send trade offer {}: {}", id, e)),
+        _ => Err(format!("no attributes returned!")),
+    }
+}
+
+/// Throws out an offer, returning what it was so that any GP the banker's holding for it
+/// can be refunded. Either side of the trade can call it off.
+pub async fn call_off(
+    db: &DynamoDbClient,
+    id: uuid::Uuid,
+    user: String,
+) -> Result<Option<TradeOffer>, String> {
+    match db
+        .delete_item(rusoto_dynamodb::DeleteItemInput {
+            key: Key {
+                category: Category::TradeOffer,
+                id,
+            }
+            .into_item(),
+            table_name: core::TABLE_NAME.to_string(),
+            condition_expression: Some("steader = :user OR recipient = :user".to_string()),
+            expression_attribute_values: Some(
+                [(
+                    ":user".to_string(),
+                    AttributeValue {
+                        s: Some(user),
+                        ..Default::default()
+                    },
+                )]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            return_values: Some("ALL_OLD".to_string()),
+            ..Default::default()
+        })
+        .await
+    {
+        Ok(rusoto_dynamodb::DeleteItemOutput {
+            attributes: Some(item),
+            ..
+        }) => TradeOffer::from_item(&item)
+            .map(Some)
+            .map_err(|e| format!("couldn't parse trade offer called off: {}", e)),
+        Ok(_) => Ok(None),
+        Err(RusotoError::Service(DeleteItemError::ConditionalCheckFailed(_))) => Ok(None),
+        Err(e) => Err(format!("Couldn't call off trade offer {}: {}", id, e)),
+    }
+}
+
+/// Gives back any GP the banker was holding for an offer that's been called off.
+pub async fn refund(offer: &TradeOffer) -> Result<(), String> {
+    if offer.sent && offer.gp > 0 {
+        banker::pay(
+            offer.proposer.clone(),
+            offer.gp,
+            "a trade offer that didn't go through".to_string(),
+        )
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Swaps everything in an offer between its proposer and its recipient, all at once.
+/// Returns false, and swaps nothing, if the offer has already been called off, or if
+/// anything in it has since changed hands or gone up on the market or for auction.
+pub async fn execute(db: &DynamoDbClient, offer: &TradeOffer) -> Result<bool, String> {
+    let transfer = |key: &Key, from: &str, to: &str| rusoto_dynamodb::TransactWriteItem {
+        update: Some(rusoto_dynamodb::Update {
+            key: key.into_item(),
+            table_name: core::TABLE_NAME.to_string(),
+            update_expression: concat!(
+                "SET ",
+                "steader = :new_owner, ",
+                "ownership_log = list_append(ownership_log, :ownership_entry)",
+            )
+            .to_string(),
+            condition_expression: Some(
+                "steader = :old_owner AND attribute_not_exists(price)".to_string(),
+            ),
+            expression_attribute_values: Some(
+                [
+                    (
+                        ":old_owner".to_string(),
+                        AttributeValue {
+                            s: Some(from.to_string()),
+                            ..Default::default()
+                        },
+                    ),
+                    (
+                        ":new_owner".to_string(),
+                        AttributeValue {
+                            s: Some(to.to_string()),
+                            ..Default::default()
+                        },
+                    ),
+                    (
+                        ":ownership_entry".to_string(),
+                        AttributeValue {
+                            l: Some(vec![possess::Owner {
+                                id: to.to_string(),
+                                acquisition: possess::Acquisition::Trade,
+                            }
+                            .into()]),
+                            ..Default::default()
+                        },
+                    ),
+                ]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+
+    // auctions hold onto their possessions until they're settled,
+    // and an auction's key shares its possession's id
+    let not_auctioned = |key: &Key| rusoto_dynamodb::TransactWriteItem {
+        condition_check: Some(rusoto_dynamodb::ConditionCheck {
+            key: Key {
+                category: Category::Auction,
+                id: key.id,
+            }
+            .into_item(),
+            table_name: core::TABLE_NAME.to_string(),
+            condition_expression: "attribute_not_exists(id)".to_string(),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+
+    // the offer is used up by the trade, so that it can't be accepted twice
+    let take_offer = rusoto_dynamodb::TransactWriteItem {
+        delete: Some(rusoto_dynamodb::Delete {
+            key: offer.key().into_item(),
+            table_name: core::TABLE_NAME.to_string(),
+            condition_expression: Some("sent = :sent".to_string()),
+            expression_attribute_values: Some(
+                [(
+                    ":sent".to_string(),
+                    AttributeValue {
+                        bool: Some(true),
+                        ..Default::default()
+                    },
+                )]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+
+    let transact_items = std::iter::once(take_offer)
+        .chain(
+            offer
+                .offered
+                .iter()
+                .map(|k| transfer(k, &offer.proposer, &offer.recipient)),
+        )
+        .chain(
+            offer
+                .requested
+                .iter()
+                .map(|k| transfer(k, &offer.recipient, &offer.proposer)),
+        )
+        .chain(
+            offer
+                .offered
+                .iter()
+                .chain(offer.requested.iter())
+                .map(not_auctioned),
+        )
+        .collect();
+
+    match db
+        .transact_write_items(rusoto_dynamodb::TransactWriteItemsInput {
+            transact_items,
+            ..Default::default()
+        })
+        .await
+    {
+        Ok(_) => Ok(true),
+        Err(RusotoError::Service(TransactWriteItemsError::TransactionCanceled(_))) => Ok(false),
+        Err(e) => Err(format!("Couldn't execute trade {}: {}", offer.id, e)),
+    }
+}
+
+/// Looks up what's being offered and what's being requested in a trade.
+pub async fn possessions(
+    db: &DynamoDbClient,
+    offer: &TradeOffer,
+) -> Result<(Vec<Possession>, Vec<Possession>), String> {
+    let fetch = |keys: Vec<Key>| {
+        futures::future::try_join_all(
+            keys.into_iter()
+                .map(move |k| hacksteader::get_possession(db, k)),
+        )
+    };
+
+    futures::try_join!(fetch(offer.offered.clone()), fetch(offer.requested.clone()))
+}
+
+/// Lists what each side of a trade would give up.
+pub fn summary_blocks(
+    offer: &TradeOffer,
+    offered: &[Possession],
+    requested: &[Possession],
+) -> Vec<Value> {
+    let list = |possessions: &[Possession], gp: u64| {
+        let mut lines: Vec<String> = possessions
+            .iter()
+            .map(|p| format!("{} _{}_", emojify(&p.name), p.nickname()))
+            .collect();
+        if gp > 0 {
+            lines.push(format!("*{} GP*", gp));
+        }
+        if lines.is_empty() {
+            lines.push("_nothing_".to_string());
+        }
+        lines.join("\n")
+    };
+
+    vec![
+        json!({
+            "type": "section",
+            "fields": [
+                mrkdwn(format!(
+                    "*<@{}> gives:*\n{}",
+                    offer.proposer,
+                    list(offered, offer.gp)
+                )),
+                mrkdwn(format!(
+                    "*<@{}> gives:*\n{}",
+                    offer.recipient,
+                    list(requested, 0)
+                )),
+            ]
+        }),
+        comment("Everything changes hands at once, or not at all."),
+    ]
+}
+
+/// Lets the recipient of an offer know that it's waiting for them.
+pub async fn send(db: &DynamoDbClient, offer: &TradeOffer) -> Result<(), String> {
+    let (offered, requested) = possessions(db, offer).await?;
+
+    let mut blocks = vec![json!({
+        "type": "section",
+        "text": mrkdwn(format!("<@{}> would like to trade with you!", offer.proposer)),
+        "accessory": {
+            "type": "button",
+            "style": "primary",
+            "text": plain_text("Review Offer"),
+            "value": offer.id.to_simple().to_string(),
+            "action_id": "trade_review",
+        }
+    })];
+    blocks.append(&mut summary_blocks(offer, &offered, &requested));
+
+    dm_blocks(offer.recipient.clone(), blocks).await
+}